@@ -0,0 +1,73 @@
+//! Full-deck image update benchmark
+//!
+//! Measures the CPU side of pushing a whole page of images: resize plus
+//! encode, one image at a time and as a parallel batch (what
+//! `StreamDeck::set_button_images` does before writing to the device).
+//! Writing over USB needs hardware and isn't covered here.
+//!
+//! Run with `cargo bench --bench image_batch`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use image::imageops::FilterType;
+use image::{DynamicImage, Rgb, RgbImage};
+use rayon::prelude::*;
+
+use streamdeck_lib::hid::constants::{MK2_MAX_IMAGE_BYTES, ORIGINAL_MAX_IMAGE_BYTES};
+use streamdeck_lib::hid::device::{encode_button_image, encode_button_images, EncodeSettings, ImageEncoding};
+
+/// Deck layouts to benchmark: (name, button count, button size in pixels)
+const LAYOUTS: &[(&str, usize, u32)] = &[("mk2", 15, 72), ("xl", 32, 96)];
+
+/// A busy source image (gradients and noise), so JPEG sizes are realistic
+fn source_image(seed: u32) -> DynamicImage {
+    let img = RgbImage::from_fn(256, 256, |x, y| {
+        let noise = (x.wrapping_mul(7919) ^ y.wrapping_mul(104_729) ^ seed.wrapping_mul(31)) % 64;
+        Rgb([(x + seed) as u8, (y * 2) as u8, ((x ^ y) + noise) as u8])
+    });
+    DynamicImage::ImageRgb8(img)
+}
+
+fn resize_all(sources: &[DynamicImage], size: u32) -> Vec<DynamicImage> {
+    sources.par_iter().map(|img| img.resize_to_fill(size, size, FilterType::Lanczos3)).collect()
+}
+
+fn bench_full_deck(c: &mut Criterion) {
+    let jpeg = EncodeSettings { encoding: ImageEncoding::Jpeg, rotate: true, max_bytes: MK2_MAX_IMAGE_BYTES };
+    let bmp = EncodeSettings { encoding: ImageEncoding::Bmp, rotate: true, max_bytes: ORIGINAL_MAX_IMAGE_BYTES };
+
+    let mut group = c.benchmark_group("full_deck_encode");
+    for &(layout, buttons, size) in LAYOUTS {
+        let sources: Vec<DynamicImage> = (0..buttons as u32).map(source_image).collect();
+        let resized = resize_all(&sources, size);
+        let batch: Vec<(&DynamicImage, f32)> = resized.iter().map(|img| (img, 0.0)).collect();
+        group.throughput(Throughput::Elements(buttons as u64));
+
+        group.bench_with_input(BenchmarkId::new("jpeg_sequential", layout), &resized, |b, resized| {
+            b.iter(|| {
+                for img in resized {
+                    encode_button_image(img, 0.0, jpeg).unwrap();
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("jpeg_parallel", layout), &batch, |b, batch| {
+            b.iter(|| encode_button_images(batch, jpeg))
+        });
+        // BMP is only used by the Original, which has 72px buttons
+        if size == 72 {
+            group.bench_with_input(BenchmarkId::new("bmp_parallel", layout), &batch, |b, batch| {
+                b.iter(|| encode_button_images(batch, bmp))
+            });
+        }
+        group.bench_with_input(BenchmarkId::new("resize_and_jpeg_parallel", layout), &sources, |b, sources| {
+            b.iter(|| {
+                let resized = resize_all(sources, size);
+                let batch: Vec<(&DynamicImage, f32)> = resized.iter().map(|img| (img, 0.0)).collect();
+                encode_button_images(&batch, jpeg)
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_full_deck);
+criterion_main!(benches);
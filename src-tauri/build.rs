@@ -1,3 +1,12 @@
 fn main() {
+    // Plugins are checked against the compiler the app was built with (see plugins.rs)
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let version = std::process::Command::new(rustc)
+        .arg("--version")
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_default();
+    println!("cargo:rustc-env=STREAMDECK_RUSTC_VERSION={}", version);
+
     tauri_build::build()
 }
@@ -0,0 +1,139 @@
+//! Busy Buttons
+//!
+//! Tracks which buttons still have an action running, including work a
+//! handler moved to a background thread (weather, scripts, webhooks), so
+//! pressing a slow button again doesn't start a second copy.
+//!
+//! What happens to a press on a busy button is up to the action's `on_busy`
+//! param: `"skip"` (the default) ignores it, `"queue"` runs it once the
+//! current run is done, `"parallel"` runs it straight away.
+//!
+//! Buttons are tracked by page and index, so a slow action doesn't hold up the
+//! button in the same spot on other pages.
+
+use std::thread;
+
+use tauri::{AppHandle, Manager};
+
+use crate::config::Action;
+use crate::polling::{Deferred, PRESSED_BUTTON_PARAM, PRESSED_PAGE_PARAM};
+use crate::AppState;
+
+/// A button with an action running
+pub struct Running {
+    /// Runs in progress (a handler's background work counts as its own run)
+    runs: usize,
+    /// Whether a press is waiting for all runs to be done (`on_busy: "queue"`)
+    queued: bool,
+}
+
+/// What to do with a press while the button's action is still running
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BusyPolicy {
+    Skip,
+    Queue,
+    Parallel,
+}
+
+impl BusyPolicy {
+    fn of(action: &Action) -> Self {
+        match action.get_string_param("on_busy") {
+            None | Some("skip") => BusyPolicy::Skip,
+            Some("queue") => BusyPolicy::Queue,
+            Some("parallel") => BusyPolicy::Parallel,
+            Some(other) => {
+                eprintln!("Unknown on_busy policy '{}', skipping presses while busy", other);
+                BusyPolicy::Skip
+            }
+        }
+    }
+}
+
+/// Marks a button busy until dropped
+pub struct BusyGuard {
+    app_handle: AppHandle,
+    /// (page, button)
+    key: (usize, usize),
+}
+
+impl Drop for BusyGuard {
+    fn drop(&mut self) {
+        let state = self.app_handle.state::<AppState>();
+        let mut busy = state.busy_buttons.lock().unwrap();
+        let Some(running) = busy.get_mut(&self.key) else {
+            return;
+        };
+
+        running.runs = running.runs.saturating_sub(1);
+        if running.runs > 0 {
+            return;
+        }
+        let queued = busy.remove(&self.key).is_some_and(|running| running.queued);
+        drop(busy);
+
+        // The queued press goes back through the polling loop, so it's handled
+        // exactly like a fresh one (lock, cooldown, toggles, holds, errors)
+        if queued {
+            let (page, button) = self.key;
+            state.deferred.lock().unwrap().push(Deferred::Press { page, button });
+        }
+    }
+}
+
+/// Start running a pressed button's action, unless it's still busy.
+///
+/// Returns a guard that keeps the button busy until dropped, or `None` if the
+/// press was skipped or queued.
+pub fn begin(app_handle: &AppHandle, page: usize, button: usize, action: &Action) -> Option<BusyGuard> {
+    let state = app_handle.state::<AppState>();
+    let mut busy = state.busy_buttons.lock().unwrap();
+
+    match busy.get_mut(&(page, button)) {
+        None => {
+            busy.insert((page, button), Running { runs: 1, queued: false });
+        }
+        Some(running) => match BusyPolicy::of(action) {
+            BusyPolicy::Skip => {
+                println!("Button {} is still running {} - ignoring press", button, action.action_type);
+                return None;
+            }
+            BusyPolicy::Queue => {
+                println!("Button {} is still running {} - queueing press", button, action.action_type);
+                running.queued = true;
+                return None;
+            }
+            BusyPolicy::Parallel => running.runs += 1,
+        },
+    }
+
+    Some(BusyGuard {
+        app_handle: app_handle.clone(),
+        key: (page, button),
+    })
+}
+
+/// Run a handler's background work on its own thread, keeping the button that
+/// was pressed (if any) busy until it's done.
+pub fn spawn_for_button(action: &Action, app_handle: &AppHandle, task: impl FnOnce() + Send + 'static) {
+    let pressed = action.get_int_param(PRESSED_PAGE_PARAM).zip(action.get_int_param(PRESSED_BUTTON_PARAM));
+    let guard = pressed.map(|(page, button)| {
+        let key = (page as usize, button as usize);
+        let state = app_handle.state::<AppState>();
+        state
+            .busy_buttons
+            .lock()
+            .unwrap()
+            .entry(key)
+            .or_insert(Running { runs: 0, queued: false })
+            .runs += 1;
+        BusyGuard {
+            app_handle: app_handle.clone(),
+            key,
+        }
+    });
+
+    thread::spawn(move || {
+        let _busy = guard;
+        task();
+    });
+}
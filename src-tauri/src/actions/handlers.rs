@@ -3,10 +3,25 @@
 //! Each function handles a specific action type. They all have the same signature:
 //! fn(&Action, &AppHandle) -> Result<(), String>
 
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use serde::Deserialize;
+
 use tauri::AppHandle;
+use crate::actions::ActionRegistry;
 use crate::config::Action;
+use crate::hid::constants::{BUTTON_COUNT, MAX_BRIGHTNESS};
+use crate::polling::{ERROR_FLASH_DURATION, MIN_POLL_INTERVAL_MS, PRESSED_BUTTON_PARAM};
+use crate::scheduler::REFRESH_PARAM;
+use crate::scripting::DEFAULT_SCRIPT_TIMEOUT_MS;
 use crate::AppState;
 use tauri::Manager;
+use tauri_plugin_opener::OpenerExt;
 
 // =============================================================================
 // Audio Actions
@@ -25,28 +40,64 @@ pub fn audio_volume_mute(_action: &Action, _app: &AppHandle) -> Result<(), Strin
 }
 
 pub fn audio_play_sound(action: &Action, _app: &AppHandle) -> Result<(), String> {
-    let path = action.get_string_param("path")
+    let path = action.get_path_param("path")
         .ok_or("Missing 'path' parameter for play_sound")?;
 
     // TODO: Implement sound playback
-    eprintln!("Play sound not yet implemented: {}", path);
+    eprintln!("Play sound not yet implemented: {}", path.display());
     Ok(())
 }
 
+pub fn audio_set_output_device(action: &Action, _app: &AppHandle) -> Result<(), String> {
+    let device_name = action.get_string_param("device_name")
+        .ok_or("Missing 'device_name' parameter for set_output_device")?;
+
+    crate::audio::output::set_output_device(device_name)
+}
+
+pub fn audio_cycle_output_device(_action: &Action, _app: &AppHandle) -> Result<(), String> {
+    crate::audio::output::cycle_output_device()
+}
+
+pub fn audio_toggle_app_mute(action: &Action, _app: &AppHandle) -> Result<bool, String> {
+    let process_name = action.get_string_param("process_name")
+        .ok_or("Missing 'process_name' parameter for toggle_app_mute")?;
+
+    crate::audio::session::toggle_app_mute(process_name)
+}
+
+pub fn audio_mic_meter(action: &Action, app: &AppHandle) -> Result<bool, String> {
+    let button_index = action.get_int_param(PRESSED_BUTTON_PARAM)
+        .ok_or("mic_meter only works on a button")? as usize;
+    crate::audio::meter::toggle_meter(app, button_index)
+}
+
 // =============================================================================
 // Navigation Actions
 // =============================================================================
 
+/// Redraw the deck after the current page changed and tell the frontend.
+/// Must be called with the config lock released.
+fn sync_page(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    crate::images::sync_images_to_device(&state, app);
+    crate::commands::config::emit_page_changed(&state, app);
+}
+
 pub fn navigation_next_page(_action: &Action, app: &AppHandle) -> Result<(), String> {
     let state = app.state::<AppState>();
     let mut config = state.config.lock().unwrap();
 
     let num_pages = config.pages.len();
     if num_pages > 0 {
-        config.current_page = (config.current_page + 1) % num_pages;
+        let next = (config.current_page + 1) % num_pages;
+        config.go_to_page(next);
         println!("Switched to page {}: {}", config.current_page, config.pages[config.current_page].name);
     }
 
+    drop(config);
+    sync_page(app);
+
     Ok(())
 }
 
@@ -56,14 +107,18 @@ pub fn navigation_previous_page(_action: &Action, app: &AppHandle) -> Result<(),
 
     let num_pages = config.pages.len();
     if num_pages > 0 {
-        config.current_page = if config.current_page == 0 {
+        let previous = if config.current_page == 0 {
             num_pages - 1
         } else {
             config.current_page - 1
         };
+        config.go_to_page(previous);
         println!("Switched to page {}: {}", config.current_page, config.pages[config.current_page].name);
     }
 
+    drop(config);
+    sync_page(app);
+
     Ok(())
 }
 
@@ -75,12 +130,37 @@ pub fn navigation_go_to_page(action: &Action, app: &AppHandle) -> Result<(), Str
     let mut config = state.config.lock().unwrap();
 
     if page_index < config.pages.len() {
-        config.current_page = page_index;
+        config.go_to_page(page_index);
         println!("Jumped to page {}: {}", config.current_page, config.pages[config.current_page].name);
     } else {
         return Err(format!("Page index {} out of range", page_index));
     }
 
+    drop(config);
+    sync_page(app);
+
+    Ok(())
+}
+
+pub fn navigation_go_to_page_named(action: &Action, app: &AppHandle) -> Result<(), String> {
+    let name = action.get_string_param("name")
+        .ok_or("Missing 'name' parameter for go_to_page_named")?;
+
+    let state = app.state::<AppState>();
+    let mut config = state.config.lock().unwrap();
+
+    // Resolve the name at press time so adding/removing pages doesn't break the button
+    match config.pages.iter().position(|page| page.name == name) {
+        Some(page_index) => {
+            config.go_to_page(page_index);
+            println!("Jumped to page {}: {}", config.current_page, name);
+        }
+        None => return Err(format!("No page named '{}'", name)),
+    }
+
+    drop(config);
+    sync_page(app);
+
     Ok(())
 }
 
@@ -88,20 +168,21 @@ pub fn navigation_go_to_page(action: &Action, app: &AppHandle) -> Result<(), Str
 // System Actions
 // =============================================================================
 
-pub fn system_launch_app(action: &Action, _app: &AppHandle) -> Result<(), String> {
-    let path = action.get_string_param("path")
-        .ok_or("Missing 'path' parameter for launch_app")?;
+#[derive(Deserialize)]
+struct LaunchAppParams {
+    path: String,
+    #[serde(default)]
+    args: Vec<String>,
+}
 
-    // Get optional arguments
-    let args: Vec<String> = action.params.get("args")
-        .and_then(|v| v.as_array())
-        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
-        .unwrap_or_default();
+pub fn system_launch_app(action: &Action, _app: &AppHandle) -> Result<(), String> {
+    let params: LaunchAppParams = action.params_as()?;
+    let path = crate::paths::expand_path(&params.path);
 
-    println!("Launching: {} {:?}", path, args);
+    println!("Launching: {} {:?}", path.display(), params.args);
 
-    std::process::Command::new(path)
-        .args(&args)
+    std::process::Command::new(&path)
+        .args(&params.args)
         .spawn()
         .map_err(|e| format!("Failed to launch app: {}", e))?;
 
@@ -123,53 +204,623 @@ pub fn system_open_url(action: &Action, _app: &AppHandle) -> Result<(), String>
     Ok(())
 }
 
+pub fn system_open_path(action: &Action, app: &AppHandle) -> Result<(), String> {
+    let path = action.get_path_param("path")
+        .ok_or("Missing 'path' parameter for open_path")?;
+
+    if !path.exists() {
+        return Err(format!("Path does not exist: {}", path.display()));
+    }
+
+    let opener = app.opener();
+    if action.get_bool_param("reveal").unwrap_or(false) {
+        // Show the item selected in its parent folder
+        println!("Revealing: {}", path.display());
+        opener
+            .reveal_item_in_dir(&path)
+            .map_err(|e| format!("Failed to reveal {}: {}", path.display(), e))
+    } else {
+        // Files open in their default app, folders in the file manager
+        println!("Opening: {}", path.display());
+        opener
+            .open_path(path.to_string_lossy(), None::<&str>)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))
+    }
+}
+
 pub fn system_hotkey(action: &Action, _app: &AppHandle) -> Result<(), String> {
     let keys = action.get_string_param("keys")
         .ok_or("Missing 'keys' parameter for hotkey")?;
 
-    // TODO: Implement hotkey sending
-    eprintln!("Hotkey not yet implemented: {}", keys);
+    crate::keyboard::tap_keys(keys, key_mode(action))
+}
+
+pub fn system_set_polling_rate(action: &Action, app: &AppHandle) -> Result<(), String> {
+    let interval_ms = action.get_int_param("interval_ms")
+        .ok_or("Missing 'interval_ms' parameter for set_polling_rate")?;
+
+    // Clamp so a typo can't turn the polling loop into a busy spin
+    let interval_ms = (interval_ms.max(0) as u64).max(MIN_POLL_INTERVAL_MS);
+
+    let state = app.state::<AppState>();
+    state.polling_interval_ms.store(interval_ms, Ordering::Relaxed);
+    println!("Button read timeout set to {}ms", interval_ms);
+
+    Ok(())
+}
+
+/// Brightness presets `system.cycle_brightness` steps through by default
+const DEFAULT_BRIGHTNESS_LEVELS: &[u8] = &[25, 50, 75, 100];
+
+#[derive(Deserialize)]
+struct CycleBrightnessParams {
+    #[serde(default)]
+    levels: Option<Vec<u8>>,
+}
+
+pub fn system_cycle_brightness(action: &Action, app: &AppHandle) -> Result<(), String> {
+    let params: CycleBrightnessParams = action.params_as()?;
+    let levels = params.levels.as_deref().unwrap_or(DEFAULT_BRIGHTNESS_LEVELS);
+    if levels.is_empty() {
+        return Err("'levels' parameter for cycle_brightness is empty".to_string());
+    }
+    // The deck would clamp these, and the lookup below would never find them again
+    if let Some(level) = levels.iter().find(|&&level| level > MAX_BRIGHTNESS) {
+        return Err(format!("Brightness level {} for cycle_brightness is above {}", level, MAX_BRIGHTNESS));
+    }
+
+    // Step on from the brightness in use, wherever it was set from; a value
+    // that isn't one of the levels moves to the next level above it
+    let state = app.state::<AppState>();
+    let current = state.brightness.load(Ordering::Relaxed);
+    let next = match levels.iter().position(|&level| level == current) {
+        Some(index) => levels[(index + 1) % levels.len()],
+        None => levels.iter().copied().find(|&level| level > current).unwrap_or(levels[0]),
+    };
+
+    println!("Brightness: {} -> {}", current, next);
+    crate::commands::streamdeck::apply_brightness(next, &state, app)
+}
+
+pub fn system_key_hold_press(action: &Action, _app: &AppHandle) -> Result<(), String> {
+    let keys = action.get_string_param("keys")
+        .ok_or("Missing 'keys' parameter for key_hold")?;
+
+    crate::keyboard::press_keys(keys, key_mode(action))
+}
+
+pub fn system_key_hold_release(action: &Action, _app: &AppHandle) -> Result<(), String> {
+    let keys = action.get_string_param("keys")
+        .ok_or("Missing 'keys' parameter for key_hold")?;
+
+    crate::keyboard::release_keys(keys, key_mode(action))
+}
+
+/// Whether a key action types characters (`by_char`) or presses physical keys
+fn key_mode(action: &Action) -> crate::keyboard::KeyMode {
+    crate::keyboard::KeyMode::from_by_char(action.get_bool_param("by_char").unwrap_or(false))
+}
+
+#[derive(Deserialize)]
+struct RegionCaptureParams {
+    x: i32,
+    y: i32,
+    w: u32,
+    h: u32,
+    #[serde(default)]
+    to_clipboard_text: bool,
+}
+
+pub fn system_region_capture(action: &Action, _app: &AppHandle) -> Result<(), String> {
+    let params: RegionCaptureParams = action.params_as()?;
+
+    let image = crate::capture::capture_region(params.x, params.y, params.w, params.h)?;
+
+    if params.to_clipboard_text {
+        let text = crate::capture::extract_text(&image)?;
+        println!("Captured {} characters of text", text.len());
+        crate::capture::copy_text_to_clipboard(&text)
+    } else {
+        crate::capture::copy_image_to_clipboard(&image)
+    }
+}
+
+#[derive(Deserialize)]
+struct WindowSwitcherParams {
+    #[serde(default)]
+    page_name: Option<String>,
+}
+
+pub fn system_window_switcher(action: &Action, app: &AppHandle) -> Result<(), String> {
+    let params: WindowSwitcherParams = action.params_as()?;
+    // Its buttons could only ever fail, so don't show it at all
+    if !cfg!(windows) {
+        return Err("The window switcher is only supported on Windows".to_string());
+    }
+    let name = params.page_name.as_deref().unwrap_or(crate::switcher::DEFAULT_SWITCHER_PAGE_NAME);
+
+    let windows = crate::switcher::list_windows()?;
+    if windows.is_empty() {
+        return Err("No windows to switch to".to_string());
+    }
+
+    // The switcher is shown over the current page, so that's where Back goes,
+    // also when it's refreshed from the switcher itself
+    let state = app.state::<AppState>();
+    let return_page = state.config.lock().unwrap().current_page;
+    let button_count = state
+        .streamdeck
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map_or(BUTTON_COUNT, |streamdeck| streamdeck.model().button_count);
+
+    // Thumbnails take a moment, so build the page before taking the config lock
+    let page = crate::switcher::build_page(&windows, name, return_page, button_count, app)?;
+
+    state.config.lock().unwrap().transient_page = Some(page);
+    println!("Window switcher: {} windows", windows.len());
+
+    sync_page(app);
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct FocusWindowParams {
+    id: u32,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    return_page: Option<usize>,
+}
+
+pub fn system_focus_window(action: &Action, app: &AppHandle) -> Result<(), String> {
+    let params: FocusWindowParams = action.params_as()?;
+    println!("Focusing window: {}", params.title.as_deref().unwrap_or("(untitled)"));
+    crate::switcher::focus_window(params.id)?;
+
+    if let Some(page) = params.return_page {
+        let state = app.state::<AppState>();
+        let mut config = state.config.lock().unwrap();
+        if page < config.pages.len() {
+            config.go_to_page(page);
+            drop(config);
+            sync_page(app);
+        }
+    }
+    Ok(())
+}
+
+/// Interpreter (program + leading args) used to run a script, picked by file extension.
+/// Returns `None` for unknown extensions, which are run directly.
+fn script_interpreter(script_path: &Path) -> Option<(&'static str, &'static [&'static str])> {
+    let extension = script_path.extension()?.to_str()?.to_ascii_lowercase();
+    match extension.as_str() {
+        "sh" => Some(("sh", &[])),
+        "ps1" => Some(("powershell", &["-ExecutionPolicy", "Bypass", "-File"])),
+        "py" => Some(("python", &[])),
+        "applescript" | "scpt" => Some(("osascript", &[])),
+        "bat" | "cmd" => Some(("cmd", &["/C"])),
+        _ => None,
+    }
+}
+
+/// Check that a script without a known interpreter can be executed directly.
+#[cfg(unix)]
+fn check_executable(path: &Path) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = std::fs::metadata(path)
+        .map_err(|e| format!("Failed to read script metadata: {}", e))?
+        .permissions()
+        .mode();
+    if mode & 0o111 == 0 {
+        return Err(format!("Script is not executable: {}", path.display()));
+    }
+    Ok(())
+}
+
+/// Check that a script without a known interpreter can be executed directly.
+#[cfg(not(unix))]
+fn check_executable(_path: &Path) -> Result<(), String> {
+    Ok(())
+}
+
+pub fn system_run_script(action: &Action, app: &AppHandle) -> Result<(), String> {
+    let script_path = action.get_path_param("script_path")
+        .ok_or("Missing 'script_path' parameter for run_script")?;
+
+    if !script_path.is_file() {
+        return Err(format!("Script not found: {}", script_path.display()));
+    }
+
+    let mut command = match script_interpreter(&script_path) {
+        Some((program, args)) => {
+            let mut command = std::process::Command::new(program);
+            command.args(args).arg(&script_path);
+            command
+        }
+        None => {
+            check_executable(&script_path)?;
+            std::process::Command::new(&script_path)
+        }
+    };
+
+    // Optionally show the script's output on a button
+    let output_button = action.get_int_param("output_button").map(|i| i as usize);
+    let app_handle = app.clone();
+
+    println!("Running script: {}", script_path.display());
+
+    // Spawn thread so long scripts don't block the polling loop
+    super::busy::spawn_for_button(action, app, move || {
+        match command.output() {
+            Ok(output) => {
+                if !output.status.success() {
+                    eprintln!("Script {} exited with {}", script_path.display(), output.status);
+                }
+
+                if let Some(button_index) = output_button {
+                    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                    update_button_label(&app_handle, button_index, stdout);
+                }
+            }
+            Err(e) => eprintln!("Failed to run script {}: {}", script_path.display(), e),
+        }
+    });
+
     Ok(())
 }
 
+// =============================================================================
+// Script Actions
+// =============================================================================
+
+pub fn script_run(action: &Action, app: &AppHandle) -> Result<(), String> {
+    let source = match action.get_string_param("script") {
+        Some(script) => script.to_string(),
+        None => {
+            let path = action.get_path_param("path")
+                .ok_or("Missing 'script' or 'path' parameter for script.run")?;
+            std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read script {}: {}", path.display(), e))?
+        }
+    };
+
+    let timeout_ms = action.get_int_param("timeout_ms")
+        .map(|ms| ms.max(0) as u64)
+        .unwrap_or(DEFAULT_SCRIPT_TIMEOUT_MS);
+
+    crate::scripting::run_script(&source, Duration::from_millis(timeout_ms), app)
+}
+
 // =============================================================================
 // Weather Actions
 // =============================================================================
-pub fn display_weather(_action: &Action, app: &AppHandle) -> Result<(), String> {
-    // TODO: Get button index from action params instead of hardcoding
-    const WEATHER_BUTTON_INDEX: usize = 3;
+pub fn display_weather(action: &Action, app: &AppHandle) -> Result<(), String> {
+    let emoji = action.get_bool_param("emoji");
+
+    // Scheduled refreshes already run off the polling thread, so fetch inline
+    if action.get_bool_param(REFRESH_PARAM).unwrap_or(false) {
+        show_weather(app, emoji);
+        return Ok(());
+    }
 
     // Clone app handle for the spawned thread
     let app_handle = app.clone();
 
     // Spawn thread so we don't block the polling loop
+    super::busy::spawn_for_button(action, app, move || show_weather(&app_handle, emoji));
+
+    Ok(())
+}
+
+/// Fetch the weather and show it on the weather button
+fn show_weather(app_handle: &AppHandle, emoji: Option<bool>) {
+    // TODO: Get button index from action params instead of hardcoding
+    const WEATHER_BUTTON_INDEX: usize = 3;
+
+    match crate::weather::get_weather(app_handle, emoji) {
+        Some(weather) => {
+            println!("Weather: {}", weather);
+
+            // Update the button label with weather data
+            update_button_label(app_handle, WEATHER_BUTTON_INDEX, weather);
+        }
+        None => eprintln!("Failed to fetch weather"),
+    }
+}
+
+// =============================================================================
+// Meta Actions
+// =============================================================================
+
+pub fn meta_toggle_simulate(_action: &Action, app: &AppHandle) -> Result<bool, String> {
+    let state = app.state::<AppState>();
+    let simulate = !state.simulate_actions.fetch_xor(true, Ordering::Relaxed);
+    println!("Simulate mode {}", if simulate { "enabled" } else { "disabled" });
+    Ok(simulate)
+}
+
+pub fn meta_toggle_lock(_action: &Action, app: &AppHandle) -> Result<bool, String> {
+    let state = app.state::<AppState>();
+    let locked = !state.locked.fetch_xor(true, Ordering::Relaxed);
+    println!("Deck {}", if locked { "locked" } else { "unlocked" });
+    Ok(locked)
+}
+
+/// Last sub-action picked by each `meta.random` button, keyed by its `actions` param
+static LAST_RANDOM_CHOICE: LazyLock<Mutex<HashMap<String, usize>>> = LazyLock::new(Default::default);
+
+pub fn meta_random(action: &Action, app: &AppHandle) -> Result<Option<bool>, String> {
+    let choices = action.params.get("actions")
+        .ok_or("Missing 'actions' parameter for random")?;
+    let actions: Vec<Action> = serde_json::from_value(choices.clone())
+        .map_err(|e| format!("Invalid 'actions' parameter for random: {}", e))?;
+    if actions.is_empty() {
+        return Err("'actions' parameter for random is empty".to_string());
+    }
+    let registry = app.state::<ActionRegistry>();
+    for choice in &actions {
+        reject_hold_action(&registry, choice, "random")?;
+    }
+
+    let no_repeat = action.get_bool_param("no_repeat").unwrap_or(false);
+    let key = choices.to_string();
+    let mut last_choices = LAST_RANDOM_CHOICE.lock().unwrap();
+    let last = last_choices.get(&key).copied().filter(|_| no_repeat && actions.len() > 1);
+
+    // Skip over the last pick by drawing from one fewer slot
+    let mut rng = rand::thread_rng();
+    let index = match last {
+        Some(last) => {
+            let index = rng.gen_range(0..actions.len() - 1);
+            if index >= last { index + 1 } else { index }
+        }
+        None => rng.gen_range(0..actions.len()),
+    };
+    last_choices.insert(key, index);
+    drop(last_choices);
+
+    let chosen = &actions[index];
+    println!("Random pick: {}", chosen.action_type);
+    registry.execute(chosen, app)
+}
+
+/// Refuse a hold-type action (like `system.key_hold`) wrapped in `wrapper`:
+/// only its press would run, since nothing forwards the button's release
+fn reject_hold_action(registry: &ActionRegistry, action: &Action, wrapper: &str) -> Result<(), String> {
+    if registry.has_release_handler(&action.action_type) {
+        return Err(format!("{} can't run '{}', which needs its button held down", wrapper, action.action_type));
+    }
+    Ok(())
+}
+
+/// How long a `meta.confirm` button waits for its second press by default
+const DEFAULT_CONFIRM_TIMEOUT_MS: u64 = 3000;
+
+pub fn meta_confirm(action: &Action, app: &AppHandle) -> Result<(), String> {
+    let confirmed = action.params.get("action")
+        .ok_or("Missing 'action' parameter for confirm")?;
+    let confirmed: Action = serde_json::from_value(confirmed.clone())
+        .map_err(|e| format!("Invalid 'action' parameter for confirm: {}", e))?;
+    reject_hold_action(&app.state::<ActionRegistry>(), &confirmed, "confirm")?;
+    let button_index = action.get_int_param(PRESSED_BUTTON_PARAM)
+        .ok_or("confirm only works on a button")? as usize;
+    let timeout = Duration::from_millis(
+        action.get_int_param("timeout_ms").map(|ms| ms.max(0) as u64).unwrap_or(DEFAULT_CONFIRM_TIMEOUT_MS),
+    );
+
+    let state = app.state::<AppState>();
+    let key = (state.config.lock().unwrap().shown_page(), button_index);
+    let now = Instant::now();
+
+    // Second press inside the window: disarm and run the wrapped action
+    let armed_at = state.confirm_armed.lock().unwrap().remove(&key);
+    if armed_at.is_some_and(|armed_at| now.duration_since(armed_at) < timeout) {
+        crate::images::refresh_button(&state, app, button_index);
+        println!("Confirmed: {}", confirmed.action_type);
+        return app.state::<ActionRegistry>().execute(&confirmed, app).map(|_| ());
+    }
+
+    // First press: arm, show the prompt, and disarm again if nothing follows
+    state.confirm_armed.lock().unwrap().insert(key, now);
+    let prompt = action.get_string_param("prompt").unwrap_or("Confirm?");
+    crate::images::show_button_text(&state, app, button_index, prompt);
+    println!("Button {} armed - press again within {} ms to run {}", button_index, timeout.as_millis(), confirmed.action_type);
+
+    let app_handle = app.clone();
     std::thread::spawn(move || {
-        match crate::weather::get_weather() {
-            Some(weather) => {
-                println!("Weather: {}", weather);
-
-                // Update the button label with weather data
-                let state = app_handle.state::<AppState>();
-                {
-                    let mut config = state.config.lock().unwrap();
-                    let current_page = config.current_page;
-                    if let Some(page) = config.pages.get_mut(current_page) {
-                        if let Some(button) = page.buttons.get_mut(&WEATHER_BUTTON_INDEX) {
-                            button.label = Some(weather);
-                        }
+        std::thread::sleep(timeout);
+        let state = app_handle.state::<AppState>();
+
+        // Only disarm if this is still the same arming (not confirmed or re-armed since)
+        let mut armed = state.confirm_armed.lock().unwrap();
+        if armed.get(&key) != Some(&now) {
+            return;
+        }
+        armed.remove(&key);
+        drop(armed);
+
+        if state.config.lock().unwrap().shown_page() == key.0 {
+            crate::images::refresh_button(&state, &app_handle, button_index);
+        }
+    });
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct ConditionalParams {
+    /// Program whose exit code decides the branch
+    #[serde(default)]
+    command: Option<String>,
+    #[serde(default)]
+    args: Vec<String>,
+    /// Or an action whose success decides it
+    #[serde(default, rename = "if")]
+    condition: Option<Action>,
+    #[serde(default)]
+    then: Option<Action>,
+    #[serde(default, rename = "else")]
+    otherwise: Option<Action>,
+}
+
+pub fn meta_conditional(action: &Action, app: &AppHandle) -> Result<(), String> {
+    let params: ConditionalParams = action.params_as()?;
+    if params.command.is_some() == params.condition.is_some() {
+        return Err("conditional needs either a 'command' or an 'if' action".to_string());
+    }
+    if params.then.is_none() && params.otherwise.is_none() {
+        return Err("conditional needs a 'then' or 'else' action".to_string());
+    }
+    let registry = app.state::<ActionRegistry>();
+    for wrapped in [&params.condition, &params.then, &params.otherwise].into_iter().flatten() {
+        reject_hold_action(&registry, wrapped, "conditional")?;
+    }
+    // Their handlers succeed as soon as the work has started, so the branch
+    // would be chosen before the outcome is known
+    if let Some(condition) = &params.condition {
+        if registry.runs_in_background(&condition.action_type) {
+            return Err(format!(
+                "conditional can't wait for '{}', which finishes in the background; use 'command' instead",
+                condition.action_type
+            ));
+        }
+    }
+
+    let state = app.state::<AppState>();
+    if params.command.is_some() && state.simulate_actions.load(Ordering::Relaxed) {
+        println!("[simulate] Would run: {} {:?}", params.command.unwrap_or_default(), params.args);
+        return Ok(());
+    }
+
+    // The condition may take a while (a test suite, say), so everything runs
+    // off the polling thread, keeping the button busy until the branch is done
+    let app_handle = app.clone();
+    super::busy::spawn_for_button(action, app, move || {
+        let registry = app_handle.state::<ActionRegistry>();
+
+        let succeeded = match (&params.command, &params.condition) {
+            (Some(command), _) => {
+                let path = crate::paths::expand_path(command);
+                println!("Conditional: running {} {:?}", path.display(), params.args);
+                match std::process::Command::new(&path).args(&params.args).status() {
+                    Ok(status) => {
+                        println!("Conditional: {} exited with {}", path.display(), status);
+                        status.success()
+                    }
+                    Err(e) => {
+                        eprintln!("Conditional: failed to run {}: {}", path.display(), e);
+                        false
                     }
                 }
-
-                // Re-sync images to update the display
-                crate::images::sync_images_to_device(&app_handle.state(), &app_handle);
             }
-            None => eprintln!("Failed to fetch weather"),
+            (None, Some(condition)) => match registry.execute(condition, &app_handle) {
+                Ok(_) => true,
+                Err(e) => {
+                    println!("Conditional: {} failed: {}", condition.action_type, e);
+                    false
+                }
+            },
+            (None, None) => unreachable!("checked above"),
+        };
+
+        let branch = if succeeded { params.then } else { params.otherwise };
+        let Some(branch) = branch else {
+            return;
+        };
+        println!("Conditional: {} - running {}", if succeeded { "then" } else { "else" }, branch.action_type);
+        if let Err(e) = registry.execute(&branch, &app_handle) {
+            eprintln!("Action error: {}", e);
+        }
+    });
+
+    Ok(())
+}
+
+// =============================================================================
+// Web Actions
+// =============================================================================
+
+pub fn web_post_message(action: &Action, app: &AppHandle) -> Result<(), String> {
+    let webhook_url = action.get_string_param("webhook_url")
+        .ok_or("Missing 'webhook_url' parameter for post_message")?
+        .to_string();
+    let message = action.get_string_param("message")
+        .ok_or("Missing 'message' parameter for post_message")?
+        .to_string();
+    let platform = action.get_string_param("platform")
+        .map(crate::webhook::WebhookPlatform::from_name)
+        .transpose()?;
+    let button_index = action.get_int_param(PRESSED_BUTTON_PARAM).map(|i| i as usize);
+
+    // Posting can take seconds, so it runs off the polling thread and reports
+    // back by flashing the button instead of through the return value
+    let app_handle = app.clone();
+    super::busy::spawn_for_button(action, app, move || {
+        let result = crate::webhook::post_message(&webhook_url, &message, platform);
+        let state = app_handle.state::<AppState>();
+
+        match result {
+            Ok(()) => println!("Posted webhook message: {}", message),
+            Err(ref e) => eprintln!("{}", e),
+        }
+
+        let Some(button_index) = button_index else {
+            return;
+        };
+        let page = state.config.lock().unwrap().shown_page();
+        if result.is_ok() {
+            crate::images::flash_success(&state, button_index);
+        } else {
+            crate::images::flash_error(&state, button_index);
+        }
+
+        std::thread::sleep(ERROR_FLASH_DURATION);
+        if state.config.lock().unwrap().shown_page() == page {
+            crate::images::refresh_button(&state, &app_handle, button_index);
         }
     });
 
     Ok(())
 }
 
+pub fn meta_redraw(_action: &Action, app: &AppHandle) -> Result<(), String> {
+    crate::images::redraw_deck(&app.state::<AppState>(), app);
+    Ok(())
+}
+
+// =============================================================================
+// App Actions
+// =============================================================================
+
+pub fn app_toggle_window(_action: &Action, app: &AppHandle) -> Result<(), String> {
+    crate::shortcut::toggle_main_window(app)
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Set the label of a button on the current page and redraw the deck.
+/// Used by actions that display their results (weather, script output).
+fn update_button_label(app_handle: &AppHandle, button_index: usize, label: String) {
+    let state = app_handle.state::<AppState>();
+    {
+        let mut config = state.config.lock().unwrap();
+        let shown = config.shown_page();
+        if let Some(page) = config.page_mut(shown) {
+            if let Some(button) = page.buttons.get_mut(&button_index) {
+                button.label = Some(label);
+            }
+        }
+    }
+
+    // Re-sync images to update the display
+    crate::images::sync_images_to_device(&state, app_handle);
+}
+
 // =============================================================================
 // Special Actions
 // =============================================================================
@@ -3,19 +3,48 @@
 //! Provides a registry pattern for mapping action type strings to handler functions.
 //! This allows adding new actions without modifying enums or match statements.
 
+pub mod busy;
 mod handlers;
 
-use std::collections::HashMap;
-use tauri::AppHandle;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use tauri::{AppHandle, Emitter, Manager};
 use crate::config::Action;
+use crate::AppState;
 
 /// Function signature for action handlers
 /// Takes the action (with params) and app handle, returns Result
 pub type ActionHandler = fn(&Action, &AppHandle) -> Result<(), String>;
 
+/// Function signature for handlers that report an on/off state after running
+/// (e.g. locked/unlocked), so toggle buttons can show the matching image
+pub type StatefulActionHandler = fn(&Action, &AppHandle) -> Result<bool, String>;
+
+/// Function signature for handlers that run another action and pass on the
+/// state it reported, if any (e.g. `meta.random`)
+pub type ForwardingActionHandler = fn(&Action, &AppHandle) -> Result<Option<bool>, String>;
+
+/// A registered handler, with or without a state report
+#[derive(Clone, Copy)]
+enum Handler {
+    Plain(ActionHandler),
+    Stateful(StatefulActionHandler),
+    Forwarding(ForwardingActionHandler),
+}
+
 /// Registry that maps action type strings to their handler functions
+#[derive(Clone)]
 pub struct ActionRegistry {
-    handlers: HashMap<String, ActionHandler>,
+    handlers: HashMap<String, Handler>,
+    /// Handlers run when a button with a hold-type action is released
+    release_handlers: HashMap<String, ActionHandler>,
+    /// Action types that affect things outside the app (skipped in simulate mode)
+    side_effecting: HashSet<String>,
+    /// Default icon files for action types added at runtime (e.g. by plugins)
+    icons: HashMap<String, PathBuf>,
+    /// Action types whose handler returns before the work is done
+    background: HashSet<String>,
 }
 
 impl ActionRegistry {
@@ -23,6 +52,10 @@ impl ActionRegistry {
     pub fn new() -> Self {
         let mut registry = Self {
             handlers: HashMap::new(),
+            release_handlers: HashMap::new(),
+            side_effecting: HashSet::new(),
+            icons: HashMap::new(),
+            background: HashSet::new(),
         };
         registry.register_defaults();
         registry
@@ -30,17 +63,113 @@ impl ActionRegistry {
 
     /// Register a handler for an action type
     pub fn register(&mut self, action_type: &str, handler: ActionHandler) {
-        self.handlers.insert(action_type.to_string(), handler);
+        self.handlers.insert(action_type.to_string(), Handler::Plain(handler));
     }
 
-    /// Execute an action by looking up its handler
-    pub fn execute(&self, action: &Action, app_handle: &AppHandle) -> Result<(), String> {
-        match self.handlers.get(&action.action_type) {
+    /// Register a handler that reports the on/off state it leaves things in
+    pub fn register_stateful(&mut self, action_type: &str, handler: StatefulActionHandler) {
+        self.handlers.insert(action_type.to_string(), Handler::Stateful(handler));
+    }
+
+    /// Register a handler that runs another action and reports whatever state it did
+    pub fn register_forwarding(&mut self, action_type: &str, handler: ForwardingActionHandler) {
+        self.handlers.insert(action_type.to_string(), Handler::Forwarding(handler));
+    }
+
+    /// Set the default icon (a PNG file) for an action type.
+    /// Relative paths registered by plugins are resolved against the plugins folder.
+    pub fn register_icon(&mut self, action_type: &str, path: impl Into<PathBuf>) {
+        self.icons.insert(action_type.to_string(), path.into());
+    }
+
+    /// Get the registered default icon for an action type, if any
+    pub fn icon_for(&self, action_type: &str) -> Option<&Path> {
+        self.icons.get(action_type).map(PathBuf::as_path)
+    }
+
+    /// Make relative icon paths absolute by joining them onto `base`
+    pub(crate) fn resolve_icon_paths(&mut self, base: &Path) {
+        for path in self.icons.values_mut() {
+            if path.is_relative() {
+                *path = base.join(&*path);
+            }
+        }
+    }
+
+    /// Register a handler for an action type that has side effects outside the app
+    /// (launching programs, sending keys, changing volume...).
+    /// These are logged instead of run while simulate mode is on.
+    pub fn register_side_effecting(&mut self, action_type: &str, handler: ActionHandler) {
+        self.register(action_type, handler);
+        self.side_effecting.insert(action_type.to_string());
+    }
+
+    /// Register a stateful handler that has side effects outside the app
+    pub fn register_stateful_side_effecting(&mut self, action_type: &str, handler: StatefulActionHandler) {
+        self.register_stateful(action_type, handler);
+        self.side_effecting.insert(action_type.to_string());
+    }
+
+    /// Mark an action type as finishing in the background: its handler
+    /// returns once the work has started, so its result says nothing about
+    /// whether the work succeeded.
+    pub fn register_background(&mut self, action_type: &str) {
+        self.background.insert(action_type.to_string());
+    }
+
+    /// Check if an action type finishes in the background
+    pub fn runs_in_background(&self, action_type: &str) -> bool {
+        self.background.contains(action_type)
+    }
+
+    /// Register a hold-type action: `press` runs when the button goes down and
+    /// `release` runs when it comes back up (e.g. holding a key for push-to-talk).
+    pub fn register_hold(&mut self, action_type: &str, press: ActionHandler, release: ActionHandler) {
+        self.register_side_effecting(action_type, press);
+        self.release_handlers.insert(action_type.to_string(), release);
+    }
+
+    /// Check if an action type needs to be notified when its button is released
+    pub fn has_release_handler(&self, action_type: &str) -> bool {
+        self.release_handlers.contains_key(action_type)
+    }
+
+    /// Run the release half of a hold-type action
+    pub fn execute_release(&self, action: &Action, app_handle: &AppHandle) -> Result<(), String> {
+        if app_handle.state::<AppState>().simulate_actions.load(Ordering::Relaxed) {
+            println!("[simulate] Would release: {}", action.action_type);
+            return Ok(());
+        }
+
+        match self.release_handlers.get(&action.action_type) {
             Some(handler) => handler(action, app_handle),
+            None => Ok(()),
+        }
+    }
+
+    /// Execute an action by looking up its handler.
+    ///
+    /// Returns the state reported by stateful handlers, or `None` for
+    /// handlers that don't report one.
+    pub fn execute(&self, action: &Action, app_handle: &AppHandle) -> Result<Option<bool>, String> {
+        // In simulate mode, report what would have run instead of running it
+        if self.side_effecting.contains(&action.action_type) {
+            let state = app_handle.state::<AppState>();
+            if state.simulate_actions.load(Ordering::Relaxed) {
+                println!("[simulate] Would execute: {} {:?}", action.action_type, action.params);
+                let _ = app_handle.emit("streamdeck://action-simulated", action.clone());
+                return Ok(None);
+            }
+        }
+
+        match self.handlers.get(&action.action_type) {
+            Some(Handler::Plain(handler)) => handler(action, app_handle).map(|()| None),
+            Some(Handler::Stateful(handler)) => handler(action, app_handle).map(Some),
+            Some(Handler::Forwarding(handler)) => handler(action, app_handle),
             None => {
                 // Log unknown action but don't crash
                 eprintln!("Unknown action type: {}", action.action_type);
-                Ok(())
+                Ok(None)
             }
         }
     }
@@ -58,26 +187,68 @@ impl ActionRegistry {
     /// Register all built-in action handlers
     fn register_defaults(&mut self) {
         // Audio actions
-        self.register("audio.volume_up", handlers::audio_volume_up);
-        self.register("audio.volume_down", handlers::audio_volume_down);
-        self.register("audio.volume_mute", handlers::audio_volume_mute);
-        self.register("audio.play_sound", handlers::audio_play_sound);
+        self.register_side_effecting("audio.volume_up", handlers::audio_volume_up);
+        self.register_side_effecting("audio.volume_down", handlers::audio_volume_down);
+        self.register_side_effecting("audio.volume_mute", handlers::audio_volume_mute);
+        self.register_side_effecting("audio.play_sound", handlers::audio_play_sound);
+        self.register_side_effecting("audio.set_output_device", handlers::audio_set_output_device);
+        self.register_side_effecting("audio.cycle_output_device", handlers::audio_cycle_output_device);
+        self.register_stateful("audio.mic_meter", handlers::audio_mic_meter);
+        self.register_stateful_side_effecting("audio.toggle_app_mute", handlers::audio_toggle_app_mute);
 
         // Navigation actions
         self.register("navigation.next_page", handlers::navigation_next_page);
         self.register("navigation.previous_page", handlers::navigation_previous_page);
         self.register("navigation.go_to_page", handlers::navigation_go_to_page);
+        self.register("navigation.go_to_page_named", handlers::navigation_go_to_page_named);
 
         // System actions
-        self.register("system.launch_app", handlers::system_launch_app);
-        self.register("system.open_url", handlers::system_open_url);
-        self.register("system.hotkey", handlers::system_hotkey);
+        self.register_side_effecting("system.launch_app", handlers::system_launch_app);
+        self.register_side_effecting("system.open_url", handlers::system_open_url);
+        self.register_side_effecting("system.open_path", handlers::system_open_path);
+        self.register_side_effecting("system.hotkey", handlers::system_hotkey);
+        self.register_side_effecting("system.run_script", handlers::system_run_script);
+        self.register_side_effecting("system.region_capture", handlers::system_region_capture);
+        self.register_hold("system.key_hold", handlers::system_key_hold_press, handlers::system_key_hold_release);
+        self.register("system.set_polling_rate", handlers::system_set_polling_rate);
+        self.register("system.cycle_brightness", handlers::system_cycle_brightness);
+        self.register("system.window_switcher", handlers::system_window_switcher);
+        self.register_side_effecting("system.focus_window", handlers::system_focus_window);
+
+        // Script actions
+        self.register_side_effecting("script.run", handlers::script_run);
 
         // Weather actions
         self.register("weather.display", handlers::display_weather);
 
+        // Web actions
+        self.register_side_effecting("web.post_message", handlers::web_post_message);
+
+        // App actions
+        self.register("app.toggle_window", handlers::app_toggle_window);
+
+        // Meta actions
+        self.register_stateful("meta.toggle_simulate", handlers::meta_toggle_simulate);
+        self.register_stateful("meta.toggle_lock", handlers::meta_toggle_lock);
+        self.register("meta.redraw", handlers::meta_redraw);
+        self.register_forwarding("meta.random", handlers::meta_random);
+        self.register("meta.confirm", handlers::meta_confirm);
+        self.register("meta.conditional", handlers::meta_conditional);
+
         // Special actions
         self.register("none", handlers::action_none);
+
+        // Actions that hand their work to another thread (meta.random may pick one)
+        for action_type in [
+            "system.run_script",
+            "weather.display",
+            "web.post_message",
+            "meta.random",
+            "meta.confirm",
+            "meta.conditional",
+        ] {
+            self.register_background(action_type);
+        }
     }
 }
 
@@ -0,0 +1,151 @@
+//! Microphone Level Meter
+//!
+//! Samples the default input device with `cpal` and draws a live VU-meter bar
+//! on a button, for `audio.mic_meter`.
+//!
+//! Each meter runs on its own thread, which owns the input stream (streams
+//! can't be moved between threads on every platform). It stops when toggled
+//! off, when its page is no longer shown, or when the button is reconfigured.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{SampleFormat, Stream};
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+/// Action type the meter belongs to; the meter stops if the button is given another action
+pub const MIC_METER_ACTION: &str = "audio.mic_meter";
+
+/// How often the meter is redrawn
+const UPDATE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How much of the previous level is kept each update, so the bar falls smoothly
+const DECAY: f32 = 0.6;
+
+/// Flag that tells a meter thread to stop
+type StopFlag = Arc<AtomicBool>;
+
+/// Running meters by (page, button)
+static METERS: LazyLock<Mutex<HashMap<(usize, usize), StopFlag>>> = LazyLock::new(Default::default);
+
+/// Start a meter on a button of the current page, or stop the one already running there.
+///
+/// Returns whether a meter is now running.
+pub fn toggle_meter(app_handle: &AppHandle, button_index: usize) -> Result<bool, String> {
+    let page_index = app_handle.state::<AppState>().config.lock().unwrap().shown_page();
+    let key = (page_index, button_index);
+
+    let mut meters = METERS.lock().unwrap();
+    if let Some(stop) = meters.remove(&key) {
+        stop.store(true, Ordering::Relaxed);
+        return Ok(false);
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    meters.insert(key, stop.clone());
+    drop(meters);
+
+    let app_handle = app_handle.clone();
+    thread::spawn(move || {
+        if let Err(e) = run_meter(&app_handle, page_index, button_index, &stop) {
+            eprintln!("Mic meter on button {} stopped: {}", button_index, e);
+        }
+
+        // Forget this meter, unless it was already replaced by a new one
+        let mut meters = METERS.lock().unwrap();
+        if meters.get(&key).is_some_and(|current| Arc::ptr_eq(current, &stop)) {
+            meters.remove(&key);
+        }
+        drop(meters);
+
+        let state = app_handle.state::<AppState>();
+        if still_shown(&state, page_index, button_index) {
+            crate::images::refresh_button(&state, &app_handle, button_index);
+        }
+    });
+
+    Ok(true)
+}
+
+/// Sample the microphone and redraw the meter until stopped.
+fn run_meter(app_handle: &AppHandle, page_index: usize, button_index: usize, stop: &AtomicBool) -> Result<(), String> {
+    // Loudest sample since the last redraw, as f32 bits
+    let peak = Arc::new(AtomicU32::new(0));
+    let _stream = open_input_stream(peak.clone())?;
+
+    let state = app_handle.state::<AppState>();
+    let mut level = 0.0f32;
+
+    while !stop.load(Ordering::Relaxed) && still_shown(&state, page_index, button_index) {
+        let latest = f32::from_bits(peak.swap(0, Ordering::Relaxed));
+        level = latest.max(level * DECAY);
+        crate::images::show_level_meter(&state, button_index, level);
+        thread::sleep(UPDATE_INTERVAL);
+    }
+    Ok(())
+}
+
+/// Whether the meter's page is on screen and the button still has the meter action
+fn still_shown(state: &AppState, page_index: usize, button_index: usize) -> bool {
+    let config = state.config.lock().unwrap();
+    config.shown_page() == page_index
+        && config
+            .page(page_index)
+            .and_then(|page| page.buttons.get(&button_index))
+            .is_some_and(|button| button.action.action_type == MIC_METER_ACTION)
+}
+
+/// Open the default input device, recording the loudest sample into `peak`.
+fn open_input_stream(peak: Arc<AtomicU32>) -> Result<Stream, String> {
+    let device = cpal::default_host()
+        .default_input_device()
+        .ok_or("No microphone found")?;
+    let config = device
+        .default_input_config()
+        .map_err(|e| format!("Failed to read microphone config: {}", e))?;
+
+    let on_error = |e: cpal::StreamError| eprintln!("Microphone stream error: {}", e);
+    let stream = match config.sample_format() {
+        SampleFormat::F32 => device.build_input_stream(
+            &config.into(),
+            move |data: &[f32], _: &cpal::InputCallbackInfo| record_peak(&peak, data.iter().copied()),
+            on_error,
+            None,
+        ),
+        SampleFormat::I16 => device.build_input_stream(
+            &config.into(),
+            move |data: &[i16], _: &cpal::InputCallbackInfo| {
+                record_peak(&peak, data.iter().map(|&s| s as f32 / i16::MAX as f32))
+            },
+            on_error,
+            None,
+        ),
+        SampleFormat::U16 => device.build_input_stream(
+            &config.into(),
+            move |data: &[u16], _: &cpal::InputCallbackInfo| {
+                record_peak(&peak, data.iter().map(|&s| (s as f32 - 32768.0) / 32768.0))
+            },
+            on_error,
+            None,
+        ),
+        format => return Err(format!("Unsupported microphone sample format {:?}", format)),
+    }
+    .map_err(|e| format!("Failed to open microphone: {}", e))?;
+
+    stream.play().map_err(|e| format!("Failed to start microphone: {}", e))?;
+    Ok(stream)
+}
+
+/// Raise `peak` to the loudest of `samples` (as 0.0-1.0)
+fn record_peak(peak: &AtomicU32, samples: impl Iterator<Item = f32>) {
+    let loudest = samples.fold(0.0f32, |max, s| max.max(s.abs())).min(1.0);
+    let _ = peak.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+        (loudest > f32::from_bits(current)).then_some(loudest.to_bits())
+    });
+}
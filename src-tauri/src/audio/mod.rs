@@ -4,6 +4,10 @@
 //! POWERSHELL IS JUST A TEMP SOLUTION. THERE IS A BUG IN THE WINDOWS API FOR 
 //! .ACTIVATE() AND YOU CANNOT USE IT CURRENTLY FOR VOLUME CONTROLCL
 
+pub mod meter;
+pub mod output;
+pub mod session;
+
 use std::process::Command;
 
 /// Increase the system volume by one step.
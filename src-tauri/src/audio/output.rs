@@ -0,0 +1,193 @@
+//! Audio Output Device Switching (Windows)
+//!
+//! Lists playback devices through Core Audio and changes the default one with
+//! the undocumented `IPolicyConfig` interface (the same one the Sound control
+//! panel uses). There is no public API for setting the default device.
+
+use serde::Serialize;
+
+/// A playback device, as shown to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioDevice {
+    /// Endpoint ID (stable across reboots)
+    pub id: String,
+    /// Friendly name, e.g. "Speakers (Realtek High Definition Audio)"
+    pub name: String,
+    /// Whether this is the current default output
+    pub is_default: bool,
+}
+
+/// Find a device by name: exact (case-insensitive) match first, then substring.
+fn find_by_name<'a>(devices: &'a [AudioDevice], name: &str) -> Option<&'a AudioDevice> {
+    let name = name.to_lowercase();
+    devices
+        .iter()
+        .find(|device| device.name.to_lowercase() == name)
+        .or_else(|| devices.iter().find(|device| device.name.to_lowercase().contains(&name)))
+}
+
+/// Make the output device matching `name` the default.
+pub fn set_output_device(name: &str) -> Result<(), String> {
+    let devices = list_output_devices()?;
+    let device = find_by_name(&devices, name).ok_or_else(|| format!("No audio output device matching '{}'", name))?;
+
+    println!("Switching audio output to {}", device.name);
+    platform::set_default_device(&device.id)
+}
+
+/// Switch to the next output device after the current default.
+pub fn cycle_output_device() -> Result<(), String> {
+    let devices = list_output_devices()?;
+    if devices.is_empty() {
+        return Err("No audio output devices found".to_string());
+    }
+
+    let next = match devices.iter().position(|device| device.is_default) {
+        Some(current) => (current + 1) % devices.len(),
+        None => 0,
+    };
+
+    println!("Switching audio output to {}", devices[next].name);
+    platform::set_default_device(&devices[next].id)
+}
+
+/// List active playback devices.
+pub fn list_output_devices() -> Result<Vec<AudioDevice>, String> {
+    platform::list_output_devices()
+}
+
+#[cfg(windows)]
+#[allow(non_snake_case)]
+mod platform {
+    use windows::core::{interface, IUnknown, IUnknown_Vtbl, GUID, HRESULT, PCWSTR, PWSTR};
+    use windows::Win32::Devices::FunctionDiscovery::PKEY_Device_FriendlyName;
+    use windows::Win32::Media::Audio::{
+        eCommunications, eConsole, eMultimedia, eRender, ERole, IMMDevice, IMMDeviceEnumerator, MMDeviceEnumerator,
+        DEVICE_STATE_ACTIVE,
+    };
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CoTaskMemFree, CLSCTX_ALL, COINIT_MULTITHREADED, STGM_READ,
+    };
+
+    use super::AudioDevice;
+
+    /// CLSID of the class implementing `IPolicyConfig`
+    const CLSID_POLICY_CONFIG_CLIENT: GUID = GUID::from_u128(0x870af99c_171d_4f9e_af0d_e63df40c2bc9);
+
+    /// Undocumented policy interface (Windows 7 and later).
+    /// Only `SetDefaultEndpoint` is used; the other slots keep the vtable layout.
+    #[interface("f8679f50-850a-41cf-9c72-430f290290c8")]
+    unsafe trait IPolicyConfig: IUnknown {
+        fn GetMixFormat(&self, device_id: PCWSTR, format: *mut *mut core::ffi::c_void) -> HRESULT;
+        fn GetDeviceFormat(&self, device_id: PCWSTR, default: i32, format: *mut *mut core::ffi::c_void) -> HRESULT;
+        fn ResetDeviceFormat(&self, device_id: PCWSTR) -> HRESULT;
+        fn SetDeviceFormat(
+            &self,
+            device_id: PCWSTR,
+            endpoint_format: *mut core::ffi::c_void,
+            mix_format: *mut core::ffi::c_void,
+        ) -> HRESULT;
+        fn GetProcessingPeriod(&self, device_id: PCWSTR, default: i32, default_period: *mut i64, min_period: *mut i64) -> HRESULT;
+        fn SetProcessingPeriod(&self, device_id: PCWSTR, period: *mut i64) -> HRESULT;
+        fn GetShareMode(&self, device_id: PCWSTR, mode: *mut core::ffi::c_void) -> HRESULT;
+        fn SetShareMode(&self, device_id: PCWSTR, mode: *mut core::ffi::c_void) -> HRESULT;
+        fn GetPropertyValue(&self, device_id: PCWSTR, key: *const core::ffi::c_void, value: *mut core::ffi::c_void) -> HRESULT;
+        fn SetPropertyValue(&self, device_id: PCWSTR, key: *const core::ffi::c_void, value: *mut core::ffi::c_void) -> HRESULT;
+        fn SetDefaultEndpoint(&self, device_id: PCWSTR, role: ERole) -> HRESULT;
+        fn SetEndpointVisibility(&self, device_id: PCWSTR, visible: i32) -> HRESULT;
+    }
+
+    fn enumerator() -> Result<IMMDeviceEnumerator, String> {
+        unsafe {
+            // Already-initialized threads (e.g. the STA main thread) return an error
+            // here but can still use COM, so the result is ignored
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+            CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
+                .map_err(|e| format!("Failed to create device enumerator: {}", e))
+        }
+    }
+
+    fn device_id(device: &IMMDevice) -> Result<String, String> {
+        unsafe {
+            let id: PWSTR = device.GetId().map_err(|e| format!("Failed to get device ID: {}", e))?;
+            let result = id.to_string().map_err(|e| format!("Invalid device ID: {}", e));
+            CoTaskMemFree(Some(id.0 as *const _));
+            result
+        }
+    }
+
+    fn device_name(device: &IMMDevice) -> Result<String, String> {
+        unsafe {
+            let store = device
+                .OpenPropertyStore(STGM_READ)
+                .map_err(|e| format!("Failed to open device properties: {}", e))?;
+            let name = store
+                .GetValue(&PKEY_Device_FriendlyName)
+                .map_err(|e| format!("Failed to read device name: {}", e))?;
+            Ok(name.to_string())
+        }
+    }
+
+    pub fn list_output_devices() -> Result<Vec<AudioDevice>, String> {
+        let enumerator = enumerator()?;
+
+        unsafe {
+            // No default device is possible (e.g. everything unplugged)
+            let default_id = enumerator
+                .GetDefaultAudioEndpoint(eRender, eConsole)
+                .ok()
+                .and_then(|device| device_id(&device).ok());
+
+            let collection = enumerator
+                .EnumAudioEndpoints(eRender, DEVICE_STATE_ACTIVE)
+                .map_err(|e| format!("Failed to enumerate audio devices: {}", e))?;
+            let count = collection.GetCount().map_err(|e| format!("Failed to count audio devices: {}", e))?;
+
+            let mut devices = Vec::new();
+            for i in 0..count {
+                let device = collection.Item(i).map_err(|e| format!("Failed to get audio device: {}", e))?;
+                let id = device_id(&device)?;
+                devices.push(AudioDevice {
+                    name: device_name(&device)?,
+                    is_default: default_id.as_deref() == Some(id.as_str()),
+                    id,
+                });
+            }
+
+            Ok(devices)
+        }
+    }
+
+    pub fn set_default_device(id: &str) -> Result<(), String> {
+        let wide: Vec<u16> = id.encode_utf16().chain(std::iter::once(0)).collect();
+
+        unsafe {
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+            let policy: IPolicyConfig = CoCreateInstance(&CLSID_POLICY_CONFIG_CLIENT, None, CLSCTX_ALL)
+                .map_err(|e| format!("Failed to create policy config: {}", e))?;
+
+            // Set every role so apps, media and calls all follow the switch
+            for role in [eConsole, eMultimedia, eCommunications] {
+                policy
+                    .SetDefaultEndpoint(PCWSTR(wide.as_ptr()), role)
+                    .ok()
+                    .map_err(|e| format!("Failed to set default audio device: {}", e))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    use super::AudioDevice;
+
+    pub fn list_output_devices() -> Result<Vec<AudioDevice>, String> {
+        Err("Audio output switching is only supported on Windows".to_string())
+    }
+
+    pub fn set_default_device(_id: &str) -> Result<(), String> {
+        Err("Audio output switching is only supported on Windows".to_string())
+    }
+}
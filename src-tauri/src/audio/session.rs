@@ -0,0 +1,115 @@
+//! Per-App Audio Sessions (Windows)
+//!
+//! Mutes and unmutes one application's audio through the Core Audio session
+//! API, for `audio.toggle_app_mute`. Every app playing sound on the default
+//! output has a session there, with its own mute switch (the one in the
+//! Volume Mixer).
+
+/// Toggle the mute of every audio session belonging to `process_name`
+/// (e.g. "Spotify.exe" or just "spotify").
+///
+/// An app can have several sessions (browsers often do); if any of them is
+/// audible they are all muted, otherwise they are all unmuted.
+/// Returns whether the app is now muted.
+pub fn toggle_app_mute(process_name: &str) -> Result<bool, String> {
+    let muted = platform::toggle_mute(process_name)?;
+    println!("{} {}", if muted { "Muted" } else { "Unmuted" }, process_name);
+    Ok(muted)
+}
+
+#[cfg(windows)]
+mod platform {
+    use windows::core::{Interface, PWSTR};
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::Media::Audio::{
+        eConsole, eRender, IAudioSessionControl2, IAudioSessionManager2, IMMDeviceEnumerator, ISimpleAudioVolume,
+        MMDeviceEnumerator,
+    };
+    use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_MULTITHREADED};
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    /// Whether a session's process name matches `name`, ignoring case and ".exe"
+    fn matches_process(process_name: &str, name: &str) -> bool {
+        let strip = |s: &str| {
+            let s = s.to_lowercase();
+            s.strip_suffix(".exe").map(str::to_string).unwrap_or(s)
+        };
+        strip(process_name) == strip(name)
+    }
+
+    /// File name of a process's executable, e.g. "Spotify.exe"
+    fn process_name(pid: u32) -> Option<String> {
+        unsafe {
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+            let mut buffer = [0u16; 1024];
+            let mut len = buffer.len() as u32;
+            let result = QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, PWSTR(buffer.as_mut_ptr()), &mut len);
+            let _ = CloseHandle(process);
+            result.ok()?;
+
+            let path = String::from_utf16_lossy(&buffer[..len as usize]);
+            path.rsplit('\\').next().map(str::to_string)
+        }
+    }
+
+    /// Volume controls of the sessions on the default output that belong to `name`
+    fn app_sessions(name: &str) -> Result<Vec<ISimpleAudioVolume>, String> {
+        unsafe {
+            // See output.rs: an already-initialized thread can still use COM
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+            let enumerator: IMMDeviceEnumerator = CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
+                .map_err(|e| format!("Failed to create device enumerator: {}", e))?;
+            let device = enumerator
+                .GetDefaultAudioEndpoint(eRender, eConsole)
+                .map_err(|e| format!("No default audio output: {}", e))?;
+            let manager: IAudioSessionManager2 = device
+                .Activate(CLSCTX_ALL, None)
+                .map_err(|e| format!("Failed to open audio sessions: {}", e))?;
+            let sessions = manager
+                .GetSessionEnumerator()
+                .map_err(|e| format!("Failed to enumerate audio sessions: {}", e))?;
+            let count = sessions.GetCount().map_err(|e| format!("Failed to count audio sessions: {}", e))?;
+
+            let mut volumes = Vec::new();
+            for i in 0..count {
+                let Ok(control) = sessions.GetSession(i) else { continue };
+                let Ok(control) = control.cast::<IAudioSessionControl2>() else { continue };
+                // Process 0 is the shared "System Sounds" session
+                let pid = control.GetProcessId().unwrap_or(0);
+                if pid == 0 || !process_name(pid).is_some_and(|process| matches_process(&process, name)) {
+                    continue;
+                }
+                if let Ok(volume) = control.cast::<ISimpleAudioVolume>() {
+                    volumes.push(volume);
+                }
+            }
+            Ok(volumes)
+        }
+    }
+
+    pub fn toggle_mute(name: &str) -> Result<bool, String> {
+        let sessions = app_sessions(name)?;
+        if sessions.is_empty() {
+            return Err(format!("No audio session for '{}' (is it playing sound?)", name));
+        }
+
+        unsafe {
+            let any_audible = sessions.iter().any(|session| session.GetMute().is_ok_and(|muted| !muted.as_bool()));
+            for session in &sessions {
+                session
+                    .SetMute(any_audible, std::ptr::null())
+                    .map_err(|e| format!("Failed to mute '{}': {}", name, e))?;
+            }
+            Ok(any_audible)
+        }
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    pub fn toggle_mute(_name: &str) -> Result<bool, String> {
+        Err("Muting individual apps is only supported on Windows".to_string())
+    }
+}
@@ -0,0 +1,88 @@
+//! Screen Capture Module
+//!
+//! Captures regions of the screen and hands them to the clipboard,
+//! either as an image or (with the `ocr` feature) as recognized text.
+//! Also reads images back off the clipboard, for pasting button images.
+
+use arboard::{Clipboard, ImageData};
+use image::RgbaImage;
+use xcap::Monitor;
+
+/// Capture a screen region given in desktop coordinates.
+///
+/// The region must lie on a single monitor (the one containing its top-left corner).
+pub fn capture_region(x: i32, y: i32, width: u32, height: u32) -> Result<RgbaImage, String> {
+    if width == 0 || height == 0 {
+        return Err("Capture region must have a non-zero size".to_string());
+    }
+
+    let monitor = Monitor::from_point(x, y)
+        .map_err(|e| format!("No monitor at ({}, {}): {}", x, y, e))?;
+
+    let monitor_x = monitor.x().map_err(|e| format!("Failed to read monitor position: {}", e))?;
+    let monitor_y = monitor.y().map_err(|e| format!("Failed to read monitor position: {}", e))?;
+
+    monitor
+        .capture_region((x - monitor_x) as u32, (y - monitor_y) as u32, width, height)
+        .map_err(|e| format!("Failed to capture screen region: {}", e))
+}
+
+/// Put an image on the system clipboard.
+pub fn copy_image_to_clipboard(image: &RgbaImage) -> Result<(), String> {
+    let mut clipboard = Clipboard::new().map_err(|e| format!("Failed to open clipboard: {}", e))?;
+
+    clipboard
+        .set_image(ImageData {
+            width: image.width() as usize,
+            height: image.height() as usize,
+            bytes: image.as_raw().into(),
+        })
+        .map_err(|e| format!("Failed to copy image to clipboard: {}", e))
+}
+
+/// Read the image on the system clipboard.
+pub fn read_image_from_clipboard() -> Result<RgbaImage, String> {
+    let mut clipboard = Clipboard::new().map_err(|e| format!("Failed to open clipboard: {}", e))?;
+
+    let data = clipboard.get_image().map_err(|e| match e {
+        arboard::Error::ContentNotAvailable => "The clipboard doesn't contain an image".to_string(),
+        e => format!("Failed to read image from clipboard: {}", e),
+    })?;
+
+    RgbaImage::from_raw(data.width as u32, data.height as u32, data.bytes.into_owned())
+        .ok_or_else(|| "Clipboard image data doesn't match its size".to_string())
+}
+
+/// Put text on the system clipboard.
+pub fn copy_text_to_clipboard(text: &str) -> Result<(), String> {
+    let mut clipboard = Clipboard::new().map_err(|e| format!("Failed to open clipboard: {}", e))?;
+
+    clipboard
+        .set_text(text)
+        .map_err(|e| format!("Failed to copy text to clipboard: {}", e))
+}
+
+/// Recognize the text in an image (English) with Tesseract.
+#[cfg(feature = "ocr")]
+pub fn extract_text(image: &RgbaImage) -> Result<String, String> {
+    use std::io::Cursor;
+
+    // Tesseract reads encoded images, so hand it a PNG
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode capture: {}", e))?;
+
+    tesseract::Tesseract::new(None, Some("eng"))
+        .map_err(|e| format!("Failed to initialize OCR: {}", e))?
+        .set_image_from_mem(&png)
+        .map_err(|e| format!("Failed to load capture for OCR: {}", e))?
+        .get_text()
+        .map_err(|e| format!("OCR failed: {}", e))
+}
+
+/// OCR isn't compiled in; build with `--features ocr` to enable it.
+#[cfg(not(feature = "ocr"))]
+pub fn extract_text(_image: &RgbaImage) -> Result<String, String> {
+    Err("OCR support is not enabled (rebuild with --features ocr)".to_string())
+}
@@ -0,0 +1,202 @@
+//! Button Combos
+//!
+//! Detects chords from `config.combos` (e.g. hold button 0 and press 4) for
+//! the polling loop.
+//!
+//! A press of a button that's part of any combo is held back for as long as
+//! the button stays down, in case the rest of a combo follows. If it does, the
+//! combo's action runs and its buttons are hidden from the loop until they're
+//! released, so their own actions don't run. Otherwise the press goes through
+//! when the button is released, followed by the release.
+
+use crate::config::{Action, Combo};
+use crate::hid::constants::BUTTON_COUNT;
+
+/// Tracks held-back and combo presses across polls
+pub struct ComboTracker {
+    /// Raw button states from the previous step
+    prev: [bool; BUTTON_COUNT],
+    /// Combo buttons held down whose press is being held back
+    pending: [bool; BUTTON_COUNT],
+    /// Buttons used by a combo that fired, hidden until released
+    consumed: [bool; BUTTON_COUNT],
+}
+
+impl ComboTracker {
+    /// Start tracking, with `held` the buttons already down (they aren't new presses)
+    pub fn new(held: [bool; BUTTON_COUNT]) -> Self {
+        Self {
+            prev: held,
+            pending: [false; BUTTON_COUNT],
+            consumed: [false; BUTTON_COUNT],
+        }
+    }
+
+    /// Feed one raw button state.
+    ///
+    /// Returns the states the rest of the polling loop should process, in
+    /// order (usually just one, with held-back and combo buttons left out),
+    /// and the actions of any combos completed by this state.
+    pub fn step(&mut self, raw: [bool; BUTTON_COUNT], combos: &[Combo]) -> (Vec<[bool; BUTTON_COUNT]>, Vec<Action>) {
+        let combos: Vec<&Combo> = combos.iter().filter(|combo| combo.buttons.len() >= 2).collect();
+        let mut states = Vec::new();
+        let mut fired = Vec::new();
+
+        for i in 0..BUTTON_COUNT {
+            if raw[i] && !self.prev[i] && combos.iter().any(|combo| combo.buttons.contains(&i)) {
+                self.pending[i] = true;
+            } else if !raw[i] && self.prev[i] {
+                self.consumed[i] = false;
+                // Released without completing a combo: show the press before the release
+                if std::mem::take(&mut self.pending[i]) {
+                    let mut pressed = self.visible(raw);
+                    pressed[i] = true;
+                    states.push(pressed);
+                }
+            }
+        }
+
+        for combo in combos {
+            let complete = combo
+                .buttons
+                .iter()
+                .all(|&i| raw.get(i).copied().unwrap_or(false) && self.pending.get(i).copied().unwrap_or(false));
+
+            if complete {
+                for &i in &combo.buttons {
+                    self.pending[i] = false;
+                    self.consumed[i] = true;
+                }
+                fired.push(combo.action.clone());
+            }
+        }
+
+        self.prev = raw;
+        states.push(self.visible(raw));
+        (states, fired)
+    }
+
+    /// `raw` without the buttons that are held back or part of a combo
+    fn visible(&self, raw: [bool; BUTTON_COUNT]) -> [bool; BUTTON_COUNT] {
+        let mut visible = raw;
+        for (i, pressed) in visible.iter_mut().enumerate() {
+            if self.pending[i] || self.consumed[i] {
+                *pressed = false;
+            }
+        }
+        visible
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn combo(buttons: &[usize], action_type: &str) -> Combo {
+        Combo { buttons: buttons.to_vec(), action: Action::new(action_type) }
+    }
+
+    fn down(buttons: &[usize]) -> [bool; BUTTON_COUNT] {
+        let mut state = [false; BUTTON_COUNT];
+        for &i in buttons {
+            state[i] = true;
+        }
+        state
+    }
+
+    fn fired_types(fired: &[Action]) -> Vec<&str> {
+        fired.iter().map(|action| action.action_type.as_str()).collect()
+    }
+
+    #[test]
+    fn other_buttons_pass_straight_through() {
+        let combos = [combo(&[0, 4], "combo")];
+        let mut tracker = ComboTracker::new(down(&[]));
+
+        let (states, fired) = tracker.step(down(&[2]), &combos);
+        assert_eq!(states, vec![down(&[2])]);
+        assert!(fired.is_empty());
+    }
+
+    #[test]
+    fn combo_stays_armed_while_its_first_button_is_held() {
+        let combos = [combo(&[0, 4], "combo")];
+        let mut tracker = ComboTracker::new(down(&[]));
+
+        let (states, fired) = tracker.step(down(&[0]), &combos);
+        assert_eq!(states, vec![down(&[])]);
+        assert!(fired.is_empty());
+
+        // However long button 0 is held, 4 still completes the combo
+        for _ in 0..100 {
+            let (states, _) = tracker.step(down(&[0]), &combos);
+            assert_eq!(states, vec![down(&[])]);
+        }
+        let (states, fired) = tracker.step(down(&[0, 4]), &combos);
+        assert_eq!(states, vec![down(&[])]);
+        assert_eq!(fired_types(&fired), vec!["combo"]);
+    }
+
+    #[test]
+    fn combo_buttons_stay_hidden_until_released() {
+        let combos = [combo(&[0, 4], "combo")];
+        let mut tracker = ComboTracker::new(down(&[]));
+        tracker.step(down(&[0]), &combos);
+        tracker.step(down(&[0, 4]), &combos);
+
+        // Neither button's own action runs on release
+        let (states, fired) = tracker.step(down(&[0]), &combos);
+        assert_eq!(states, vec![down(&[])]);
+        assert!(fired.is_empty());
+        let (states, _) = tracker.step(down(&[]), &combos);
+        assert_eq!(states, vec![down(&[])]);
+
+        // The next press is held back again, not swallowed
+        let (states, _) = tracker.step(down(&[0]), &combos);
+        assert_eq!(states, vec![down(&[])]);
+        let (states, _) = tracker.step(down(&[]), &combos);
+        assert_eq!(states, vec![down(&[0]), down(&[])]);
+    }
+
+    #[test]
+    fn lone_combo_button_presses_on_release() {
+        let combos = [combo(&[0, 4], "combo")];
+        let mut tracker = ComboTracker::new(down(&[]));
+
+        let (states, _) = tracker.step(down(&[0]), &combos);
+        assert_eq!(states, vec![down(&[])]);
+
+        let (states, fired) = tracker.step(down(&[]), &combos);
+        assert_eq!(states, vec![down(&[0]), down(&[])]);
+        assert!(fired.is_empty());
+    }
+
+    #[test]
+    fn combo_fires_whichever_button_goes_down_first() {
+        let combos = [combo(&[0, 4], "combo")];
+        let mut tracker = ComboTracker::new(down(&[]));
+
+        tracker.step(down(&[4]), &combos);
+        let (_, fired) = tracker.step(down(&[0, 4]), &combos);
+        assert_eq!(fired_types(&fired), vec!["combo"]);
+    }
+
+    #[test]
+    fn buttons_held_at_start_are_not_presses() {
+        let combos = [combo(&[0, 4], "combo")];
+        let mut tracker = ComboTracker::new(down(&[0]));
+
+        let (_, fired) = tracker.step(down(&[0, 4]), &combos);
+        assert!(fired.is_empty());
+    }
+
+    #[test]
+    fn single_button_combos_are_ignored() {
+        let combos = [combo(&[0], "combo")];
+        let mut tracker = ComboTracker::new(down(&[]));
+
+        let (states, fired) = tracker.step(down(&[0]), &combos);
+        assert_eq!(states, vec![down(&[0])]);
+        assert!(fired.is_empty());
+    }
+}
@@ -0,0 +1,22 @@
+//! Audio Tauri Commands
+//!
+//! Commands for querying audio devices, so the UI can offer them as action parameters.
+
+use super::error::{CommandError, CommandResult};
+use crate::audio::output::{self, AudioDevice};
+
+/// List active audio output devices (Windows only).
+///
+/// # Returns
+///
+/// Every playback device, with `is_default` set on the current default.
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// const outputs = await invoke<AudioDevice[]>('list_audio_devices');
+/// ```
+#[tauri::command]
+pub fn list_audio_devices() -> CommandResult<Vec<AudioDevice>> {
+    output::list_output_devices().map_err(CommandError::device)
+}
@@ -0,0 +1,690 @@
+//! Config Tauri Commands
+//!
+//! Commands for reading and replacing the button configuration.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+
+use super::error::{CommandError, CommandResult, ErrorCode};
+use crate::config::presets::{self, PresetInfo};
+use crate::config::{profiles, storage, Action, ButtonImage, Config, Page};
+use crate::actions::ActionRegistry;
+use crate::hid::constants::BUTTON_COUNT;
+use crate::AppState;
+
+/// What a button does, in human terms (returned by `describe_button`)
+#[derive(Debug, Clone, Serialize)]
+pub struct ButtonDescription {
+    /// Action type identifier (e.g. "system.launch_app")
+    pub action_type: String,
+    /// Human-readable action name, or the action type for unknown actions
+    pub display_name: String,
+    /// Parameters as `key: value` pairs, comma separated (empty if none)
+    pub params_summary: String,
+    /// Whether a handler is registered for the action type
+    pub known_action: bool,
+    /// The button's label, if any
+    pub label: Option<String>,
+    /// Absolute path of the image the button shows, if any
+    pub image_path: Option<String>,
+}
+
+/// A built-in icon, as listed by `list_builtin_icons`
+#[derive(Debug, Clone, Serialize)]
+pub struct BuiltinIcon {
+    /// Name to use in `{ "type": "icon", "name": ... }` images
+    pub name: String,
+    /// Absolute path of the icon file
+    pub path: String,
+}
+
+/// Payload of the `streamdeck://page-changed` event
+#[derive(Debug, Clone, Serialize)]
+pub struct PageChanged {
+    /// Index of the page now shown
+    pub index: usize,
+    /// Name of the page now shown
+    pub name: String,
+}
+
+/// A saved profile, as listed by `list_profiles`
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileInfo {
+    pub name: String,
+    /// Whether this is the profile the current config belongs to
+    pub active: bool,
+}
+
+/// Payload of the `streamdeck://profile-changed` event
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileChanged {
+    /// Name of the profile now loaded
+    pub name: String,
+}
+
+/// Tell the frontend which page is now shown, after it changed.
+/// Nothing is sent for the transient page, which the frontend doesn't list.
+/// Must be called with the config lock released.
+pub(crate) fn emit_page_changed(state: &AppState, app_handle: &AppHandle) {
+    let event = {
+        let config = state.config.lock().unwrap();
+        config.pages.get(config.shown_page()).map(|page| PageChanged {
+            index: config.current_page,
+            name: page.name.clone(),
+        })
+    };
+
+    if let Some(event) = event {
+        let _ = app_handle.emit("streamdeck://page-changed", event);
+    }
+}
+
+/// Apply `edit` to one page of the config, then save the config and re-sync the device
+fn edit_page(
+    page: usize,
+    state: &State<'_, AppState>,
+    app_handle: &AppHandle,
+    edit: impl FnOnce(&mut Page) -> CommandResult<()>,
+) -> CommandResult<()> {
+    let mut config = state.config.lock().unwrap();
+    let num_pages = config.pages.len();
+    let target = config
+        .pages
+        .get_mut(page)
+        .ok_or_else(|| CommandError::invalid_page(format!("Page {} out of range ({} pages)", page, num_pages)))?;
+
+    edit(target)?;
+
+    let snapshot = config.clone();
+    drop(config);
+
+    storage::save(&snapshot, app_handle).map_err(CommandError::io)?;
+    crate::images::sync_images_to_device(state, app_handle);
+    Ok(())
+}
+
+/// Check that a button position exists on the deck
+fn check_position(index: usize) -> CommandResult<()> {
+    if index >= BUTTON_COUNT {
+        return Err(CommandError::invalid_button(format!("Button index {} out of range", index)));
+    }
+    Ok(())
+}
+
+/// List the bundled starter presets.
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// const presets = await invoke<PresetInfo[]>('list_presets');
+/// ```
+#[tauri::command]
+pub fn list_presets() -> Vec<PresetInfo> {
+    presets::list_presets()
+}
+
+/// Replace the current config with a bundled preset.
+///
+/// The current config is overwritten, so the caller must pass `confirm: true`
+/// after asking the user; otherwise nothing is changed and an error is returned.
+///
+/// # Arguments
+///
+/// * `name` - Preset name as returned by `list_presets`
+/// * `confirm` - Must be true to actually overwrite the config
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// if (window.confirm('Replace your current buttons with this preset?')) {
+///     await invoke('apply_preset', { name: 'Streaming', confirm: true });
+/// }
+/// ```
+#[tauri::command]
+pub fn apply_preset(name: String, confirm: bool, state: State<'_, AppState>, app_handle: AppHandle) -> CommandResult<()> {
+    let preset = presets::load_preset(&name).map_err(CommandError::not_found)?;
+
+    if !confirm {
+        return Err(CommandError::new(
+            ErrorCode::ConfirmationRequired,
+            "Applying a preset overwrites the current config; confirm to continue",
+        ));
+    }
+
+    storage::save(&preset, &app_handle).map_err(CommandError::io)?;
+    *state.config.lock().unwrap() = preset;
+    println!("Applied preset: {}", name);
+
+    crate::images::sync_images_to_device(&state, &app_handle);
+    Ok(())
+}
+
+/// Replace the whole config in one step, e.g. after editing it in the UI.
+///
+/// The new config is checked first (pages, button and dial indices, combos,
+/// action types). If anything is wrong nothing changes and the command fails
+/// with `INVALID_CONFIG`, listing every problem in `details`. Otherwise it's
+/// saved and takes effect: the device settings are applied to the connected
+/// decks as on connect, the focus shortcut is re-registered and the deck is
+/// redrawn. A focus shortcut that can't be registered fails the command with
+/// `INVALID_ARGUMENT`, leaving the old config in place.
+///
+/// # Arguments
+///
+/// * `config` - The complete new config
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// try {
+///     await invoke('replace_config', { config: edited });
+/// } catch (e) {
+///     if (isCommandError(e) && e.code === 'INVALID_CONFIG') showProblems(e.details);
+/// }
+/// ```
+#[tauri::command]
+pub fn replace_config(
+    config: Config,
+    state: State<'_, AppState>,
+    registry: State<'_, ActionRegistry>,
+    app_handle: AppHandle,
+) -> CommandResult<()> {
+    let problems = config.validate(|action_type| registry.has_action(action_type));
+    if !problems.is_empty() {
+        return Err(CommandError::invalid_config(problems));
+    }
+
+    let previous_shortcut = state.config.lock().unwrap().app.focus_shortcut.clone();
+    let shortcut_changed = config.app.focus_shortcut != previous_shortcut;
+    let restore_shortcut = || {
+        let _ = crate::shortcut::register_focus_shortcut(&app_handle, previous_shortcut.as_deref());
+    };
+    if shortcut_changed {
+        if let Err(e) = crate::shortcut::register_focus_shortcut(&app_handle, config.app.focus_shortcut.as_deref()) {
+            restore_shortcut();
+            return Err(CommandError::invalid_argument(e));
+        }
+    }
+
+    // Saved first, so a failed write leaves the old config in place
+    if let Err(e) = storage::save(&config, &app_handle) {
+        if shortcut_changed {
+            restore_shortcut();
+        }
+        return Err(CommandError::io(e));
+    }
+    *state.config.lock().unwrap() = config;
+    println!("Replaced config");
+
+    crate::commands::streamdeck::reapply_device_settings(&state);
+    crate::images::sync_images_to_device(&state, &app_handle);
+    emit_page_changed(&state, &app_handle);
+    Ok(())
+}
+
+/// Move a button to another position on a page.
+///
+/// # Arguments
+///
+/// * `page` - Page index
+/// * `from` - Current button position
+/// * `to` - New button position
+/// * `swap` - If `to` is occupied, swap the two buttons instead of failing (defaults to false)
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('move_button', { page: 0, from: 3, to: 7, swap: true });
+/// ```
+#[tauri::command]
+pub fn move_button(
+    page: usize,
+    from: usize,
+    to: usize,
+    swap: Option<bool>,
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+) -> CommandResult<()> {
+    check_position(from)?;
+    check_position(to)?;
+
+    edit_page(page, &state, &app_handle, |page| {
+        page.move_button(from, to, swap.unwrap_or(false))
+            .map_err(CommandError::invalid_button)
+    })
+}
+
+/// Swap two buttons on a page (either position may be empty).
+///
+/// # Arguments
+///
+/// * `page` - Page index
+/// * `a` - First button position
+/// * `b` - Second button position
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('swap_buttons', { page: 0, a: 3, b: 7 });
+/// ```
+#[tauri::command]
+pub fn swap_buttons(page: usize, a: usize, b: usize, state: State<'_, AppState>, app_handle: AppHandle) -> CommandResult<()> {
+    check_position(a)?;
+    check_position(b)?;
+
+    edit_page(page, &state, &app_handle, |page| {
+        page.swap_buttons(a, b);
+        Ok(())
+    })
+}
+
+/// Change a button's displayed label without touching its action.
+///
+/// The text/overlay image is regenerated and the device is redrawn,
+/// which makes this suitable for live values like scores or counters.
+///
+/// # Arguments
+///
+/// * `page` - Page index
+/// * `button` - Button position
+/// * `label` - New label, or `null` to remove it
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('set_button_label', { page: 0, button: 4, label: 'HOME 2 - 1 AWAY' });
+/// ```
+#[tauri::command]
+pub fn set_button_label(
+    page: usize,
+    button: usize,
+    label: Option<String>,
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+) -> CommandResult<()> {
+    check_position(button)?;
+
+    edit_page(page, &state, &app_handle, |page| {
+        let config = page
+            .buttons
+            .get_mut(&button)
+            .ok_or_else(|| CommandError::invalid_button(format!("No button at position {}", button)))?;
+        config.label = label;
+        Ok(())
+    })
+}
+
+/// Set a button's image to the image on the clipboard (e.g. one copied from a
+/// browser or image editor).
+///
+/// The image is saved as a PNG in the app cache directory and used as the
+/// button's `file` image, then the deck is redrawn.
+///
+/// # Arguments
+///
+/// * `page` - Page index
+/// * `button` - Button position
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('set_button_image_from_clipboard', { page: 0, button: 4 });
+/// ```
+#[tauri::command]
+pub fn set_button_image_from_clipboard(
+    page: usize,
+    button: usize,
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+) -> CommandResult<()> {
+    check_position(button)?;
+    {
+        let config = state.config.lock().unwrap();
+        let target = config.pages.get(page).ok_or_else(|| {
+            CommandError::invalid_page(format!("Page {} out of range ({} pages)", page, config.pages.len()))
+        })?;
+        if !target.buttons.contains_key(&button) {
+            return Err(CommandError::invalid_button(format!("No button at position {}", button)));
+        }
+    }
+
+    let image = crate::capture::read_image_from_clipboard().map_err(CommandError::invalid_image)?;
+
+    // Name the file after its content, so pasting the same image twice reuses it
+    let mut hasher = DefaultHasher::new();
+    image.as_raw().hash(&mut hasher);
+    let path = crate::images::cache_file_path(&app_handle, &format!("pasted_{:x}.png", hasher.finish()))
+        .ok_or_else(|| CommandError::io("Failed to get the app cache directory"))?;
+    image
+        .save(&path)
+        .map_err(|e| CommandError::io(format!("Failed to write {}: {}", path.display(), e)))?;
+    println!("Pasted {}x{} image onto button {}", image.width(), image.height(), button);
+
+    let path = path.to_string_lossy().into_owned();
+    edit_page(page, &state, &app_handle, |page| {
+        let config = page
+            .buttons
+            .get_mut(&button)
+            .ok_or_else(|| CommandError::invalid_button(format!("No button at position {}", button)))?;
+        config.image = Some(ButtonImage::File { path });
+        Ok(())
+    })
+}
+
+/// Describe what a button does, for showing a summary in the UI.
+///
+/// # Arguments
+///
+/// * `page` - Page index
+/// * `button` - Button position
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// const info = await invoke<ButtonDescription>('describe_button', { page: 0, button: 4 });
+/// console.log(`${info.display_name} (${info.params_summary})`);
+/// ```
+#[tauri::command]
+pub fn describe_button(
+    page: usize,
+    button: usize,
+    state: State<'_, AppState>,
+    registry: State<'_, ActionRegistry>,
+    app_handle: AppHandle,
+) -> CommandResult<ButtonDescription> {
+    check_position(button)?;
+
+    let button_config = {
+        let config = state.config.lock().unwrap();
+        let num_pages = config.pages.len();
+        let target = config
+            .pages
+            .get(page)
+            .ok_or_else(|| CommandError::invalid_page(format!("Page {} out of range ({} pages)", page, num_pages)))?;
+        target
+            .buttons
+            .get(&button)
+            .cloned()
+            .ok_or_else(|| CommandError::invalid_button(format!("No button at position {}", button)))?
+    };
+
+    let action = &button_config.action;
+    Ok(ButtonDescription {
+        action_type: action.action_type.clone(),
+        display_name: action.display_name().unwrap_or(&action.action_type).to_string(),
+        params_summary: action.params_summary(),
+        known_action: registry.has_action(&action.action_type),
+        label: button_config.label.clone(),
+        image_path: crate::images::resolve_button_image(&button_config, &app_handle),
+    })
+}
+
+/// Get the icon an action shows by default, for previews in the action picker.
+///
+/// Returns the absolute path of the icon (built-in or registered by a plugin),
+/// or `null` if the action type has none.
+///
+/// # Arguments
+///
+/// * `action_type` - Action type identifier (e.g. "audio.volume_up")
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// const icon = await invoke<string | null>('get_action_icon', { actionType: 'audio.volume_up' });
+/// if (icon) img.src = convertFileSrc(icon);
+/// ```
+#[tauri::command]
+pub fn get_action_icon(action_type: String, app_handle: AppHandle) -> Option<String> {
+    crate::images::resolve_action_icon(&Action::new(&action_type), &app_handle)
+}
+
+/// List the built-in icons, for an icon picker.
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// const icons = await invoke<BuiltinIcon[]>('list_builtin_icons');
+/// const urls = icons.map(icon => convertFileSrc(icon.path));
+/// ```
+#[tauri::command]
+pub fn list_builtin_icons(app_handle: AppHandle) -> Vec<BuiltinIcon> {
+    crate::images::list_builtin_icons(&app_handle)
+        .into_iter()
+        .map(|(name, path)| BuiltinIcon { name, path })
+        .collect()
+}
+
+/// Change the system-wide hotkey that brings the window to the front.
+///
+/// # Arguments
+///
+/// * `shortcut` - Accelerator like `"CmdOrCtrl+Alt+Shift+D"`, or `null` to disable
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('set_focus_shortcut', { shortcut: 'CmdOrCtrl+Alt+F12' });
+/// ```
+#[tauri::command]
+pub fn set_focus_shortcut(shortcut: Option<String>, state: State<'_, AppState>, app_handle: AppHandle) -> CommandResult<()> {
+    if let Err(e) = crate::shortcut::register_focus_shortcut(&app_handle, shortcut.as_deref()) {
+        // Put the previous shortcut back so a bad binding doesn't leave none at all
+        let previous = state.config.lock().unwrap().app.focus_shortcut.clone();
+        let _ = crate::shortcut::register_focus_shortcut(&app_handle, previous.as_deref());
+        return Err(CommandError::invalid_argument(e));
+    }
+
+    let snapshot = {
+        let mut config = state.config.lock().unwrap();
+        config.app.focus_shortcut = shortcut;
+        config.clone()
+    };
+    storage::save(&snapshot, &app_handle).map_err(CommandError::io)
+}
+
+/// Show a page on the deck, like a navigation button would.
+///
+/// Emits `streamdeck://page-changed` once the deck has been redrawn.
+///
+/// # Arguments
+///
+/// * `index` - Page index
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('set_current_page', { index: 2 });
+/// ```
+#[tauri::command]
+pub fn set_current_page(index: usize, state: State<'_, AppState>, app_handle: AppHandle) -> CommandResult<()> {
+    {
+        let mut config = state.config.lock().unwrap();
+        if index >= config.pages.len() {
+            return Err(CommandError::invalid_page(format!(
+                "Page {} out of range ({} pages)",
+                index,
+                config.pages.len()
+            )));
+        }
+        config.go_to_page(index);
+    }
+
+    crate::images::sync_images_to_device(&state, &app_handle);
+    emit_page_changed(&state, &app_handle);
+    Ok(())
+}
+
+/// Set the page the deck goes back to when left idle on another page.
+///
+/// The setting is saved in the config.
+///
+/// # Arguments
+///
+/// * `home_page` - Page index to return to
+/// * `timeout_ms` - Time without input before returning, or `null` to never return
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('set_auto_home', { homePage: 0, timeoutMs: 60000 });
+/// ```
+#[tauri::command]
+pub fn set_auto_home(
+    home_page: usize,
+    timeout_ms: Option<u64>,
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+) -> CommandResult<()> {
+    if timeout_ms == Some(0) {
+        return Err(CommandError::invalid_argument("Auto-home timeout must be at least 1 ms"));
+    }
+
+    let snapshot = {
+        let mut config = state.config.lock().unwrap();
+        if home_page >= config.pages.len() {
+            return Err(CommandError::invalid_page(format!(
+                "Page {} out of range ({} pages)",
+                home_page,
+                config.pages.len()
+            )));
+        }
+        config.home_page = home_page;
+        config.auto_home_timeout_ms = timeout_ms;
+        config.clone()
+    };
+    storage::save(&snapshot, &app_handle).map_err(CommandError::io)
+}
+
+/// Choose whether closing the window hides it to the tray (deck keeps running)
+/// or quits and clears the deck.
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('set_close_to_tray', { enabled: true });
+/// ```
+#[tauri::command]
+pub fn set_close_to_tray(enabled: bool, state: State<'_, AppState>, app_handle: AppHandle) -> CommandResult<()> {
+    let snapshot = {
+        let mut config = state.config.lock().unwrap();
+        config.app.close_to_tray = enabled;
+        config.clone()
+    };
+    storage::save(&snapshot, &app_handle).map_err(CommandError::io)
+}
+
+/// Profile names for the UI and tray: every saved profile, plus the active one
+/// even if it hasn't been saved yet.
+pub(crate) fn profile_list(state: &AppState, app_handle: &AppHandle) -> Result<Vec<ProfileInfo>, String> {
+    let active = state.config.lock().unwrap().app.active_profile.clone();
+    let mut names = profiles::list_profiles(app_handle)?;
+    if !names.contains(&active) {
+        names.insert(0, active.clone());
+    }
+
+    Ok(names
+        .into_iter()
+        .map(|name| ProfileInfo { active: name == active, name })
+        .collect())
+}
+
+/// Save the current config back to its profile, then load `name` in its place
+/// and apply its device settings. Picking the active profile does nothing.
+///
+/// App settings (window, shortcuts) aren't part of a profile and carry over.
+pub(crate) fn switch_to_profile(name: &str, state: &State<'_, AppState>, app_handle: &AppHandle) -> CommandResult<()> {
+    {
+        let mut config = state.config.lock().unwrap();
+        // Loading it again would throw away unsaved edits for an older snapshot
+        if config.app.active_profile == name {
+            return Ok(());
+        }
+        profiles::save_profile(&config.app.active_profile, &config, app_handle).map_err(CommandError::io)?;
+        let mut next = profiles::load_profile(name, app_handle).map_err(CommandError::not_found)?;
+
+        next.app = config.app.clone();
+        next.app.active_profile = name.to_string();
+        next.preview = config.preview.clone();
+        // Confirmed encodings describe the hardware, not the layout
+        next.encodings = config.encodings.clone();
+        next.current_page = next.current_page.min(next.pages.len().saturating_sub(1));
+
+        storage::save(&next, app_handle).map_err(CommandError::io)?;
+        *config = next;
+    }
+    println!("Switched to profile: {}", name);
+
+    crate::commands::streamdeck::reapply_device_settings(state);
+    crate::images::sync_images_to_device(state, app_handle);
+    emit_page_changed(state, app_handle);
+    let _ = app_handle.emit("streamdeck://profile-changed", ProfileChanged { name: name.to_string() });
+    crate::tray::refresh_menu(app_handle);
+    Ok(())
+}
+
+/// List saved profiles, marking the one the current config belongs to.
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// const profiles = await invoke<ProfileInfo[]>('list_profiles');
+/// const active = profiles.find(p => p.active)?.name;
+/// ```
+#[tauri::command]
+pub fn list_profiles(state: State<'_, AppState>, app_handle: AppHandle) -> CommandResult<Vec<ProfileInfo>> {
+    profile_list(&state, &app_handle).map_err(CommandError::io)
+}
+
+/// Save the current config as a profile and make it the active one.
+///
+/// Saving over an existing profile replaces it.
+///
+/// # Arguments
+///
+/// * `name` - Profile name (also its file name, so no path separators)
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('save_profile', { name: 'Gaming' });
+/// ```
+#[tauri::command]
+pub fn save_profile(name: String, state: State<'_, AppState>, app_handle: AppHandle) -> CommandResult<()> {
+    profiles::check_name(&name).map_err(CommandError::invalid_argument)?;
+
+    {
+        let mut config = state.config.lock().unwrap();
+        let mut snapshot = config.clone();
+        snapshot.app.active_profile = name.clone();
+
+        profiles::save_profile(&name, &snapshot, &app_handle).map_err(CommandError::io)?;
+        storage::save(&snapshot, &app_handle).map_err(CommandError::io)?;
+        *config = snapshot;
+    }
+    println!("Saved profile: {}", name);
+
+    crate::tray::refresh_menu(&app_handle);
+    Ok(())
+}
+
+/// Switch to a saved profile, saving the current config back to its own profile first.
+///
+/// Emits `streamdeck://profile-changed` (`{ name }`) and `streamdeck://page-changed`.
+///
+/// # Arguments
+///
+/// * `name` - Profile name as returned by `list_profiles`
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('switch_profile', { name: 'Work' });
+/// ```
+#[tauri::command]
+pub fn switch_profile(name: String, state: State<'_, AppState>, app_handle: AppHandle) -> CommandResult<()> {
+    switch_to_profile(&name, &state, &app_handle)
+}
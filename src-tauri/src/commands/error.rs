@@ -0,0 +1,115 @@
+//! Command Error Type
+//!
+//! Every Tauri command returns `CommandError` on failure, so the frontend
+//! gets a stable `code` to branch on alongside the human-readable message.
+
+use serde::Serialize;
+
+use crate::hid::device::{ConnectError, ConnectErrorKind};
+
+/// Stable error codes sent to the frontend (serialized as e.g. `"NO_DEVICE"`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// No Stream Deck is connected
+    NoDevice,
+    /// Talking to the device (or enumerating devices) failed
+    DeviceError,
+    /// The deck is open in another program (e.g. the Elgato software)
+    DeviceInUse,
+    /// The OS refused access to the deck
+    PermissionDenied,
+    /// Button position out of range, empty, or already taken
+    InvalidButton,
+    /// Page index out of range
+    InvalidPage,
+    /// Image file missing or not a supported format
+    InvalidImage,
+    /// Some other argument was rejected
+    InvalidArgument,
+    /// The named device, preset, etc. doesn't exist
+    NotFound,
+    /// A destructive command was called without `confirm: true`
+    ConfirmationRequired,
+    /// A whole config was rejected; `details` lists the problems
+    InvalidConfig,
+    /// Reading or writing a file (e.g. the config) failed
+    IoError,
+}
+
+/// Error returned by Tauri commands.
+///
+/// Serialized as `{ "code": "NO_DEVICE", "message": "No device connected" }`,
+/// plus a `details` list for errors made of several problems.
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandError {
+    pub code: ErrorCode,
+    pub message: String,
+    /// Individual problems, when there's more than one thing wrong
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<String>,
+}
+
+impl CommandError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            details: Vec::new(),
+        }
+    }
+
+    pub fn no_device() -> Self {
+        Self::new(ErrorCode::NoDevice, "No device connected")
+    }
+
+    pub fn device(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::DeviceError, message)
+    }
+
+    pub fn invalid_button(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidButton, message)
+    }
+
+    pub fn invalid_page(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidPage, message)
+    }
+
+    pub fn invalid_image(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidImage, message)
+    }
+
+    pub fn invalid_argument(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidArgument, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    pub fn io(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::IoError, message)
+    }
+
+    pub fn invalid_config(problems: Vec<String>) -> Self {
+        Self {
+            details: problems.clone(),
+            ..Self::new(ErrorCode::InvalidConfig, format!("Invalid config: {}", problems.join("; ")))
+        }
+    }
+}
+
+impl From<ConnectError> for CommandError {
+    fn from(error: ConnectError) -> Self {
+        let code = match error.kind {
+            ConnectErrorKind::InUse => ErrorCode::DeviceInUse,
+            ConnectErrorKind::PermissionDenied => ErrorCode::PermissionDenied,
+            ConnectErrorKind::NotFound => ErrorCode::NotFound,
+            ConnectErrorKind::Other => ErrorCode::DeviceError,
+        };
+        Self::new(code, error.message)
+    }
+}
+
+/// Shorthand for command return types
+pub type CommandResult<T> = Result<T, CommandError>;
@@ -3,4 +3,7 @@
 //! This module contains all the Tauri commands that can be invoked
 //! from the frontend via the `invoke()` function.
 
+pub mod audio;
+pub mod config;
+pub mod error;
 pub mod streamdeck;
@@ -3,25 +3,113 @@
 //! This module provides the Tauri command handlers for Stream Deck operations.
 //! These commands are invoked from the TypeScript frontend via `invoke()`.
 
-use tauri::{AppHandle, Manager, State};
+use std::io::Cursor;
+use std::sync::atomic::Ordering;
 
-use crate::hid::device::{DeviceInfo, StreamDeck};
+use base64::Engine;
+use image::ImageFormat;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use super::error::{CommandError, CommandResult};
+use crate::hid::constants::{IMAGE_SIZE, MAX_BRIGHTNESS, STREAM_DECK_MK2};
+use crate::hid::device::{ConnectError, DeviceInfo, HidDeviceSummary, ImageEncoding, IoStats, StreamDeck};
 use crate::AppState;
 
+/// Connection status returned by `is_connected` and emitted on connect/disconnect.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionStatus {
+    /// Whether a Stream Deck is currently connected
+    pub connected: bool,
+    /// Serial number of the connected device, if known
+    pub serial_number: Option<String>,
+}
+
 /// List all connected Stream Deck devices.
 ///
+/// # Arguments
+///
+/// * `include_unsupported` - Also list Elgato devices with an unsupported PID
+///   (returned with `supported: false`). Defaults to false.
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// const devices = await invoke<DeviceInfo[]>('list_devices', { includeUnsupported: true });
+/// ```
+#[tauri::command]
+pub fn list_devices(include_unsupported: Option<bool>, state: State<'_, AppState>) -> CommandResult<Vec<DeviceInfo>> {
+    let mut devices = StreamDeck::discover(include_unsupported.unwrap_or(false)).map_err(CommandError::device)?;
+
+    let config = state.config.lock().unwrap();
+    for device in &mut devices {
+        device.nickname = config.nicknames.get(device.identity()).cloned();
+    }
+
+    Ok(devices)
+}
+
+/// Give a deck a nickname so identical models can be told apart.
+///
+/// The nickname is stored by serial number when the device has one,
+/// so it survives moving the deck to another USB port.
+///
+/// # Arguments
+///
+/// * `device_path` - The USB device path from `DeviceInfo.path`
+/// * `nickname` - New nickname, or `null` to remove it
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('set_device_nickname', { devicePath: device.path, nickname: 'Left Deck' });
+/// ```
+#[tauri::command]
+pub fn set_device_nickname(
+    device_path: String,
+    nickname: Option<String>,
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+) -> CommandResult<()> {
+    let device = StreamDeck::discover(true)
+        .map_err(CommandError::device)?
+        .into_iter()
+        .find(|device| device.path == device_path)
+        .ok_or_else(|| CommandError::not_found(format!("Device not found: {}", device_path)))?;
+
+    let snapshot = {
+        let mut config = state.config.lock().unwrap();
+        match nickname.filter(|name| !name.trim().is_empty()) {
+            Some(name) => config.nicknames.insert(device.identity().to_string(), name),
+            None => config.nicknames.remove(device.identity()),
+        };
+        config.clone()
+    };
+
+    crate::config::storage::save(&snapshot, &app_handle).map_err(CommandError::io)
+}
+
+/// List every HID device the OS can see, without the Elgato filter.
+///
+/// Useful for diagnosing permission problems or new/unsupported PIDs.
+///
 /// # Frontend Usage
 ///
 /// ```typescript
-/// const devices = await invoke<DeviceInfo[]>('list_devices');
+/// const all = await invoke<HidDeviceSummary[]>('list_all_hid_devices');
 /// ```
 #[tauri::command]
-pub fn list_devices() -> Result<Vec<DeviceInfo>, String> {
-    StreamDeck::discover()
+pub fn list_all_hid_devices() -> CommandResult<Vec<HidDeviceSummary>> {
+    StreamDeck::list_all_hid_devices().map_err(CommandError::device)
 }
 
 /// Connect to a Stream Deck device.
 ///
+/// Fails with `DEVICE_IN_USE` when another program (usually the Elgato
+/// software) has the deck open, `PERMISSION_DENIED` when the OS won't allow
+/// access, and `NOT_FOUND` when the deck is gone. Calling it again once the
+/// other program is closed is enough to retry.
+///
 /// # Arguments
 ///
 /// * `device_path` - The USB device path from `DeviceInfo.path`
@@ -32,8 +120,58 @@ pub fn list_devices() -> Result<Vec<DeviceInfo>, String> {
 /// await invoke('connect_device', { devicePath: '/dev/hidraw0' });
 /// ```
 #[tauri::command]
-pub fn connect_device(device_path: String, state: State<'_, AppState>, app_handle: AppHandle) -> Result<(), String> {
-    let streamdeck = StreamDeck::connect(&device_path)?;
+pub fn connect_device(device_path: String, state: State<'_, AppState>, app_handle: AppHandle) -> CommandResult<()> {
+    start_device(&device_path, &state, app_handle).map_err(CommandError::from)
+}
+
+/// Open a deck with the device settings from the config applied.
+fn open_deck(device_path: &str, state: &State<'_, AppState>) -> Result<StreamDeck, ConnectError> {
+    let mut streamdeck = StreamDeck::connect(device_path)?;
+    apply_device_settings(&mut streamdeck, state);
+    Ok(streamdeck)
+}
+
+/// Apply the config's device settings and the tracked brightness to a deck
+fn apply_device_settings(streamdeck: &mut StreamDeck, state: &AppState) {
+    let config = state.config.lock().unwrap();
+    streamdeck.set_rotate_images(config.device.rotate_images);
+    streamdeck.set_image_padding(config.device.padding);
+    streamdeck.set_encode_cache_size(config.device.encode_cache_size);
+    streamdeck.set_image_command_override(config.device.image_command);
+    streamdeck.set_encoding_override(config.encodings.get(streamdeck.get_device_info().identity()).copied());
+    drop(config);
+
+    // A reopened deck doesn't keep its brightness, so re-apply the one we track
+    if let Err(e) = streamdeck.set_brightness(state.brightness.load(Ordering::Relaxed)) {
+        eprintln!("{}", e);
+    }
+}
+
+/// Apply the device settings of a config that was just swapped in (another
+/// profile, say) to the connected deck and its mirrors, the same way as on
+/// connect. Redrawing is left to the caller.
+pub(crate) fn reapply_device_settings(state: &AppState) {
+    if let Some(streamdeck) = state.streamdeck.lock().unwrap().as_mut() {
+        apply_device_settings(streamdeck, state);
+    }
+    for mirror in state.mirrors.lock().unwrap().iter_mut() {
+        apply_device_settings(mirror, state);
+    }
+}
+
+/// Open a deck and make it the connected one, then draw the current page
+/// and start polling it for presses.
+pub(crate) fn start_device(
+    device_path: &str,
+    state: &State<'_, AppState>,
+    app_handle: AppHandle,
+) -> Result<(), ConnectError> {
+    let streamdeck = open_deck(device_path, state)?;
+    let serial_number = streamdeck.get_serial_number().map(String::from);
+    let probe_encoding = {
+        let config = state.config.lock().unwrap();
+        config.device.probe_encoding && !config.encodings.contains_key(streamdeck.get_device_info().identity())
+    };
 
     //Lock the mutex, get mutable acces to the Option inside
     let mut guard = state.streamdeck.lock().unwrap();
@@ -41,8 +179,24 @@ pub fn connect_device(device_path: String, state: State<'_, AppState>, app_handl
 
     drop(guard); // Release the lock before spawning thread
 
-    // Sync button images to the Stream Deck LCD
-    crate::images::sync_images_to_device(&state, &app_handle);
+    let _ = app_handle.emit(
+        "streamdeck://connected",
+        ConnectionStatus { connected: true, serial_number },
+    );
+
+    // Sync button images to the Stream Deck LCD, unless the test pattern
+    // needs checking first (confirming it syncs them)
+    let probing = probe_encoding
+        && match show_encoding_probe(state, &app_handle) {
+            Ok(_) => true,
+            Err(e) => {
+                eprintln!("Encoding probe failed: {}", e);
+                false
+            }
+        };
+    if !probing {
+        crate::images::sync_images_to_device(state, &app_handle);
+    }
 
     // Start the button polling loop in a background thread
     crate::polling::start_polling(app_handle);
@@ -60,7 +214,7 @@ pub fn connect_device(device_path: String, state: State<'_, AppState>, app_handl
 /// await invoke('disconnect_device');
 /// ```
 #[tauri::command]
-pub fn disconnect_device(state: State<'_, AppState>) -> Result<(), String> {
+pub fn disconnect_device(state: State<'_, AppState>, app_handle: AppHandle) -> CommandResult<()> {
     let mut guard = state.streamdeck.lock().unwrap();
 
     // Clear all button images before disconnecting
@@ -70,7 +224,66 @@ pub fn disconnect_device(state: State<'_, AppState>) -> Result<(), String> {
         }
     }
 
+    let was_connected = guard.is_some();
     *guard = None; //setting the option within (aka stream deck state) to none
+    drop(guard);
+
+    // Mirrors have nothing to mirror without the main deck
+    for mut mirror in state.mirrors.lock().unwrap().drain(..) {
+        if let Err(e) = mirror.clear_all_buttons() {
+            eprintln!("Warning: Failed to clear mirror buttons on disconnect: {}", e);
+        }
+    }
+
+    if was_connected {
+        let _ = app_handle.emit(
+            "streamdeck://disconnected",
+            ConnectionStatus { connected: false, serial_number: None },
+        );
+    }
+    Ok(())
+}
+
+/// Check whether a Stream Deck is currently connected.
+///
+/// # Returns
+///
+/// The connection status, including the device serial when connected.
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// const { connected, serial_number } = await invoke<ConnectionStatus>('is_connected');
+/// ```
+#[tauri::command]
+pub fn is_connected(state: State<'_, AppState>) -> ConnectionStatus {
+    let guard = state.streamdeck.lock().unwrap();
+
+    ConnectionStatus {
+        connected: guard.is_some(),
+        serial_number: guard
+            .as_ref()
+            .and_then(|streamdeck| streamdeck.get_serial_number().map(String::from)),
+    }
+}
+
+/// Force a full redraw of the current page.
+///
+/// Blanks every button and re-sends all images without using the encode
+/// cache. Use it when the deck shows stale images, e.g. after the PC wakes up.
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('redraw_deck');
+/// ```
+#[tauri::command]
+pub fn redraw_deck(state: State<'_, AppState>, app_handle: AppHandle) -> CommandResult<()> {
+    if state.streamdeck.lock().unwrap().is_none() {
+        return Err(CommandError::no_device());
+    }
+
+    crate::images::redraw_deck(&state, &app_handle);
     Ok(())
 }
 
@@ -87,18 +300,18 @@ pub fn disconnect_device(state: State<'_, AppState>) -> Result<(), String> {
 /// const states = await invoke<boolean[]>('get_button_state');
 /// ```
 #[tauri::command]
-pub fn get_button_state(state: State<'_, AppState>) -> Result<Vec<bool>, String> {
+pub fn get_button_state(state: State<'_, AppState>) -> CommandResult<Vec<bool>> {
     let mut guard = state.streamdeck.lock().unwrap();
 
     match &mut *guard {
         Some(streamdeck) => {
             //streamdeck is now a variable holding &mut of the option (StreamDeck)
-            let buttons = streamdeck.read_buttons()?;
+            let buttons = streamdeck.read_buttons().map_err(CommandError::device)?;
             Ok(buttons.to_vec())
         }
         None => {
             //no device connected
-            Err("No device connected".to_string())
+            Err(CommandError::no_device())
         }
     }
 }
@@ -121,4 +334,681 @@ pub fn get_button_state(state: State<'_, AppState>) -> Result<Vec<bool>, String>
 #[tauri::command]
 pub fn get_button_images(state: State<'_, AppState>, app_handle: AppHandle) -> Vec<Option<String>> {
     crate::images::get_current_page_images(&state, &app_handle)
-}
\ No newline at end of file
+}
+
+/// Live preview image returned by `get_deck_preview`.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeckPreview {
+    /// The encoded image, base64
+    pub data: String,
+    /// `"image/png"`, or `"image/jpeg"` when `preview.quality` is set
+    pub mime_type: &'static str,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Get a live preview of what the deck is currently showing.
+///
+/// Composites the last image sent to each button into a single grid image,
+/// encoded as set with `set_preview_quality` (a full-size PNG by default).
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// const preview = await invoke<DeckPreview>('get_deck_preview');
+/// img.src = `data:${preview.mime_type};base64,${preview.data}`;
+/// ```
+#[tauri::command]
+pub fn get_deck_preview(state: State<'_, AppState>) -> CommandResult<DeckPreview> {
+    let guard = state.streamdeck.lock().unwrap();
+
+    let streamdeck = guard.as_ref().ok_or_else(CommandError::no_device)?;
+    let mut preview = crate::images::compose_deck_preview(streamdeck);
+    drop(guard);
+
+    let settings = state.config.lock().unwrap().preview.clone();
+    if let Some(max_width) = settings.max_width.filter(|&width| width < preview.width()) {
+        let height = (preview.height() as u64 * max_width as u64 / preview.width() as u64).max(1) as u32;
+        preview = image::imageops::resize(&preview, max_width, height, image::imageops::FilterType::Triangle);
+    }
+
+    let mut buffer = Cursor::new(Vec::new());
+    let mime_type = match settings.quality {
+        Some(quality) => {
+            image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buffer, quality)
+                .encode_image(&preview)
+                .map_err(|e| CommandError::io(format!("Failed to encode preview: {}", e)))?;
+            "image/jpeg"
+        }
+        None => {
+            preview
+                .write_to(&mut buffer, ImageFormat::Png)
+                .map_err(|e| CommandError::io(format!("Failed to encode preview: {}", e)))?;
+            "image/png"
+        }
+    };
+
+    Ok(DeckPreview {
+        data: base64::engine::general_purpose::STANDARD.encode(buffer.into_inner()),
+        mime_type,
+        width: preview.width(),
+        height: preview.height(),
+    })
+}
+
+/// Set how the live deck preview is encoded, e.g. a small low-quality JPEG
+/// for streaming it over a slow connection.
+///
+/// The setting is saved in the config.
+///
+/// # Arguments
+///
+/// * `quality` - JPEG quality (1-100), or `null` for a lossless PNG
+/// * `max_width` - Largest preview width in pixels, or `null` for full size
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('set_preview_quality', { quality: 40, maxWidth: 240 });
+/// ```
+#[tauri::command]
+pub fn set_preview_quality(
+    quality: Option<u8>,
+    max_width: Option<u32>,
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+) -> CommandResult<()> {
+    if quality.is_some_and(|quality| !(1..=100).contains(&quality)) {
+        return Err(CommandError::invalid_argument("Preview quality must be between 1 and 100"));
+    }
+    if max_width == Some(0) {
+        return Err(CommandError::invalid_argument("Preview width must be at least 1 pixel"));
+    }
+
+    let snapshot = {
+        let mut config = state.config.lock().unwrap();
+        config.preview.quality = quality;
+        config.preview.max_width = max_width;
+        config.clone()
+    };
+    crate::config::storage::save(&snapshot, &app_handle).map_err(CommandError::io)
+}
+
+/// Save a button's final rendered image as a PNG.
+///
+/// Runs the same pipeline used for the device (image or label, overlay,
+/// padding, highlight, dimming) but writes the result before rotation,
+/// so generated icons can be reused elsewhere or checked for problems.
+/// Uses the connected deck's button size, or 72px with no deck connected.
+///
+/// # Arguments
+///
+/// * `page` - Page index
+/// * `button` - Button position
+/// * `out_path` - Where to write the PNG
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('export_button_image', { page: 0, button: 3, outPath: 'C:/icons/weather.png' });
+/// ```
+#[tauri::command]
+pub fn export_button_image(
+    page: usize,
+    button: usize,
+    out_path: String,
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+) -> CommandResult<()> {
+    let (button_config, active, padding) = {
+        let config = state.config.lock().unwrap();
+        let num_pages = config.pages.len();
+        let target = config
+            .pages
+            .get(page)
+            .ok_or_else(|| CommandError::invalid_page(format!("Page {} out of range ({} pages)", page, num_pages)))?;
+        let button_config = target
+            .buttons
+            .get(&button)
+            .cloned()
+            .ok_or_else(|| CommandError::invalid_button(format!("No button at position {}", button)))?;
+
+        let active = button_config.action.target_page(&config.pages) == Some(page);
+        let padding = button_config.padding.or(config.device.padding);
+        (button_config, active, padding)
+    };
+
+    let image_size = state
+        .streamdeck
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map_or(IMAGE_SIZE, |streamdeck| streamdeck.model().image_size);
+
+    let img = crate::images::render_button_image(&button_config, active, padding, image_size, &app_handle)
+        .map_err(CommandError::invalid_image)?;
+
+    img.save_with_format(&out_path, ImageFormat::Png)
+        .map_err(|e| CommandError::io(format!("Failed to write {}: {}", out_path, e)))
+}
+
+/// Save an annotated picture of a page's layout as a PNG, for sharing or
+/// documentation: each button's image with its label and action name
+/// underneath, laid out like the connected deck (an MK.2 with none connected).
+///
+/// # Arguments
+///
+/// * `page` - Page index, or `null` for the current page
+/// * `out_path` - Where to write the PNG
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('export_layout_image', { page: null, outPath: 'C:/Users/me/Pictures/layout.png' });
+/// ```
+#[tauri::command]
+pub fn export_layout_image(
+    page: Option<usize>,
+    out_path: String,
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+) -> CommandResult<()> {
+    let config = state.config.lock().unwrap().clone();
+    let page = page.unwrap_or(config.current_page);
+    if page >= config.pages.len() {
+        return Err(CommandError::invalid_page(format!("Page {} out of range ({} pages)", page, config.pages.len())));
+    }
+
+    let model = state
+        .streamdeck
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map_or(&STREAM_DECK_MK2, |streamdeck| streamdeck.model());
+
+    let sheet = crate::images::render_layout_sheet(&config, page, model, &app_handle).map_err(CommandError::io)?;
+    sheet
+        .save_with_format(&out_path, ImageFormat::Png)
+        .map_err(|e| CommandError::io(format!("Failed to write {}: {}", out_path, e)))
+}
+
+/// Set the image the device shows while idle/in standby.
+///
+/// Models without standby image support skip this silently.
+///
+/// # Arguments
+///
+/// * `image_path` - Path to the image file (PNG, JPEG, etc.)
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('set_standby_image', { imagePath: 'C:/images/logo.png' });
+/// ```
+#[tauri::command]
+pub fn set_standby_image(image_path: String, state: State<'_, AppState>) -> CommandResult<()> {
+    // Check the file up front so a bad image isn't reported as a device error
+    image::image_dimensions(&image_path)
+        .map_err(|e| CommandError::invalid_image(format!("Failed to load image: {}", e)))?;
+
+    let mut guard = state.streamdeck.lock().unwrap();
+
+    match &mut *guard {
+        Some(streamdeck) => streamdeck.set_standby_image(&image_path).map_err(CommandError::device),
+        None => Err(CommandError::no_device()),
+    }
+}
+
+/// Images whose shorter side is under the button size divided by this look pixelated
+const PIXELATED_DIVISOR: u32 = 2;
+
+/// Images with a longer side than this make every encode noticeably slower
+const LARGE_IMAGE_SIDE: u32 = 2048;
+
+/// What `validate_image` found out about an image file
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageValidation {
+    pub width: u32,
+    pub height: u32,
+    /// Detected format, as its usual file extension (e.g. "png", "jpg")
+    pub format: String,
+    /// Why the image may not look or perform well on a button, if anything
+    pub warning: Option<String>,
+}
+
+/// Check that an image file can be shown on a button, before assigning it.
+///
+/// The whole image is decoded, so truncated or corrupt files are caught too.
+/// Images much smaller than the button (pixelated) or very large (slow to
+/// encode) come back with a warning rather than an error.
+///
+/// # Arguments
+///
+/// * `path` - Path to the image file
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// const info = await invoke<ImageValidation>('validate_image', { path });
+/// if (info.warning) showWarning(info.warning);
+/// ```
+#[tauri::command]
+pub fn validate_image(path: String, state: State<'_, AppState>) -> CommandResult<ImageValidation> {
+    let reader = image::ImageReader::open(&path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|e| CommandError::invalid_image(format!("Failed to open {}: {}", path, e)))?;
+    let format = reader
+        .format()
+        .ok_or_else(|| CommandError::invalid_image(format!("{} is not a supported image format", path)))?;
+    let img = reader
+        .decode()
+        .map_err(|e| CommandError::invalid_image(format!("Failed to decode {}: {}", path, e)))?;
+
+    let button_size = state
+        .streamdeck
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map_or(IMAGE_SIZE, |streamdeck| streamdeck.model().image_size);
+    let (width, height) = (img.width(), img.height());
+
+    let warning = if width.min(height) < button_size / PIXELATED_DIVISOR {
+        Some(format!(
+            "{}x{} is much smaller than the {}x{} button and will look pixelated",
+            width, height, button_size, button_size
+        ))
+    } else if width.max(height) > LARGE_IMAGE_SIDE {
+        Some(format!(
+            "{}x{} is very large and slows down every redraw; resize it to around {}x{}",
+            width, height, button_size, button_size
+        ))
+    } else {
+        None
+    };
+
+    Ok(ImageValidation {
+        width,
+        height,
+        format: format.extensions_str().first().unwrap_or(&"unknown").to_string(),
+        warning,
+    })
+}
+
+/// Get the connected deck's HID traffic and encoding counters, for telling
+/// whether slow updates are encoding-bound or HID-bound.
+///
+/// Counts run from connecting, or from the last call with `reset: true`.
+///
+/// # Arguments
+///
+/// * `reset` - Zero the counters after reading them (defaults to false)
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('get_io_stats', { reset: true });
+/// // ...switch pages a few times...
+/// const stats = await invoke<IoStats>('get_io_stats');
+/// console.log(`${stats.packets_sent} packets, ${stats.images_encoded} encodes`);
+/// ```
+#[tauri::command]
+pub fn get_io_stats(reset: Option<bool>, state: State<'_, AppState>) -> CommandResult<IoStats> {
+    let mut guard = state.streamdeck.lock().unwrap();
+    let streamdeck = guard.as_mut().ok_or_else(CommandError::no_device)?;
+
+    let stats = streamdeck.io_stats();
+    if reset.unwrap_or(false) {
+        streamdeck.reset_io_stats();
+    }
+    Ok(stats)
+}
+
+/// Payload of the `streamdeck://brightness-changed` event
+#[derive(Debug, Clone, Serialize)]
+pub struct BrightnessChanged {
+    /// New brightness, 0-100
+    pub brightness: u8,
+}
+
+/// Get the deck brightness.
+///
+/// The hardware can't report its brightness, so this is the last value set
+/// (100 until changed).
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// const brightness = await invoke<number>('get_brightness');
+/// ```
+#[tauri::command]
+pub fn get_brightness(state: State<'_, AppState>) -> u8 {
+    state.brightness.load(Ordering::Relaxed)
+}
+
+/// Set the brightness of the connected deck and any mirrors.
+///
+/// The value is kept while no deck is connected and applied on connect, and
+/// restored on the next launch.
+/// Emits `streamdeck://brightness-changed` with the clamped value.
+///
+/// # Arguments
+///
+/// * `brightness` - Brightness from 0 (off) to 100 (full); higher values are clamped
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('set_brightness', { brightness: 60 });
+/// ```
+#[tauri::command]
+pub fn set_brightness(brightness: u8, state: State<'_, AppState>, app_handle: AppHandle) -> CommandResult<()> {
+    apply_brightness(brightness, &state, &app_handle).map_err(CommandError::device)
+}
+
+/// Set and remember the brightness of the connected deck and its mirrors (also
+/// across restarts), and tell the frontend. Also used by brightness actions.
+pub(crate) fn apply_brightness(brightness: u8, state: &AppState, app_handle: &AppHandle) -> Result<(), String> {
+    let brightness = brightness.min(MAX_BRIGHTNESS);
+    state.brightness.store(brightness, Ordering::Relaxed);
+
+    if let Some(ref mut streamdeck) = *state.streamdeck.lock().unwrap() {
+        streamdeck.set_brightness(brightness)?;
+    }
+    for mirror in state.mirrors.lock().unwrap().iter_mut() {
+        if let Err(e) = mirror.set_brightness(brightness) {
+            eprintln!("Mirror deck: {}", e);
+        }
+    }
+
+    let _ = app_handle.emit("streamdeck://brightness-changed", BrightnessChanged { brightness });
+    crate::polling::save_runtime_state(state, app_handle);
+    Ok(())
+}
+
+/// Force JPEG or BMP encoding for button images, bypassing model detection.
+///
+/// Intended for debugging image corruption on new or mis-detected models.
+/// Affects images sent after the call; on reconnect the deck goes back to its
+/// confirmed encoding (see `confirm_encoding`) or the detected one.
+///
+/// # Arguments
+///
+/// * `encoding` - `"jpeg"`, `"bmp"`, or `null` to restore auto-detection
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('set_encoding_override', { encoding: 'bmp' });
+/// ```
+#[tauri::command]
+pub fn set_encoding_override(encoding: Option<ImageEncoding>, state: State<'_, AppState>) -> CommandResult<()> {
+    let mut guard = state.streamdeck.lock().unwrap();
+
+    match &mut *guard {
+        Some(streamdeck) => {
+            streamdeck.set_encoding_override(encoding);
+            Ok(())
+        }
+        None => Err(CommandError::no_device()),
+    }
+}
+
+/// Emitted when the encoding test pattern is drawn, so the UI can ask whether it looks right.
+#[derive(Debug, Clone, Serialize)]
+pub struct EncodingProbe {
+    /// Encoding the test pattern was sent with
+    pub encoding: ImageEncoding,
+}
+
+/// Draw the encoding test pattern on button 0 with the deck's current encoding.
+fn show_encoding_probe(state: &State<'_, AppState>, app_handle: &AppHandle) -> Result<ImageEncoding, String> {
+    let mut guard = state.streamdeck.lock().unwrap();
+    let streamdeck = guard.as_mut().ok_or("No device connected")?;
+
+    let pattern = crate::images::encoding_test_pattern(streamdeck.model().image_size);
+    streamdeck.set_button_image_from_data(0, pattern)?;
+    let encoding = streamdeck.image_encoding();
+    drop(guard);
+
+    let _ = app_handle.emit("streamdeck://encoding-probe", EncodingProbe { encoding });
+    Ok(encoding)
+}
+
+/// Draw a test pattern on button 0 to check the image encoding by eye.
+///
+/// The pattern is red, green, blue and white quadrants. Follow up with
+/// `confirm_encoding`. Also runs on connect when `device.probe_encoding` is
+/// set and the deck has no confirmed encoding yet.
+///
+/// Returns the encoding the pattern was sent with.
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// const encoding = await invoke<'jpeg' | 'bmp'>('probe_encoding');
+/// ```
+#[tauri::command]
+pub fn probe_encoding(state: State<'_, AppState>, app_handle: AppHandle) -> CommandResult<ImageEncoding> {
+    if state.streamdeck.lock().unwrap().is_none() {
+        return Err(CommandError::no_device());
+    }
+    show_encoding_probe(&state, &app_handle).map_err(CommandError::device)
+}
+
+/// Answer the encoding probe.
+///
+/// If the pattern looked right, the encoding is remembered for this deck
+/// (by serial number) and used on every connect, and the page is redrawn.
+/// Otherwise the other encoding is tried and the pattern drawn again. If the
+/// pattern can't be drawn with the other encoding, the deck stays on the
+/// current one and the error is returned.
+///
+/// Returns the encoding now in use.
+///
+/// # Arguments
+///
+/// * `correct` - Whether button 0 showed four clean colored quadrants
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// const next = await invoke<'jpeg' | 'bmp'>('confirm_encoding', { correct: false });
+/// ```
+#[tauri::command]
+pub fn confirm_encoding(correct: bool, state: State<'_, AppState>, app_handle: AppHandle) -> CommandResult<ImageEncoding> {
+    let mut guard = state.streamdeck.lock().unwrap();
+    let streamdeck = guard.as_mut().ok_or_else(CommandError::no_device)?;
+    let encoding = streamdeck.image_encoding();
+
+    if !correct {
+        let other = match encoding {
+            ImageEncoding::Jpeg => ImageEncoding::Bmp,
+            ImageEncoding::Bmp => ImageEncoding::Jpeg,
+        };
+        let previous_override = streamdeck.encoding_override();
+        streamdeck.set_encoding_override(Some(other));
+        drop(guard);
+
+        return show_encoding_probe(&state, &app_handle).map_err(|e| {
+            if let Some(streamdeck) = state.streamdeck.lock().unwrap().as_mut() {
+                streamdeck.set_encoding_override(previous_override);
+            }
+            CommandError::device(format!(
+                "Couldn't draw the test pattern as {:?}, staying on {:?}: {}",
+                other, encoding, e
+            ))
+        });
+    }
+
+    let identity = streamdeck.get_device_info().identity().to_string();
+    drop(guard);
+
+    let snapshot = {
+        let mut config = state.config.lock().unwrap();
+        config.encodings.insert(identity, encoding);
+        config.clone()
+    };
+    crate::config::storage::save(&snapshot, &app_handle).map_err(CommandError::io)?;
+
+    crate::images::sync_images_to_device(&state, &app_handle);
+    Ok(encoding)
+}
+
+/// Enable or disable the 180° image rotation (for pre-rotated clone firmware).
+///
+/// The setting is saved in the config and applied on every connect. Models
+/// that show images the right way up (the Stream Deck +) are never rotated.
+///
+/// # Arguments
+///
+/// * `rotate` - False if images show up upside down on this device
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('set_rotate_images', { rotate: false });
+/// ```
+#[tauri::command]
+pub fn set_rotate_images(rotate: bool, state: State<'_, AppState>, app_handle: AppHandle) -> CommandResult<()> {
+    let snapshot = {
+        let mut config = state.config.lock().unwrap();
+        config.device.rotate_images = rotate;
+        config.clone()
+    };
+    crate::config::storage::save(&snapshot, &app_handle).map_err(CommandError::io)?;
+
+    if let Some(streamdeck) = state.streamdeck.lock().unwrap().as_mut() {
+        streamdeck.set_rotate_images(rotate);
+    }
+    for mirror in state.mirrors.lock().unwrap().iter_mut() {
+        mirror.set_rotate_images(rotate);
+    }
+
+    // Redraw so the change is visible straight away
+    crate::images::sync_images_to_device(&state, &app_handle);
+    Ok(())
+}
+
+/// Override the button image command byte for MK.2-style decks, for a unit
+/// whose firmware expects something other than the standard one.
+///
+/// The setting is saved in the config and applied on every connect.
+///
+/// # Arguments
+///
+/// * `command` - Command byte (the default is `0x07`), or `null` to go back to it
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('set_image_command', { command: null });
+/// ```
+#[tauri::command]
+pub fn set_image_command(command: Option<u8>, state: State<'_, AppState>, app_handle: AppHandle) -> CommandResult<()> {
+    let snapshot = {
+        let mut config = state.config.lock().unwrap();
+        config.device.image_command = command;
+        config.clone()
+    };
+    crate::config::storage::save(&snapshot, &app_handle).map_err(CommandError::io)?;
+
+    if let Some(streamdeck) = state.streamdeck.lock().unwrap().as_mut() {
+        streamdeck.set_image_command_override(command);
+    }
+    for mirror in state.mirrors.lock().unwrap().iter_mut() {
+        mirror.set_image_command_override(command);
+    }
+
+    // Redraw so the change is visible straight away
+    crate::images::sync_images_to_device(&state, &app_handle);
+    Ok(())
+}
+
+/// Connect another deck as a mirror of the connected one.
+///
+/// The mirror shows the same images and follows page changes. Its presses
+/// run the connected deck's actions if `set_mirror_presses` is enabled and
+/// are ignored otherwise. Mirrors are disconnected along with the main deck.
+///
+/// # Arguments
+///
+/// * `device_path` - The USB device path from `DeviceInfo.path`
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('add_mirror_device', { devicePath: devices[1].path });
+/// ```
+#[tauri::command]
+pub fn add_mirror_device(device_path: String, state: State<'_, AppState>, app_handle: AppHandle) -> CommandResult<()> {
+    {
+        let guard = state.streamdeck.lock().unwrap();
+        let primary = guard.as_ref().ok_or_else(CommandError::no_device)?;
+        if primary.get_device_info().path == device_path {
+            return Err(CommandError::invalid_argument("Can't mirror the connected deck to itself"));
+        }
+    }
+    if state.mirrors.lock().unwrap().iter().any(|mirror| mirror.get_device_info().path == device_path) {
+        return Err(CommandError::invalid_argument(format!("Already mirroring to {}", device_path)));
+    }
+
+    start_mirror(&device_path, &state, &app_handle).map_err(CommandError::from)
+}
+
+/// Open a deck as a mirror and draw the current page on it.
+pub(crate) fn start_mirror(
+    device_path: &str,
+    state: &State<'_, AppState>,
+    app_handle: &AppHandle,
+) -> Result<(), ConnectError> {
+    let mirror = open_deck(device_path, state)?;
+    state.mirrors.lock().unwrap().push(mirror);
+
+    crate::images::sync_images_to_device(state, app_handle);
+    Ok(())
+}
+
+/// Stop mirroring to a deck, clearing its buttons.
+///
+/// # Arguments
+///
+/// * `device_path` - The mirror's USB device path
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('remove_mirror_device', { devicePath: devices[1].path });
+/// ```
+#[tauri::command]
+pub fn remove_mirror_device(device_path: String, state: State<'_, AppState>) -> CommandResult<()> {
+    let mut mirrors = state.mirrors.lock().unwrap();
+    let index = mirrors
+        .iter()
+        .position(|mirror| mirror.get_device_info().path == device_path)
+        .ok_or_else(|| CommandError::not_found(format!("Not mirroring to {}", device_path)))?;
+
+    let mut mirror = mirrors.remove(index);
+    if let Err(e) = mirror.clear_all_buttons() {
+        eprintln!("Warning: Failed to clear mirror buttons: {}", e);
+    }
+    Ok(())
+}
+
+/// Choose whether presses on mirror decks run the connected deck's actions.
+///
+/// Saved in the config. When disabled, mirrors are display-only.
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('set_mirror_presses', { enabled: true });
+/// ```
+#[tauri::command]
+pub fn set_mirror_presses(enabled: bool, state: State<'_, AppState>, app_handle: AppHandle) -> CommandResult<()> {
+    let snapshot = {
+        let mut config = state.config.lock().unwrap();
+        config.device.mirror_presses = enabled;
+        config.clone()
+    };
+    crate::config::storage::save(&snapshot, &app_handle).map_err(CommandError::io)
+}
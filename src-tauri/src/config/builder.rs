@@ -0,0 +1,231 @@
+//! Config Builder
+//!
+//! Fluent API for constructing a `Config` in code (defaults, presets, fixtures).
+//!
+//! ```ignore
+//! let config = ConfigBuilder::new()
+//!     .page("Main")
+//!     .button(0, Action::new("audio.volume_up")).label("Vol +")
+//!     .button(14, Action::new("navigation.next_page"))
+//!     .build()?;
+//! ```
+
+use std::collections::HashMap;
+
+use super::{
+    Action, AppSettings, ButtonConfig, ButtonImage, Combo, Config, DeviceSettings, DialConfig, Page, PreviewSettings,
+    ToggleImages,
+};
+use crate::hid::constants::{BUTTON_COUNT, DIAL_COUNT};
+
+/// Builds a `Config` page by page, validating it in `build()`
+pub struct ConfigBuilder {
+    pages: Vec<Page>,
+    current_page: usize,
+    /// Button that `label()`, `image()` etc. apply to
+    last_button: Option<usize>,
+    combos: Vec<Combo>,
+    home_page: usize,
+    auto_home_timeout_ms: Option<u64>,
+    /// Problems found while building, reported together by `build()`
+    errors: Vec<String>,
+}
+
+impl ConfigBuilder {
+    pub fn new() -> Self {
+        Self {
+            pages: Vec::new(),
+            current_page: 0,
+            last_button: None,
+            combos: Vec::new(),
+            home_page: 0,
+            auto_home_timeout_ms: None,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Start a new page; following buttons and dials are added to it
+    pub fn page(mut self, name: &str) -> Self {
+        self.pages.push(Page {
+            name: name.to_string(),
+            buttons: HashMap::new(),
+            dials: HashMap::new(),
+        });
+        self.last_button = None;
+        self
+    }
+
+    /// Add a button to the current page
+    pub fn button(mut self, index: usize, action: Action) -> Self {
+        let Some(page) = self.pages.last_mut() else {
+            self.errors.push(format!("Button {} added before any page", index));
+            return self;
+        };
+
+        if index >= BUTTON_COUNT {
+            self.errors.push(format!("Button index {} out of range on page '{}'", index, page.name));
+        } else if page.buttons.contains_key(&index) {
+            self.errors.push(format!("Button {} defined twice on page '{}'", index, page.name));
+        }
+
+        page.buttons.insert(
+            index,
+            ButtonConfig {
+                action,
+                label: None,
+                image: None,
+                label_overlay: false,
+                label_color: None,
+                cooldown_ms: None,
+                dim_on_cooldown: false,
+                padding: None,
+                dim: 0.0,
+                toggle_images: None,
+                toggled: None,
+                refresh_interval_ms: None,
+                group: None,
+            },
+        );
+        self.last_button = Some(index);
+        self
+    }
+
+    /// Set the label of the last added button
+    pub fn label(self, label: &str) -> Self {
+        self.with_last_button("label", |button| button.label = Some(label.to_string()))
+    }
+
+    /// Set the image of the last added button
+    pub fn image(self, image: ButtonImage) -> Self {
+        self.with_last_button("image", |button| button.image = Some(image))
+    }
+
+    /// Show `on`/`off` images on the last added button for the state its action reports
+    pub fn toggle_images(self, on: ButtonImage, off: ButtonImage) -> Self {
+        self.with_last_button("toggle_images", |button| button.toggle_images = Some(ToggleImages { on, off }))
+    }
+
+    /// Draw the last added button's label as a caption over its image
+    pub fn label_overlay(self, color: Option<&str>) -> Self {
+        self.with_last_button("label_overlay", |button| {
+            button.label_overlay = true;
+            button.label_color = color.map(str::to_string);
+        })
+    }
+
+    /// Give the last added button a cooldown, optionally dimming it meanwhile
+    pub fn cooldown(self, cooldown_ms: u64, dim: bool) -> Self {
+        self.with_last_button("cooldown", |button| {
+            button.cooldown_ms = Some(cooldown_ms);
+            button.dim_on_cooldown = dim;
+        })
+    }
+
+    /// Darken the last added button's image (0.0 = unchanged, 1.0 = black)
+    pub fn dim(self, dim: f32) -> Self {
+        self.with_last_button("dim", |button| button.dim = dim)
+    }
+
+    /// Re-run the last added button's action every `interval_ms` while it's on screen
+    pub fn refresh_every(self, interval_ms: u64) -> Self {
+        self.with_last_button("refresh_every", |button| button.refresh_interval_ms = Some(interval_ms))
+    }
+
+    /// Put the last added button in a radio group with the other buttons on its page named `group`
+    pub fn group(self, group: &str) -> Self {
+        self.with_last_button("group", |button| button.group = Some(group.to_string()))
+    }
+
+    /// Add a dial to the current page
+    pub fn dial(mut self, index: usize, dial: DialConfig) -> Self {
+        let Some(page) = self.pages.last_mut() else {
+            self.errors.push(format!("Dial {} added before any page", index));
+            return self;
+        };
+
+        if index >= DIAL_COUNT {
+            self.errors.push(format!("Dial index {} out of range on page '{}'", index, page.name));
+        }
+        page.dials.insert(index, dial);
+        self
+    }
+
+    /// Run `action` when all of `buttons` are pressed together
+    pub fn combo(mut self, buttons: &[usize], action: Action) -> Self {
+        if buttons.len() < 2 {
+            self.errors.push(format!("Combo {:?} needs at least two buttons", buttons));
+        } else if let Some(index) = buttons.iter().find(|&&index| index >= BUTTON_COUNT) {
+            self.errors.push(format!("Button index {} out of range in combo {:?}", index, buttons));
+        }
+
+        self.combos.push(Combo { buttons: buttons.to_vec(), action });
+        self
+    }
+
+    /// Set which page is active when the config is loaded
+    pub fn current_page(mut self, index: usize) -> Self {
+        self.current_page = index;
+        self
+    }
+
+    /// Go back to page `index` after `timeout_ms` without input on another page
+    pub fn auto_home(mut self, index: usize, timeout_ms: u64) -> Self {
+        self.home_page = index;
+        self.auto_home_timeout_ms = Some(timeout_ms);
+        self
+    }
+
+    /// Validate and produce the config
+    pub fn build(mut self) -> Result<Config, String> {
+        if self.pages.is_empty() {
+            self.errors.push("Config must have at least one page".to_string());
+        } else if self.current_page >= self.pages.len() {
+            self.errors.push(format!(
+                "Current page {} out of range ({} pages)",
+                self.current_page,
+                self.pages.len()
+            ));
+        }
+        if !self.pages.is_empty() && self.home_page >= self.pages.len() {
+            self.errors.push(format!("Home page {} out of range ({} pages)", self.home_page, self.pages.len()));
+        }
+
+        if !self.errors.is_empty() {
+            return Err(self.errors.join("; "));
+        }
+
+        Ok(Config {
+            version: 1,
+            current_page: self.current_page,
+            pages: self.pages,
+            device: DeviceSettings::default(),
+            nicknames: HashMap::new(),
+            encodings: HashMap::new(),
+            app: AppSettings::default(),
+            preview: PreviewSettings::default(),
+            combos: self.combos,
+            home_page: self.home_page,
+            auto_home_timeout_ms: self.auto_home_timeout_ms,
+            transient_page: None,
+        })
+    }
+
+    /// Apply `f` to the last added button on the current page
+    fn with_last_button(mut self, what: &str, f: impl FnOnce(&mut ButtonConfig)) -> Self {
+        let button = self
+            .last_button
+            .and_then(|index| self.pages.last_mut()?.buttons.get_mut(&index));
+
+        match button {
+            Some(button) => f(button),
+            None => self.errors.push(format!("'{}' set before any button", what)),
+        }
+        self
+    }
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,108 @@
+//! Index Key Validation
+//!
+//! Buttons and dials are stored as `HashMap<usize, _>`, but JSON object keys are
+//! strings, so a hand-edited config can refer to the same button as "0" and "00"
+//! (or repeat a key), and the later entry silently wins. These deserializers
+//! normalize keys to indices and reject out-of-range or duplicate entries instead.
+//! The serializer writes keys back as strings, in index order.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+use super::{ButtonConfig, DialConfig};
+use crate::hid::constants::{BUTTON_COUNT, DIAL_COUNT};
+
+/// Deserialize `Page.buttons`, validating keys against `BUTTON_COUNT`
+pub fn deserialize_buttons<'de, D>(deserializer: D) -> Result<HashMap<usize, ButtonConfig>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_map(IndexMapVisitor::new("button", BUTTON_COUNT))
+}
+
+/// Deserialize `Page.dials`, validating keys against `DIAL_COUNT`
+pub fn deserialize_dials<'de, D>(deserializer: D) -> Result<HashMap<usize, DialConfig>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_map(IndexMapVisitor::new("dial", DIAL_COUNT))
+}
+
+/// Serialize an index-keyed map with string keys, sorted by index so saved
+/// configs diff cleanly
+pub fn serialize_index_map<S, V>(map: &HashMap<usize, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    V: Serialize,
+{
+    let mut indices: Vec<&usize> = map.keys().collect();
+    indices.sort();
+
+    let mut out = serializer.serialize_map(Some(map.len()))?;
+    for index in indices {
+        out.serialize_entry(&index.to_string(), &map[index])?;
+    }
+    out.end()
+}
+
+/// Visits a map whose keys are index strings in `0..limit`
+struct IndexMapVisitor<V> {
+    what: &'static str,
+    limit: usize,
+    marker: PhantomData<V>,
+}
+
+impl<V> IndexMapVisitor<V> {
+    fn new(what: &'static str, limit: usize) -> Self {
+        Self {
+            what,
+            limit,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, V: Deserialize<'de>> Visitor<'de> for IndexMapVisitor<V> {
+    type Value = HashMap<usize, V>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a map of {} indices (0 through {})", self.what, self.limit - 1)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut entries = HashMap::new();
+        // Original spelling of each index, for conflict messages
+        let mut keys: HashMap<usize, String> = HashMap::new();
+
+        while let Some(key) = map.next_key::<String>()? {
+            let index: usize = key.trim().parse().map_err(|_| {
+                de::Error::custom(format!("invalid {} key \"{}\": not an index", self.what, key))
+            })?;
+
+            if index >= self.limit {
+                return Err(de::Error::custom(format!(
+                    "invalid {} key \"{}\": must be 0 through {}",
+                    self.what,
+                    key,
+                    self.limit - 1
+                )));
+            }
+
+            if let Some(existing) = keys.get(&index) {
+                return Err(de::Error::custom(format!(
+                    "{} key \"{}\" conflicts with \"{}\" (both are {} {})",
+                    self.what, key, existing, self.what, index
+                )));
+            }
+
+            entries.insert(index, map.next_value()?);
+            keys.insert(index, key);
+        }
+
+        Ok(entries)
+    }
+}
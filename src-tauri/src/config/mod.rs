@@ -3,8 +3,25 @@
 //! Defines the structure for storing button mappings, actions, and images.
 //! Configuration is persisted as JSON in the app data directory.
 
+mod builder;
+mod keys;
+mod params;
+pub mod presets;
+pub mod profiles;
+pub mod runtime;
+pub mod storage;
+
+pub use builder::ConfigBuilder;
+
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::hid::constants::{BUTTON_COUNT, DIAL_COUNT};
+use crate::hid::device::{ImageEncoding, ImagePadding};
+
+/// Page index of `Config::transient_page` while it's on the deck
+pub const TRANSIENT_PAGE: usize = usize::MAX;
 
 /// Root configuration structure
 #[derive(Serialize, Deserialize, Clone)]
@@ -15,6 +32,146 @@ pub struct Config {
     pub current_page: usize,
     /// All pages of button configurations
     pub pages: Vec<Page>,
+    /// Hardware settings applied when a device connects
+    #[serde(default)]
+    pub device: DeviceSettings,
+    /// Device nicknames, keyed by serial number (or USB path when there is no serial)
+    #[serde(default)]
+    pub nicknames: HashMap<String, String>,
+    /// Image encodings confirmed with the encoding probe, keyed like `nicknames`
+    #[serde(default)]
+    pub encodings: HashMap<String, ImageEncoding>,
+    /// Desktop app behavior (window, shortcuts)
+    #[serde(default)]
+    pub app: AppSettings,
+    /// Encoding of the live deck preview
+    #[serde(default)]
+    pub preview: PreviewSettings,
+    /// Actions run by pressing several buttons together (on any page)
+    #[serde(default)]
+    pub combos: Vec<Combo>,
+    /// Page the deck goes back to after `auto_home_timeout_ms` without input
+    #[serde(default)]
+    pub home_page: usize,
+    /// Return to `home_page` after this long without input on another page,
+    /// in milliseconds (`null` stays put)
+    #[serde(default)]
+    pub auto_home_timeout_ms: Option<u64>,
+    /// Generated page shown over `current_page` until the next page change (the
+    /// window switcher). Runtime only: it isn't saved, listed or navigated to.
+    #[serde(skip)]
+    pub transient_page: Option<Page>,
+}
+
+/// Desktop app behavior (window, shortcuts)
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AppSettings {
+    /// System-wide hotkey that brings the window to the front, or `null` to disable
+    #[serde(default = "default_focus_shortcut")]
+    pub focus_shortcut: Option<String>,
+    /// Hide to the system tray when the window is closed, keeping the deck running
+    #[serde(default)]
+    pub close_to_tray: bool,
+    /// Name of the profile this config is saved back to when switching profiles
+    #[serde(default = "default_active_profile")]
+    pub active_profile: String,
+    /// How long button input waits for a pressed button's action before moving
+    /// on without it, in milliseconds (0 waits as long as the action takes)
+    #[serde(default = "default_action_timeout_ms")]
+    pub action_timeout_ms: u64,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            focus_shortcut: default_focus_shortcut(),
+            close_to_tray: false,
+            active_profile: default_active_profile(),
+            action_timeout_ms: default_action_timeout_ms(),
+        }
+    }
+}
+
+fn default_focus_shortcut() -> Option<String> {
+    Some(crate::shortcut::DEFAULT_FOCUS_SHORTCUT.to_string())
+}
+
+fn default_active_profile() -> String {
+    profiles::DEFAULT_PROFILE_NAME.to_string()
+}
+
+fn default_action_timeout_ms() -> u64 {
+    crate::polling::DEFAULT_ACTION_TIMEOUT_MS
+}
+
+/// Encoding of the live deck preview (`get_deck_preview`), to trade quality
+/// for bandwidth when it's streamed to a phone
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct PreviewSettings {
+    /// JPEG quality (1-100), or `null` for a lossless PNG
+    #[serde(default)]
+    pub quality: Option<u8>,
+    /// Shrink the preview to at most this many pixels wide, or `null` for full size
+    #[serde(default)]
+    pub max_width: Option<u32>,
+}
+
+/// Hardware settings applied when a device connects
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DeviceSettings {
+    /// Rotate button images 180° before sending them, on models that need it
+    /// (all but the Stream Deck +). Genuine decks need this; some clones and
+    /// re-flashed units are already pre-rotated.
+    #[serde(default = "default_true")]
+    pub rotate_images: bool,
+    /// Margin around button images, in pixels or `{ "percent": n }`
+    #[serde(default)]
+    pub padding: Option<ImagePadding>,
+    /// Presses on mirror decks run the connected deck's actions (otherwise they're ignored)
+    #[serde(default)]
+    pub mirror_presses: bool,
+    /// How many encoded button images to keep cached per deck (0 disables the cache)
+    #[serde(default = "default_encode_cache_size")]
+    pub encode_cache_size: usize,
+    /// Encode the pages before and after the current one in the background,
+    /// so switching to them is instant (uses the encode cache)
+    #[serde(default = "default_true")]
+    pub prerender_adjacent_pages: bool,
+    /// Button image command byte for MK.2-style decks, overriding the
+    /// standard `0x07` (`null` for the standard one)
+    #[serde(default)]
+    pub image_command: Option<u8>,
+    /// Show the encoding test pattern when a deck without a confirmed
+    /// encoding connects (for clones that get detected wrong)
+    #[serde(default)]
+    pub probe_encoding: bool,
+    /// On syncs that redraw many buttons, mark them as pending first so the
+    /// deck visibly fills in instead of looking frozen
+    #[serde(default)]
+    pub sync_progress: bool,
+}
+
+impl Default for DeviceSettings {
+    fn default() -> Self {
+        Self {
+            rotate_images: true,
+            padding: None,
+            mirror_presses: false,
+            encode_cache_size: default_encode_cache_size(),
+            prerender_adjacent_pages: true,
+            image_command: None,
+            probe_encoding: false,
+            sync_progress: false,
+        }
+    }
+}
+
+fn default_encode_cache_size() -> usize {
+    crate::hid::constants::ENCODE_CACHE_SIZE
+}
+
+fn default_true() -> bool {
+    true
 }
 
 /// A single page of button configurations
@@ -23,7 +180,73 @@ pub struct Page {
     /// Display name for this page
     pub name: String,
     /// Button configurations, keyed by button index (0 through 14)
+    #[serde(
+        serialize_with = "keys::serialize_index_map",
+        deserialize_with = "keys::deserialize_buttons"
+    )]
     pub buttons: HashMap<usize, ButtonConfig>,
+    /// Dial configurations (Stream Deck + only), keyed by dial index (0 through 3)
+    #[serde(
+        default,
+        serialize_with = "keys::serialize_index_map",
+        deserialize_with = "keys::deserialize_dials"
+    )]
+    pub dials: HashMap<usize, DialConfig>,
+}
+
+impl Page {
+    /// Swap the buttons at two positions (either may be empty)
+    pub fn swap_buttons(&mut self, a: usize, b: usize) {
+        let button_a = self.buttons.remove(&a);
+        let button_b = self.buttons.remove(&b);
+
+        if let Some(button) = button_a {
+            self.buttons.insert(b, button);
+        }
+        if let Some(button) = button_b {
+            self.buttons.insert(a, button);
+        }
+    }
+
+    /// Move a button to another position.
+    ///
+    /// If the target is occupied, the two buttons are swapped when `swap` is set,
+    /// otherwise an error is returned and nothing changes.
+    pub fn move_button(&mut self, from: usize, to: usize, swap: bool) -> Result<(), String> {
+        if !self.buttons.contains_key(&from) {
+            return Err(format!("No button at position {}", from));
+        }
+        if from != to && self.buttons.contains_key(&to) && !swap {
+            return Err(format!("Position {} is already occupied", to));
+        }
+
+        self.swap_buttons(from, to);
+        Ok(())
+    }
+}
+
+/// Buttons that run their own action when pressed together, instead of
+/// their individual actions
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Combo {
+    /// Button indices that all have to be pressed (at least two)
+    pub buttons: Vec<usize>,
+    /// Action to run when they are
+    pub action: Action,
+}
+
+/// Configuration for a single rotary dial
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct DialConfig {
+    /// Action run once per detent when turned clockwise
+    #[serde(default)]
+    pub rotate_clockwise: Option<Action>,
+    /// Action run once per detent when turned counter-clockwise
+    #[serde(default)]
+    pub rotate_counterclockwise: Option<Action>,
+    /// Action run when the dial is pushed in
+    #[serde(default)]
+    pub press: Option<Action>,
 }
 
 /// Configuration for a single button
@@ -35,6 +258,46 @@ pub struct ButtonConfig {
     pub label: Option<String>,
     /// Optional custom image (overrides action's default image)
     pub image: Option<ButtonImage>,
+    /// Draw the label as a caption over the image (when both are present)
+    #[serde(default)]
+    pub label_overlay: bool,
+    /// Caption text color as a hex string (e.g. "#ffffff"), defaults to white
+    #[serde(default)]
+    pub label_color: Option<String>,
+    /// Ignore presses for this long after the action fires (milliseconds)
+    #[serde(default)]
+    pub cooldown_ms: Option<u64>,
+    /// Dim the button while it's cooling down
+    #[serde(default)]
+    pub dim_on_cooldown: bool,
+    /// Margin around this button's image (overrides the device padding)
+    #[serde(default)]
+    pub padding: Option<ImagePadding>,
+    /// Darken this button's image, from 0.0 (unchanged) to 1.0 (black)
+    #[serde(default)]
+    pub dim: f32,
+    /// Images for the on/off state reported by the action (e.g. locked/unlocked)
+    #[serde(default)]
+    pub toggle_images: Option<ToggleImages>,
+    /// Last state reported by the action (runtime only, not saved)
+    #[serde(skip)]
+    pub toggled: Option<bool>,
+    /// Re-run the action this often while the button is on screen (milliseconds)
+    #[serde(default)]
+    pub refresh_interval_ms: Option<u64>,
+    /// Radio group on the same page: pressing this button turns it on and the
+    /// group's other buttons off (shown with `toggle_images`)
+    #[serde(default)]
+    pub group: Option<String>,
+}
+
+/// Images shown by a button whose action reports an on/off state
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ToggleImages {
+    /// Shown when the action reports "on" (muted, recording, locked...)
+    pub on: ButtonImage,
+    /// Shown when the action reports "off", and before it has run
+    pub off: ButtonImage,
 }
 
 /// Image to display on a button
@@ -49,6 +312,14 @@ pub enum ButtonImage {
     #[serde(rename = "icon")]
     Icon { name: String },
 
+    /// Image downloaded from an `http(s)://` URL and cached for `ttl_secs`
+    #[serde(rename = "url")]
+    Url {
+        url: String,
+        #[serde(default)]
+        ttl_secs: Option<u64>,
+    },
+
     /// Dynamically generated text image
     #[serde(rename = "text")]
     Text {
@@ -100,91 +371,219 @@ impl Action {
         self.params.get(key).and_then(|v| v.as_i64())
     }
 
+    /// Get a path parameter, with `~` and environment variables expanded
+    pub fn get_path_param(&self, key: &str) -> Option<PathBuf> {
+        self.get_string_param(key).map(crate::paths::expand_path)
+    }
+
+    /// Get a boolean parameter
+    pub fn get_bool_param(&self, key: &str) -> Option<bool> {
+        self.params.get(key).and_then(|v| v.as_bool())
+    }
+
+    /// Deserialize all parameters into a handler's params struct.
+    ///
+    /// Errors name the action and the missing or invalid parameter, e.g.
+    /// `Invalid parameters for system.launch_app: 'args': invalid type: string "-v", expected a sequence`.
+    pub fn params_as<T: serde::de::DeserializeOwned>(&self) -> Result<T, String> {
+        params::deserialize(&self.params)
+            .map_err(|e| format!("Invalid parameters for {}: {}", self.action_type, e))
+    }
+
+    /// Returns the page index this action jumps to, if it's a direct page navigation.
+    ///
+    /// Named page targets are resolved against the given pages.
+    pub fn target_page(&self, pages: &[Page]) -> Option<usize> {
+        match self.action_type.as_str() {
+            "navigation.go_to_page" => self
+                .get_int_param("page")
+                .map(|page| page as usize)
+                .filter(|&page| page < pages.len()),
+            "navigation.go_to_page_named" => {
+                let name = self.get_string_param("name")?;
+                pages.iter().position(|page| page.name == name)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns a human-readable name for the action type, if it's a built-in one
+    pub fn display_name(&self) -> Option<&'static str> {
+        match self.action_type.as_str() {
+            "audio.volume_up" => Some("Volume Up"),
+            "audio.volume_down" => Some("Volume Down"),
+            "audio.volume_mute" => Some("Mute"),
+            "audio.play_sound" => Some("Play Sound"),
+            "audio.set_output_device" => Some("Set Output Device"),
+            "audio.cycle_output_device" => Some("Next Output Device"),
+            "audio.mic_meter" => Some("Mic Level Meter"),
+            "audio.toggle_app_mute" => Some("Mute App"),
+            "navigation.next_page" => Some("Next Page"),
+            "navigation.previous_page" => Some("Previous Page"),
+            "navigation.go_to_page" | "navigation.go_to_page_named" => Some("Go to Page"),
+            "system.launch_app" => Some("Launch App"),
+            "system.open_url" => Some("Open URL"),
+            "system.open_path" => Some("Open File or Folder"),
+            "system.hotkey" => Some("Hotkey"),
+            "system.key_hold" => Some("Hold Keys"),
+            "system.run_script" => Some("Run Script"),
+            "system.region_capture" => Some("Capture Screen Region"),
+            "system.set_polling_rate" => Some("Set Polling Rate"),
+            "system.cycle_brightness" => Some("Cycle Brightness"),
+            "system.window_switcher" => Some("Window Switcher"),
+            "system.focus_window" => Some("Focus Window"),
+            "script.run" => Some("Run Rhai Script"),
+            "weather.display" => Some("Weather"),
+            "web.post_message" => Some("Post to Webhook"),
+            "app.toggle_window" => Some("Show/Hide Window"),
+            "meta.toggle_simulate" => Some("Toggle Simulate Mode"),
+            "meta.toggle_lock" => Some("Lock Deck"),
+            "meta.redraw" => Some("Redraw Deck"),
+            "meta.random" => Some("Random Action"),
+            "meta.confirm" => Some("Confirm First"),
+            "meta.conditional" => Some("If / Else"),
+            "none" => Some("Nothing"),
+            _ => None,
+        }
+    }
+
+    /// One-line summary of the parameters, e.g. `path: "C:/obs.exe", args: ["--minimize"]`.
+    /// Keys are sorted so the summary is stable.
+    pub fn params_summary(&self) -> String {
+        let mut params: Vec<_> = self.params.iter().collect();
+        params.sort_by_key(|(key, _)| key.as_str());
+        params
+            .iter()
+            .map(|(key, value)| format!("{}: {}", key, value))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
     /// Returns the default icon name based on action type
     pub fn default_icon(&self) -> Option<&'static str> {
         match self.action_type.as_str() {
             "audio.volume_up" => Some("volume-up"),
             "audio.volume_down" => Some("volume-down"),
             "audio.volume_mute" => Some("volume-mute"),
+            "audio.toggle_app_mute" => Some("volume-mute"),
             "audio.play_sound" => Some("music"),
             "navigation.next_page" => Some("arrow-right"),
             "navigation.previous_page" => Some("arrow-left"),
-            "navigation.go_to_page" => Some("layers"),
+            "navigation.go_to_page" | "navigation.go_to_page_named" => Some("layers"),
             "system.launch_app" => Some("terminal"),
             "system.open_url" => Some("globe"),
+            "system.open_path" => Some("folder"),
             "system.hotkey" => Some("keyboard"),
             _ => None,
         }
     }
 }
 
+impl Config {
+    /// Start building a config in code
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::new()
+    }
+
+    /// Index of the page on the deck: `TRANSIENT_PAGE` while the transient
+    /// page is shown, otherwise `current_page`
+    pub fn shown_page(&self) -> usize {
+        if self.transient_page.is_some() {
+            TRANSIENT_PAGE
+        } else {
+            self.current_page
+        }
+    }
+
+    /// Page by index, including `TRANSIENT_PAGE`
+    pub fn page(&self, index: usize) -> Option<&Page> {
+        match index {
+            TRANSIENT_PAGE => self.transient_page.as_ref(),
+            _ => self.pages.get(index),
+        }
+    }
+
+    /// Mutable page by index, including `TRANSIENT_PAGE`
+    pub fn page_mut(&mut self, index: usize) -> Option<&mut Page> {
+        match index {
+            TRANSIENT_PAGE => self.transient_page.as_mut(),
+            _ => self.pages.get_mut(index),
+        }
+    }
+
+    /// Switch to a page, closing the transient page if it's open.
+    /// The caller checks that `index` is in range.
+    pub fn go_to_page(&mut self, index: usize) {
+        self.current_page = index;
+        self.transient_page = None;
+    }
+
+    /// Check the whole config, returning every problem found (empty if it's valid).
+    ///
+    /// `known_action` tells whether an action type has a handler.
+    pub fn validate(&self, known_action: impl Fn(&str) -> bool) -> Vec<String> {
+        let mut errors = Vec::new();
+        // Every action in the config, with where it is, checked at the end
+        let mut actions: Vec<(String, &Action)> = Vec::new();
+
+        if self.pages.is_empty() {
+            errors.push("Config must have at least one page".to_string());
+        } else if self.current_page >= self.pages.len() {
+            errors.push(format!("Current page {} out of range ({} pages)", self.current_page, self.pages.len()));
+        }
+        if !self.pages.is_empty() && self.home_page >= self.pages.len() {
+            errors.push(format!("Home page {} out of range ({} pages)", self.home_page, self.pages.len()));
+        }
+
+        for page in &self.pages {
+            for (&index, button) in &page.buttons {
+                if index >= BUTTON_COUNT {
+                    errors.push(format!("Button index {} out of range on page '{}'", index, page.name));
+                }
+                actions.push((format!("Button {} on page '{}'", index, page.name), &button.action));
+            }
+
+            for (&index, dial) in &page.dials {
+                if index >= DIAL_COUNT {
+                    errors.push(format!("Dial index {} out of range on page '{}'", index, page.name));
+                }
+                let dial_actions = [&dial.rotate_clockwise, &dial.rotate_counterclockwise, &dial.press];
+                for action in dial_actions.into_iter().flatten() {
+                    actions.push((format!("Dial {} on page '{}'", index, page.name), action));
+                }
+            }
+        }
+
+        for combo in &self.combos {
+            if combo.buttons.len() < 2 {
+                errors.push(format!("Combo {:?} needs at least two buttons", combo.buttons));
+            } else if let Some(index) = combo.buttons.iter().find(|&&index| index >= BUTTON_COUNT) {
+                errors.push(format!("Button index {} out of range in combo {:?}", index, combo.buttons));
+            }
+            actions.push((format!("Combo {:?}", combo.buttons), &combo.action));
+        }
+
+        for (place, action) in actions {
+            if !known_action(&action.action_type) {
+                errors.push(format!("{}: unknown action '{}'", place, action.action_type));
+            }
+        }
+        errors
+    }
+}
+
 impl Default for Config {
     /// Creates a default configuration with one page and volume controls
     fn default() -> Self {
-        let mut buttons = HashMap::new();
-
-        buttons.insert(
-            0,
-            ButtonConfig {
-                action: Action::new("audio.volume_up"),
-                label: Some("Vol +".to_string()),
-                image: None,
-            },
-        );
-
-        buttons.insert(
-            1,
-            ButtonConfig {
-                action: Action::new("audio.volume_down"),
-                label: Some("Vol -".to_string()),
-                image: None,
-            },
-        );
-
-        buttons.insert(
-            2,
-            ButtonConfig {
-                action: Action::new("audio.volume_mute"),
-                label: Some("Vol 0".to_string()),
-                image: None,
-            },
-        );
-
-        buttons.insert(
-            3,
-            ButtonConfig {
-                action: Action::new("weather.display"),
-                label: Some("Weather".to_string()),
-                image: None,
-            },
-        );
-
-        buttons.insert(
-            13,
-            ButtonConfig {
-                action: Action::new("navigation.previous_page"),
-                label: Some("<==".to_string()),
-                image: None,
-            },
-        );
-
-        buttons.insert(
-            14,
-            ButtonConfig {
-                action: Action::new("navigation.next_page"),
-                label: Some("==>".to_string()),
-                image: None,
-            },
-        );
-
-
-        Config {
-            version: 1,
-            current_page: 0,
-            pages: vec![Page {
-                name: "Main".to_string(),
-                buttons,
-            }],
-        }
+        Config::builder()
+            .page("Main")
+            .button(0, Action::new("audio.volume_up")).label("Vol +")
+            .button(1, Action::new("audio.volume_down")).label("Vol -")
+            .button(2, Action::new("audio.volume_mute")).label("Vol 0")
+            .button(3, Action::new("weather.display")).label("Weather").refresh_every(30 * 60 * 1000)
+            .button(13, Action::new("navigation.previous_page")).label("<==")
+            .button(14, Action::new("navigation.next_page")).label("==>")
+            .build()
+            .expect("default config is valid")
     }
 }
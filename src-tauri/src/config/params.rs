@@ -0,0 +1,89 @@
+//! Typed Action Params
+//!
+//! Deserializes `Action.params` into a handler's own params struct, for
+//! `Action::params_as`. Plain `serde_json::from_value` reports a wrong type as
+//! e.g. "invalid type: integer `5`, expected a string" without saying which
+//! param it was, so each value is wrapped in a deserializer that puts the
+//! param's name in front of its errors. Missing params are already named by
+//! serde ("missing field `path`").
+
+use std::collections::HashMap;
+
+use serde::de::value::MapDeserializer;
+use serde::de::{self, DeserializeOwned, Deserializer, IntoDeserializer, Visitor};
+use serde_json::Value;
+
+/// Deserialize `params` into `T`, naming the param in any error
+pub fn deserialize<T: DeserializeOwned>(params: &HashMap<String, Value>) -> Result<T, serde_json::Error> {
+    let entries = params.iter().map(|(key, value)| (key.as_str(), Param { key, value }));
+    T::deserialize(MapDeserializer::new(entries))
+}
+
+/// One param's value, deserialized like the JSON value it holds
+struct Param<'a> {
+    key: &'a str,
+    value: &'a Value,
+}
+
+impl Param<'_> {
+    fn named(&self, error: serde_json::Error) -> serde_json::Error {
+        de::Error::custom(format_args!("'{}': {}", self.key, error))
+    }
+}
+
+impl<'de> IntoDeserializer<'de, serde_json::Error> for Param<'_> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+/// Forward deserializer methods to the JSON value, naming the param in errors
+macro_rules! forward_to_value {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Self::Error> {
+                self.value.clone().$method($($arg,)* visitor).map_err(|e| self.named(e))
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Param<'_> {
+    type Error = serde_json::Error;
+
+    forward_to_value! {
+        deserialize_any();
+        deserialize_bool();
+        deserialize_i8();
+        deserialize_i16();
+        deserialize_i32();
+        deserialize_i64();
+        deserialize_i128();
+        deserialize_u8();
+        deserialize_u16();
+        deserialize_u32();
+        deserialize_u64();
+        deserialize_u128();
+        deserialize_f32();
+        deserialize_f64();
+        deserialize_char();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_option();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_ignored_any();
+    }
+}
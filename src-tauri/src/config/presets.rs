@@ -0,0 +1,62 @@
+//! Built-in Presets
+//!
+//! Starter configs bundled into the binary, so new users get a working deck
+//! instead of a mostly empty one.
+
+use serde::Serialize;
+
+use super::Config;
+
+/// A bundled preset config
+struct Preset {
+    name: &'static str,
+    description: &'static str,
+    json: &'static str,
+}
+
+const PRESETS: &[Preset] = &[
+    Preset {
+        name: "Streaming",
+        description: "Push-to-talk, mute, scene hotkeys and stream dashboard",
+        json: include_str!("../../presets/streaming.json"),
+    },
+    Preset {
+        name: "Productivity",
+        description: "Editing shortcuts, mail and calendar, plus an audio page",
+        json: include_str!("../../presets/productivity.json"),
+    },
+    Preset {
+        name: "Media Control",
+        description: "Volume, playback keys and media sites",
+        json: include_str!("../../presets/media.json"),
+    },
+];
+
+/// Preset summary shown to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct PresetInfo {
+    pub name: String,
+    pub description: String,
+}
+
+/// List all bundled presets
+pub fn list_presets() -> Vec<PresetInfo> {
+    PRESETS
+        .iter()
+        .map(|preset| PresetInfo {
+            name: preset.name.to_string(),
+            description: preset.description.to_string(),
+        })
+        .collect()
+}
+
+/// Load a bundled preset by name (case-insensitive)
+pub fn load_preset(name: &str) -> Result<Config, String> {
+    let preset = PRESETS
+        .iter()
+        .find(|preset| preset.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| format!("Unknown preset: '{}'", name))?;
+
+    serde_json::from_str(preset.json)
+        .map_err(|e| format!("Preset '{}' is invalid: {}", preset.name, e))
+}
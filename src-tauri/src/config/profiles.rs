@@ -0,0 +1,87 @@
+//! Profiles
+//!
+//! Named snapshots of the whole config (e.g. "Work" and "Gaming"), saved as
+//! `profiles/<name>.json` in the app config directory.
+//!
+//! `config.json` is still the live config; `app.active_profile` records which
+//! profile it belongs to, so switching can save it back before loading the
+//! next one.
+
+use std::fs;
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Manager};
+
+use super::Config;
+
+/// Folder in the app config directory that profiles are saved in
+const PROFILES_DIR_NAME: &str = "profiles";
+
+/// Profile the live config belongs to before any profile has been chosen
+pub const DEFAULT_PROFILE_NAME: &str = "Default";
+
+/// Reject names that can't be used as a file name in the profiles folder
+pub fn check_name(name: &str) -> Result<(), String> {
+    let invalid = name.trim().is_empty()
+        || name.trim() != name
+        || name.starts_with('.')
+        || name.chars().any(|c| matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') || c.is_control());
+
+    if invalid {
+        return Err(format!("Invalid profile name: '{}'", name));
+    }
+    Ok(())
+}
+
+/// Path of the profiles folder, creating it if needed
+fn profiles_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app_handle
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("Failed to get config dir: {}", e))?
+        .join(PROFILES_DIR_NAME);
+
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create profiles dir: {}", e))?;
+    Ok(dir)
+}
+
+/// Path of a profile's file
+fn profile_path(name: &str, app_handle: &AppHandle) -> Result<PathBuf, String> {
+    check_name(name)?;
+    Ok(profiles_dir(app_handle)?.join(format!("{}.json", name)))
+}
+
+/// Names of all saved profiles, sorted
+pub fn list_profiles(app_handle: &AppHandle) -> Result<Vec<String>, String> {
+    let dir = profiles_dir(app_handle)?;
+    let entries = fs::read_dir(&dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| path.file_stem().map(|stem| stem.to_string_lossy().to_string()))
+        .collect();
+    names.sort_by_key(|name| name.to_lowercase());
+    Ok(names)
+}
+
+/// Save a config as a profile, replacing any profile with the same name
+pub fn save_profile(name: &str, config: &Config, app_handle: &AppHandle) -> Result<(), String> {
+    let path = profile_path(name, app_handle)?;
+    let temp = path.with_extension("json.tmp");
+
+    let json = serde_json::to_string_pretty(config).map_err(|e| format!("Failed to serialize profile: {}", e))?;
+    fs::write(&temp, json).map_err(|e| format!("Failed to write {}: {}", temp.display(), e))?;
+    fs::rename(&temp, &path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+}
+
+/// Load a saved profile
+pub fn load_profile(name: &str, app_handle: &AppHandle) -> Result<Config, String> {
+    let path = profile_path(name, app_handle)?;
+    if !path.exists() {
+        return Err(format!("Unknown profile: '{}'", name));
+    }
+
+    let contents = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&contents).map_err(|e| format!("Profile '{}' is invalid: {}", name, e))
+}
@@ -0,0 +1,118 @@
+//! Runtime State
+//!
+//! Remembers what the buttons were showing when the app closed: the on/off
+//! state actions last reported (which also records the active button of
+//! each group) and the deck brightness. It's saved as `runtime_state.json`
+//! next to `config.json` whenever one of them changes, and applied to the
+//! loaded config at startup, so the first sync draws the deck as it was left.
+//!
+//! Button states are keyed by page name, so they stay with their page when
+//! pages are reordered or removed. If several pages share a name, only the
+//! first one's states are kept.
+//!
+//! It's kept out of `config.json` so a button press doesn't rewrite the
+//! config (and rotate its backup).
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::{storage, Config};
+
+const RUNTIME_STATE_FILE_NAME: &str = "runtime_state.json";
+
+/// Where a save is written before being renamed into place
+const TEMP_FILE_NAME: &str = "runtime_state.json.tmp";
+
+/// Actions whose state lives in the app and starts off on every launch, so
+/// restoring their button would show the wrong state
+const UNRESTORED_ACTIONS: [&str; 3] = ["meta.toggle_lock", "meta.toggle_simulate", "audio.mic_meter"];
+
+/// Button states and brightness to restore on the next launch
+#[derive(Serialize, Deserialize, Default)]
+pub struct RuntimeState {
+    /// Last state reported for each button, by page name and button index
+    #[serde(default)]
+    pub toggled: HashMap<String, HashMap<usize, bool>>,
+    /// Deck brightness (percent)
+    #[serde(default)]
+    pub brightness: Option<u8>,
+}
+
+impl RuntimeState {
+    /// Take the button states from `config` along with the current brightness
+    pub fn capture(config: &Config, brightness: u8) -> Self {
+        let mut seen = HashSet::new();
+        let toggled = config
+            .pages
+            .iter()
+            .filter(|page| seen.insert(page.name.as_str()))
+            .filter_map(|page| {
+                let buttons: HashMap<usize, bool> = page
+                    .buttons
+                    .iter()
+                    .filter(|(_, button)| !UNRESTORED_ACTIONS.contains(&button.action.action_type.as_str()))
+                    .filter_map(|(&button_index, button)| Some((button_index, button.toggled?)))
+                    .collect();
+                (!buttons.is_empty()).then(|| (page.name.clone(), buttons))
+            })
+            .collect();
+
+        Self { toggled, brightness: Some(brightness) }
+    }
+
+    /// Set the saved button states on `config`. Pages and buttons that have
+    /// since been removed, and buttons given one of the `UNRESTORED_ACTIONS`,
+    /// are skipped.
+    pub fn apply(&self, config: &mut Config) {
+        for (page_name, buttons) in &self.toggled {
+            let Some(page) = config.pages.iter_mut().find(|page| &page.name == page_name) else {
+                continue;
+            };
+            for (button_index, &on) in buttons {
+                if let Some(button) = page.buttons.get_mut(button_index) {
+                    if !UNRESTORED_ACTIONS.contains(&button.action.action_type.as_str()) {
+                        button.toggled = Some(on);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Load the saved runtime state, or an empty one if there is none.
+/// A file that can't be read is reported and ignored; it's replaced on the next save.
+pub fn load(app_handle: &AppHandle) -> RuntimeState {
+    let path = match storage::config_path(app_handle) {
+        Ok(path) => path.with_file_name(RUNTIME_STATE_FILE_NAME),
+        Err(e) => {
+            eprintln!("Not restoring button states: {}", e);
+            return RuntimeState::default();
+        }
+    };
+
+    if !path.exists() {
+        return RuntimeState::default();
+    }
+
+    fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|contents| serde_json::from_str(&contents).map_err(|e| e.to_string()))
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to load {}: {}", path.display(), e);
+            RuntimeState::default()
+        })
+}
+
+/// Write the runtime state to disk, replacing the previous one atomically
+pub fn save(runtime_state: &RuntimeState, app_handle: &AppHandle) -> Result<(), String> {
+    let path = storage::config_path(app_handle)?.with_file_name(RUNTIME_STATE_FILE_NAME);
+    let temp = path.with_file_name(TEMP_FILE_NAME);
+
+    let json = serde_json::to_string_pretty(runtime_state)
+        .map_err(|e| format!("Failed to serialize runtime state: {}", e))?;
+    fs::write(&temp, json).map_err(|e| format!("Failed to write {}: {}", temp.display(), e))?;
+    fs::rename(&temp, &path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+}
@@ -0,0 +1,113 @@
+//! Config Storage
+//!
+//! Loads and saves the config as `config.json` in the app config directory.
+//!
+//! Saves are crash-safe: the new config is written to a temp file, flushed,
+//! and renamed over the old one, so an interrupted save never leaves a
+//! truncated file behind. The previous config is kept as `config.json.bak`,
+//! or as `config.json.invalid` if it didn't parse.
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Manager};
+
+use super::Config;
+
+const CONFIG_FILE_NAME: &str = "config.json";
+
+/// Previous config, kept in case the latest one turns out to be bad
+const BACKUP_FILE_NAME: &str = "config.json.bak";
+
+/// A config that didn't parse, set aside by the first save after loading it
+const INVALID_FILE_NAME: &str = "config.json.invalid";
+
+/// Where a save is written before being renamed into place
+const TEMP_FILE_NAME: &str = "config.json.tmp";
+
+/// Path of the config file, creating the config directory if needed
+pub fn config_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let config_dir = app_handle
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("Failed to get config dir: {}", e))?;
+
+    fs::create_dir_all(&config_dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
+
+    Ok(config_dir.join(CONFIG_FILE_NAME))
+}
+
+/// Load the saved config, falling back to the backup and then the default.
+///
+/// A config that fails to parse is reported and left on disk untouched, so a
+/// typo in a hand edit doesn't get overwritten by the default. The next save
+/// moves it to `config.json.invalid` so the edit can still be recovered.
+pub fn load(app_handle: &AppHandle) -> Config {
+    let path = match config_path(app_handle) {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("Using default config: {}", e);
+            return Config::default();
+        }
+    };
+
+    if !path.exists() {
+        return Config::default();
+    }
+
+    match read_config(&path) {
+        Ok(config) => {
+            println!("Loaded config from {}", path.display());
+            return config;
+        }
+        Err(e) => eprintln!("{}", e),
+    }
+
+    let backup = path.with_file_name(BACKUP_FILE_NAME);
+    match read_config(&backup) {
+        Ok(config) => {
+            println!("Loaded backup config from {}", backup.display());
+            config
+        }
+        Err(_) => {
+            eprintln!("No usable config, using default config");
+            Config::default()
+        }
+    }
+}
+
+fn read_config(path: &Path) -> Result<Config, String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&contents).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+/// Write the config to disk atomically, keeping the previous one as a backup
+pub fn save(config: &Config, app_handle: &AppHandle) -> Result<(), String> {
+    let path = config_path(app_handle)?;
+    let temp = path.with_file_name(TEMP_FILE_NAME);
+    let backup = path.with_file_name(BACKUP_FILE_NAME);
+
+    let json = serde_json::to_string_pretty(config).map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+    // Write and flush the new config fully before touching the real file
+    let mut file = File::create(&temp).map_err(|e| format!("Failed to create {}: {}", temp.display(), e))?;
+    file.write_all(json.as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("Failed to write {}: {}", temp.display(), e))?;
+    drop(file);
+
+    // Only back up a config that parses, so a bad hand edit can't replace a
+    // good backup; a bad one is set aside instead of being overwritten
+    if path.exists() {
+        if read_config(&path).is_ok() {
+            fs::copy(&path, &backup).map_err(|e| format!("Failed to back up config: {}", e))?;
+        } else {
+            let invalid = path.with_file_name(INVALID_FILE_NAME);
+            fs::copy(&path, &invalid).map_err(|e| format!("Failed to set aside unreadable config: {}", e))?;
+            eprintln!("Moved unreadable config to {}", invalid.display());
+        }
+    }
+
+    fs::rename(&temp, &path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+}
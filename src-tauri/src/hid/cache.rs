@@ -0,0 +1,95 @@
+//! Encoded Image Cache
+//!
+//! A small LRU cache of encoded button images, keyed by a hash of the image
+//! and the encoding parameters. Showing the same icon again (flipping back to
+//! a page, refreshing a button) skips the rotate/encode work entirely.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+
+use image::DynamicImage;
+
+/// Bounded least-recently-used map from cache key to encoded image bytes.
+pub struct EncodeCache {
+    /// Most entries kept (0 disables the cache)
+    capacity: usize,
+    entries: HashMap<u64, Vec<u8>>,
+    /// Keys from least to most recently used
+    order: VecDeque<u64>,
+}
+
+impl EncodeCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Look up an encoded image, marking it as recently used.
+    pub fn get(&mut self, key: u64) -> Option<Vec<u8>> {
+        let data = self.entries.get(&key)?.clone();
+        self.touch(key);
+        Some(data)
+    }
+
+    /// Whether an encoded image is cached, without marking it as used.
+    pub fn contains(&self, key: u64) -> bool {
+        self.entries.contains_key(&key)
+    }
+
+    /// Store an encoded image, evicting the least recently used one if full.
+    pub fn insert(&mut self, key: u64, data: Vec<u8>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.entries.insert(key, data).is_some() {
+            self.touch(key);
+            return;
+        }
+        self.order.push_back(key);
+        self.evict();
+    }
+
+    /// Drop every entry.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+
+    /// Change how many entries are kept, dropping the oldest if needed.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict();
+    }
+
+    /// Move `key` to the most recently used end.
+    fn touch(&mut self, key: u64) {
+        if let Some(pos) = self.order.iter().position(|&k| k == key) {
+            self.order.remove(pos);
+        }
+        self.order.push_back(key);
+    }
+
+    fn evict(&mut self) {
+        while self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// Hash an image's pixels together with everything else that affects its encoding.
+pub fn cache_key(img: &DynamicImage, params: impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    img.width().hash(&mut hasher);
+    img.height().hash(&mut hasher);
+    img.color().hash(&mut hasher);
+    img.as_bytes().hash(&mut hasher);
+    params.hash(&mut hasher);
+    hasher.finish()
+}
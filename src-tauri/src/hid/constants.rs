@@ -16,9 +16,25 @@ pub const STREAM_DECK_ORIGINAL_PID: u16 = 0x0060;
 /// Has improved LCD buttons and USB-C connection
 pub const STREAM_DECK_MK2_PID: u16 = 0x0080;
 
+/// Stream Deck MK.2 Product ID of later production runs
+/// Same hardware and protocol as the original MK.2 PID
+pub const STREAM_DECK_MK2_V2_PID: u16 = 0x00a5;
+
+/// Stream Deck + Product ID
+/// 8 buttons, 4 rotary dials and a touch strip
+pub const STREAM_DECK_PLUS_PID: u16 = 0x0084;
+
+/// Stream Deck Neo Product ID
+/// 8 buttons, two touch points and a small info screen
+pub const STREAM_DECK_NEO_PID: u16 = 0x009a;
+
 /// Number of buttons on Stream Deck Original and MK.2
+/// (also the largest button count of any supported model)
 pub const BUTTON_COUNT: usize = 15;
 
+/// Largest dial count of any supported model (Stream Deck +)
+pub const DIAL_COUNT: usize = 4;
+
 /// Number of columns in the button grid
 pub const GRID_COLUMNS: usize = 5;
 
@@ -37,9 +53,42 @@ pub const MK2_HEADER_SIZE: usize = 8;
 /// Maximum image data per packet for MK.2
 pub const MK2_IMAGE_DATA_PER_PACKET: usize = MK2_PACKET_SIZE - MK2_HEADER_SIZE;
 
+/// Standby (idle/logo) image width for MK.2, covering the whole key area
+pub const MK2_STANDBY_IMAGE_WIDTH: u32 = 480;
+
+/// Standby (idle/logo) image height for MK.2, covering the whole key area
+pub const MK2_STANDBY_IMAGE_HEIGHT: u32 = 272;
+
 /// JPEG quality for encoding button images (0-100)
 pub const JPEG_QUALITY: u8 = 95;
 
+/// Lowest JPEG quality tried when shrinking an oversized image
+pub const MIN_JPEG_QUALITY: u8 = 30;
+
+/// How much to lower JPEG quality on each re-encode attempt
+pub const JPEG_QUALITY_STEP: u8 = 10;
+
+/// Largest encoded button image the MK.2 firmware reliably accepts (bytes)
+pub const MK2_MAX_IMAGE_BYTES: usize = 8 * 1024;
+
+/// Largest encoded button image for the Stream Deck + (its keys are 120x120)
+pub const PLUS_MAX_IMAGE_BYTES: usize = 16 * 1024;
+
+/// Largest encoded button image for the Stream Deck Neo (its keys are 96x96)
+pub const NEO_MAX_IMAGE_BYTES: usize = 12 * 1024;
+
+/// Default number of encoded button images each device keeps cached
+pub const ENCODE_CACHE_SIZE: usize = 64;
+
+/// Largest encoded button image the Original accepts (two 8191-byte packets minus headers)
+pub const ORIGINAL_MAX_IMAGE_BYTES: usize = 2 * (8191 - 16);
+
+/// Highest brightness the deck accepts (percent)
+pub const MAX_BRIGHTNESS: u8 = 100;
+
+/// Brightness applied when a deck connects, until it's changed
+pub const DEFAULT_BRIGHTNESS: u8 = 100;
+
 // =============================================================================
 // HID Report Structure Notes (for implementation reference)
 // =============================================================================
@@ -79,24 +128,200 @@ pub const JPEG_QUALITY: u8 = 95;
 // - Get firmware version
 // - Get serial number
 // - Reset the device
-// - Set brightness
+// - Set brightness (report 0x03 command 0x08 on MK.2 and newer,
+//   report 0x05 with magic bytes 55 aa d1 01 on the Original)
 //
 // =============================================================================
 
+/// How a model expects button images to be encoded and sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageProtocol {
+    /// BMP images in 8191-byte packets (Stream Deck Original)
+    Original,
+    /// JPEG images in 1024-byte packets (MK.2, Stream Deck +)
+    Gen2,
+}
+
+/// Hardware description of a Stream Deck model.
+///
+/// Everything that differs between models (button count, grid layout,
+/// image size) lives here so the rest of the code doesn't hardcode the MK.2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeviceModel {
+    /// USB Product ID for this model
+    pub product_id: u16,
+    /// Human-readable model name
+    pub name: &'static str,
+    /// Number of LCD buttons
+    pub button_count: usize,
+    /// Number of columns in the button grid
+    pub columns: usize,
+    /// Number of rows in the button grid
+    pub rows: usize,
+    /// Button image dimensions in pixels (square)
+    pub image_size: u32,
+    /// Image encoding and packet layout used by this model
+    pub protocol: ImageProtocol,
+    /// Number of rotary dials (Stream Deck + only)
+    pub dial_count: usize,
+    /// Largest encoded JPEG (bytes) the firmware accepts for a single button.
+    /// BMPs (the Original's encoding, or an override) aren't held to it.
+    pub max_image_bytes: usize,
+    /// Length of the input reports this model sends, report ID included
+    /// (the read buffer size; longer reports would be cut off)
+    pub input_report_len: usize,
+    /// Maps hardware key position to logical index (left-to-right, top-to-bottom).
+    /// `None` means the hardware already reports keys in logical order.
+    pub key_remap: Option<&'static [usize]>,
+    /// Whether key images must be rotated 180° before sending (the model's
+    /// LCDs show them upside down)
+    pub rotate_images: bool,
+}
+
+impl DeviceModel {
+    /// Convert a flat button index into a `(row, col)` grid position.
+    ///
+    /// Indices run left-to-right, top-to-bottom.
+    pub fn index_to_rc(&self, index: usize) -> (usize, usize) {
+        (index / self.columns, index % self.columns)
+    }
+
+    /// Convert a `(row, col)` grid position into a flat button index.
+    ///
+    /// Returns `None` if the position is outside the grid.
+    pub fn rc_to_index(&self, row: usize, col: usize) -> Option<usize> {
+        if row < self.rows && col < self.columns {
+            Some(row * self.columns + col)
+        } else {
+            None
+        }
+    }
+
+    /// Convert a key position as reported/addressed by the hardware into a logical index.
+    pub fn hardware_to_logical(&self, hardware_index: usize) -> usize {
+        match self.key_remap {
+            Some(remap) => remap.get(hardware_index).copied().unwrap_or(hardware_index),
+            None => hardware_index,
+        }
+    }
+
+    /// Convert a logical button index into the key position the hardware expects.
+    pub fn logical_to_hardware(&self, logical_index: usize) -> usize {
+        match self.key_remap {
+            Some(remap) => remap
+                .iter()
+                .position(|&logical| logical == logical_index)
+                .unwrap_or(logical_index),
+            None => logical_index,
+        }
+    }
+}
+
+/// The Original numbers its keys right-to-left within each row
+const ORIGINAL_KEY_REMAP: [usize; BUTTON_COUNT] = [4, 3, 2, 1, 0, 9, 8, 7, 6, 5, 14, 13, 12, 11, 10];
+
+/// Stream Deck Original: 15 buttons in a 5x3 grid
+pub const STREAM_DECK_ORIGINAL: DeviceModel = DeviceModel {
+    product_id: STREAM_DECK_ORIGINAL_PID,
+    name: "Stream Deck Original",
+    button_count: BUTTON_COUNT,
+    columns: GRID_COLUMNS,
+    rows: GRID_ROWS,
+    image_size: IMAGE_SIZE,
+    protocol: ImageProtocol::Original,
+    dial_count: 0,
+    max_image_bytes: ORIGINAL_MAX_IMAGE_BYTES,
+    // 4 header bytes, then one byte per key
+    input_report_len: 4 + BUTTON_COUNT,
+    key_remap: Some(&ORIGINAL_KEY_REMAP),
+    rotate_images: true,
+};
+
+/// Stream Deck MK.2: 15 buttons in a 5x3 grid
+pub const STREAM_DECK_MK2: DeviceModel = DeviceModel {
+    product_id: STREAM_DECK_MK2_PID,
+    name: "Stream Deck MK.2",
+    button_count: BUTTON_COUNT,
+    columns: GRID_COLUMNS,
+    rows: GRID_ROWS,
+    image_size: IMAGE_SIZE,
+    protocol: ImageProtocol::Gen2,
+    dial_count: 0,
+    max_image_bytes: MK2_MAX_IMAGE_BYTES,
+    // 4 header bytes, then one byte per key
+    input_report_len: 4 + BUTTON_COUNT,
+    key_remap: None,
+    rotate_images: true,
+};
+
+/// Stream Deck MK.2, later production runs: identical apart from the Product ID
+pub const STREAM_DECK_MK2_V2: DeviceModel = DeviceModel { product_id: STREAM_DECK_MK2_V2_PID, ..STREAM_DECK_MK2 };
+
+/// Stream Deck +: 8 buttons in a 4x2 grid, plus 4 dials and a touch strip
+pub const STREAM_DECK_PLUS: DeviceModel = DeviceModel {
+    product_id: STREAM_DECK_PLUS_PID,
+    name: "Stream Deck +",
+    button_count: 8,
+    columns: 4,
+    rows: 2,
+    image_size: 120,
+    protocol: ImageProtocol::Gen2,
+    dial_count: 4,
+    max_image_bytes: PLUS_MAX_IMAGE_BYTES,
+    // Touch reports are the longest: a swipe ends with two coordinates at bytes 10-13
+    input_report_len: 14,
+    key_remap: None,
+    // Unlike the 15-key decks and the Neo, the + shows key images the right way up
+    rotate_images: false,
+};
+
+/// Stream Deck Neo: 8 buttons in a 4x2 grid.
+///
+/// The two touch points are reported after the keys and the info screen has
+/// its own image command; neither is handled yet, only the keys.
+pub const STREAM_DECK_NEO: DeviceModel = DeviceModel {
+    product_id: STREAM_DECK_NEO_PID,
+    name: "Stream Deck Neo",
+    button_count: 8,
+    columns: 4,
+    rows: 2,
+    image_size: 96,
+    protocol: ImageProtocol::Gen2,
+    dial_count: 0,
+    max_image_bytes: NEO_MAX_IMAGE_BYTES,
+    // 8 keys, then the two touch points
+    input_report_len: 4 + 8 + 2,
+    key_remap: None,
+    rotate_images: true,
+};
+
+/// All supported Stream Deck models
+pub const SUPPORTED_MODELS: &[DeviceModel] =
+    &[STREAM_DECK_ORIGINAL, STREAM_DECK_MK2, STREAM_DECK_MK2_V2, STREAM_DECK_PLUS, STREAM_DECK_NEO];
+
 /// List of all supported Stream Deck Product IDs
 /// Used when scanning for devices
-pub const SUPPORTED_PIDS: &[u16] = &[STREAM_DECK_ORIGINAL_PID, STREAM_DECK_MK2_PID];
+pub const SUPPORTED_PIDS: &[u16] = &[
+    STREAM_DECK_ORIGINAL_PID,
+    STREAM_DECK_MK2_PID,
+    STREAM_DECK_MK2_V2_PID,
+    STREAM_DECK_PLUS_PID,
+    STREAM_DECK_NEO_PID,
+];
 
 /// Returns true if the given Product ID is a supported Stream Deck device
 pub fn is_supported_device(product_id: u16) -> bool {
     SUPPORTED_PIDS.contains(&product_id)
 }
 
+/// Returns the model description for a Product ID, if it's supported
+pub fn get_model(product_id: u16) -> Option<&'static DeviceModel> {
+    SUPPORTED_MODELS.iter().find(|model| model.product_id == product_id)
+}
+
 /// Returns a human-readable name for the device based on its Product ID
 pub fn get_device_name(product_id: u16) -> &'static str {
-    match product_id {
-        STREAM_DECK_ORIGINAL_PID => "Stream Deck Original",
-        STREAM_DECK_MK2_PID => "Stream Deck MK.2",
-        _ => "Unknown Stream Deck",
-    }
+    get_model(product_id)
+        .map(|model| model.name)
+        .unwrap_or("Unknown Stream Deck")
 }
@@ -4,21 +4,50 @@
 //! communication with Stream Deck devices. It handles device discovery,
 //! connection management, and button state reading.
 
+use std::fmt;
 use std::io::Cursor;
 use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant};
 
 use hidapi::{DeviceInfo as HidDeviceInfo, HidApi, HidDevice};
-use image::{DynamicImage, ImageFormat};
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use super::cache::{cache_key, EncodeCache};
 use super::constants::{
-    is_supported_device, BUTTON_COUNT, ELGATO_VENDOR_ID, IMAGE_SIZE, JPEG_QUALITY,
-    MK2_HEADER_SIZE, MK2_IMAGE_DATA_PER_PACKET, MK2_PACKET_SIZE, STREAM_DECK_MK2_PID,
+    get_model, is_supported_device, DeviceModel, ImageProtocol, BUTTON_COUNT, ELGATO_VENDOR_ID, ENCODE_CACHE_SIZE,
+    JPEG_QUALITY, JPEG_QUALITY_STEP, MAX_BRIGHTNESS, MIN_JPEG_QUALITY,
+    MK2_HEADER_SIZE, MK2_IMAGE_DATA_PER_PACKET, MK2_PACKET_SIZE, MK2_STANDBY_IMAGE_HEIGHT,
+    MK2_STANDBY_IMAGE_WIDTH, STREAM_DECK_MK2_PID, STREAM_DECK_MK2_V2_PID,
 };
 
 /// Byte offset where button data starts in HID input report
 pub const BUTTON_DATA_OFFSET: usize = 4;
 
+/// Most input reports handled by one `read_buttons` call, so a device that
+/// keeps sending can't hold the read (and the deck lock) forever
+const MAX_REPORTS_PER_READ: usize = 64;
+
+/// How long the read right after connecting waits for the deck's first report
+const PRIME_READ_TIMEOUT_MS: u64 = 20;
+
+/// Input report type (byte 1) for button state reports
+const INPUT_REPORT_BUTTONS: u8 = 0x00;
+
+/// Input report type (byte 1) for touch strip reports (Stream Deck +)
+const INPUT_REPORT_TOUCH: u8 = 0x02;
+
+/// Input report type (byte 1) for dial reports (Stream Deck +)
+const INPUT_REPORT_DIAL: u8 = 0x03;
+
+/// MK.2 output report command for setting a button image
+const MK2_SET_BUTTON_IMAGE_COMMAND: u8 = 0x07;
+
+/// MK.2 output report command for setting the standby (idle/logo) image
+const MK2_SET_STANDBY_IMAGE_COMMAND: u8 = 0x09;
+
 /// Information about a discovered Stream Deck device.
 /// This is returned to the frontend when listing available devices.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,6 +62,27 @@ pub struct DeviceInfo {
     pub vendor_id: u16,
     /// USB Product ID (identifies the specific Stream Deck model)
     pub product_id: u16,
+    /// Whether this model is supported (unsupported Elgato devices can still be listed)
+    pub supported: bool,
+    /// User-chosen name for this particular deck (e.g. "Left Deck"), from the config
+    pub nickname: Option<String>,
+}
+
+/// Raw information about any HID device, used for troubleshooting.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HidDeviceSummary {
+    /// The device path as reported by the OS
+    pub path: String,
+    /// USB Vendor ID
+    pub vendor_id: u16,
+    /// USB Product ID
+    pub product_id: u16,
+    /// Manufacturer string, if the device reports one
+    pub manufacturer: Option<String>,
+    /// Product string, if the device reports one
+    pub product_name: Option<String>,
+    /// USB interface number (-1 if unknown)
+    pub interface_number: i32,
 }
 
 impl DeviceInfo {
@@ -47,10 +97,201 @@ impl DeviceInfo {
             serial_number: device.serial_number().map(|s| s.to_string()),
             vendor_id: device.vendor_id(),
             product_id: device.product_id(),
+            supported: is_supported_device(device.product_id()),
+            nickname: None,
+        }
+    }
+
+    /// Stable key identifying this physical deck: the serial number if known,
+    /// otherwise the USB path (which can change between ports and reboots).
+    pub fn identity(&self) -> &str {
+        match self.serial_number.as_deref() {
+            Some(serial) if !serial.is_empty() => serial,
+            _ => &self.path,
+        }
+    }
+}
+
+/// Why `StreamDeck::connect` failed, for errors the user can act on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectErrorKind {
+    /// Another program has the deck open (usually the Elgato software)
+    InUse,
+    /// The OS won't let this user open the device
+    PermissionDenied,
+    /// Nothing at that path any more (e.g. unplugged)
+    NotFound,
+    Other,
+}
+
+/// Error returned by `StreamDeck::connect`.
+#[derive(Debug, Clone)]
+pub struct ConnectError {
+    pub kind: ConnectErrorKind,
+    pub message: String,
+}
+
+impl ConnectError {
+    fn other(message: impl Into<String>) -> Self {
+        Self {
+            kind: ConnectErrorKind::Other,
+            message: message.into(),
+        }
+    }
+
+    /// Work out why `open_path` failed from hidapi's (OS-specific) message.
+    fn from_open_error(device_path: &str, error: &str) -> Self {
+        let lower = error.to_lowercase();
+        let has = |needles: &[&str]| needles.iter().any(|needle| lower.contains(needle));
+
+        // Windows has no per-user permissions on HID devices, so "access denied"
+        // there means another program opened the deck exclusively
+        let in_use = has(&["used by another process", "sharing violation", "resource busy", "exclusive access"])
+            || (cfg!(target_os = "windows") && has(&["access is denied", "access denied"]));
+
+        let (kind, hint) = if in_use {
+            (
+                ConnectErrorKind::InUse,
+                "The Stream Deck is in use by another program. Close the Elgato Stream Deck software \
+                 (or another copy of this app) and try again.",
+            )
+        } else if has(&["permission denied", "access is denied", "access denied", "not permitted"]) {
+            (
+                ConnectErrorKind::PermissionDenied,
+                if cfg!(target_os = "linux") {
+                    "Permission denied opening the Stream Deck. Add a udev rule giving your user access \
+                     to Elgato devices (vendor 0fd9), then replug it."
+                } else {
+                    "Permission denied opening the Stream Deck."
+                },
+            )
+        } else if has(&["no such file", "no such device", "not found", "cannot find"]) {
+            (ConnectErrorKind::NotFound, "The Stream Deck wasn't found. It may have been unplugged.")
+        } else {
+            (ConnectErrorKind::Other, "Failed to open the Stream Deck.")
+        };
+
+        Self {
+            kind,
+            message: format!("{} ({}: {})", hint, device_path, error),
         }
     }
 }
 
+impl fmt::Display for ConnectError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<ConnectError> for String {
+    fn from(error: ConnectError) -> Self {
+        error.message
+    }
+}
+
+/// Image encoding used for button images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageEncoding {
+    /// JPEG (MK.2)
+    Jpeg,
+    /// BMP (Original)
+    Bmp,
+}
+
+/// How long a `set_button_images` batch took.
+#[derive(Debug, Clone, Copy)]
+pub struct BatchTiming {
+    /// Number of images in the batch
+    pub images: usize,
+    /// Time spent resizing and encoding (in parallel)
+    pub encode: Duration,
+    /// Time spent writing to the device
+    pub write: Duration,
+}
+
+/// Running totals of a deck's HID traffic and encoding work, since it was
+/// connected or the stats were last reset.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct IoStats {
+    /// Bytes sent to the device in output and feature reports
+    pub bytes_written: u64,
+    /// Output and feature reports sent
+    pub packets_sent: u64,
+    /// Button images encoded (JPEG/BMP)
+    pub images_encoded: u64,
+    /// Button images served from the encode cache instead of being encoded
+    pub encode_cache_hits: u64,
+    /// Input report reads, including ones that timed out with nothing to read
+    pub read_attempts: u64,
+}
+
+/// Empty margin drawn around button images so icons don't touch the key edges.
+///
+/// Written in the config as a number of pixels (`8`) or a percentage of the
+/// button size (`{ "percent": 10 }`).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ImagePadding {
+    Pixels(u32),
+    Percent { percent: f32 },
+}
+
+impl ImagePadding {
+    /// Margin on each side in pixels, for a button of `image_size` pixels.
+    pub fn pixels(&self, image_size: u32) -> u32 {
+        match *self {
+            ImagePadding::Pixels(pixels) => pixels,
+            ImagePadding::Percent { percent } => (image_size as f32 * percent.max(0.0) / 100.0).round() as u32,
+        }
+    }
+}
+
+/// Kind of touch on the Stream Deck + touch strip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TouchKind {
+    /// Quick tap
+    Short,
+    /// Press and hold
+    Long,
+}
+
+/// Non-button input from devices with dials or a touch strip.
+///
+/// Buttons are still reported through `read_buttons`; these events are queued
+/// while reading and collected with `take_input_events`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InputEvent {
+    /// A dial was turned. Positive `delta` is clockwise, in detents.
+    DialRotate { dial: usize, delta: i8 },
+    /// A dial was pushed in or released.
+    DialPress { dial: usize, pressed: bool },
+    /// The touch strip was tapped or long-pressed at `(x, y)`.
+    Touch { kind: TouchKind, x: u16, y: u16 },
+    /// A finger was dragged across the touch strip.
+    Swipe { from_x: u16, from_y: u16, to_x: u16, to_y: u16 },
+}
+
+/// Whether a button went down or came back up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ButtonEventKind {
+    Pressed,
+    Released,
+}
+
+/// A single button press or release, delivered through `StreamDeck::event_receiver`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ButtonEvent {
+    /// Logical button index (left-to-right, top-to-bottom, after any model key
+    /// remap), the same index used for button config and images
+    pub index: usize,
+    pub kind: ButtonEventKind,
+}
+
 /// Represents a connected Stream Deck device.
 ///
 /// This struct manages the HID connection and provides methods for
@@ -60,8 +301,278 @@ pub struct StreamDeck {
     device: HidDevice,
     /// Cached device information
     info: DeviceInfo,
+    /// Hardware description for this device's model
+    model: &'static DeviceModel,
     /// Current button states (true = pressed)
     button_states: [bool; BUTTON_COUNT],
+    /// Each state the buttons passed through during the last read, oldest first
+    read_states: Vec<[bool; BUTTON_COUNT]>,
+    /// Current dial press states (true = pressed)
+    dial_states: Vec<bool>,
+    /// Subscribers to button press/release events
+    event_senders: Vec<Sender<ButtonEvent>>,
+    /// Dial/touch events read since the last `take_input_events` call
+    input_events: Vec<InputEvent>,
+    /// Last image sent to each button (resized, before dimming/rotation/encoding)
+    rendered_images: Vec<Option<DynamicImage>>,
+    /// Forces an image encoding instead of picking one from the product ID (for debugging)
+    encoding_override: Option<ImageEncoding>,
+    /// Whether the model's 180° image rotation is applied (off for pre-rotated firmware)
+    rotate_images: bool,
+    /// Default margin around button images (buttons may override it)
+    image_padding: Option<ImagePadding>,
+    /// How much to darken each button's image (0.0 = unchanged, 1.0 = black)
+    button_dim: Vec<f32>,
+    /// Recently encoded images, so re-showing an icon skips the encode
+    encode_cache: EncodeCache,
+    /// Firmware version read at connect, if the device reported one
+    firmware_version: Option<String>,
+    /// Forces a button image command instead of `MK2_SET_BUTTON_IMAGE_COMMAND`
+    image_command_override: Option<u8>,
+    /// HID traffic and encoding counters
+    io_stats: IoStats,
+}
+
+/// Shrink an image inside a `size`-pixel button, leaving a black margin around it.
+///
+/// A zero margin returns the image unchanged.
+pub fn pad_image(img: DynamicImage, padding: ImagePadding, size: u32) -> DynamicImage {
+    // Leave at least a few pixels of image
+    let margin = padding.pixels(size).min(size / 2 - 2);
+    if margin == 0 {
+        return img;
+    }
+
+    let inner = size - 2 * margin;
+    let resized = img.resize(inner, inner, image::imageops::FilterType::Lanczos3).to_rgba8();
+
+    let mut canvas = RgbaImage::from_pixel(size, size, Rgba([0, 0, 0, 255]));
+    let x = (size - resized.width()) / 2;
+    let y = (size - resized.height()) / 2;
+    image::imageops::overlay(&mut canvas, &resized, x as i64, y as i64);
+
+    DynamicImage::ImageRgba8(canvas)
+}
+
+/// Multiply every pixel by `1.0 - dim`, leaving the image untouched when `dim` is 0.
+pub fn darken(img: &DynamicImage, dim: f32) -> DynamicImage {
+    if dim <= 0.0 {
+        return img.clone();
+    }
+
+    let factor = 1.0 - dim.min(1.0);
+    let mut rgb = img.to_rgb8();
+    for pixel in rgb.pixels_mut() {
+        for channel in pixel.0.iter_mut() {
+            *channel = (*channel as f32 * factor) as u8;
+        }
+    }
+    DynamicImage::ImageRgb8(rgb)
+}
+
+/// How a deck wants its button images encoded (everything but the pixels).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncodeSettings {
+    /// JPEG or BMP
+    pub encoding: ImageEncoding,
+    /// Rotate 180° before encoding (most genuine decks show images upside down)
+    pub rotate: bool,
+    /// Largest encoded JPEG the deck accepts, in bytes (BMPs aren't limited)
+    pub max_bytes: usize,
+}
+
+/// How a deck sizes, pads and encodes its button images, so images can be
+/// prepared for it without holding it (see `StreamDeck::render_settings`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderSettings {
+    /// Button image size in pixels
+    pub image_size: u32,
+    /// The deck's default margin (buttons may override it)
+    pub padding: Option<ImagePadding>,
+    pub encode: EncodeSettings,
+}
+
+/// An image resized for a deck with its encode cache key, ready to encode
+/// with `encode_button_images` and store with `StreamDeck::cache_encoded`
+pub struct CacheCandidate {
+    pub key: u64,
+    pub image: DynamicImage,
+    pub dim: f32,
+}
+
+/// Resize (already padded) button images for a deck and work out their encode
+/// cache keys, without touching the deck. Each image comes with its dim.
+pub fn cache_candidates(images: Vec<(DynamicImage, f32)>, settings: RenderSettings) -> Vec<CacheCandidate> {
+    let size = settings.image_size;
+    images
+        .into_par_iter()
+        .map(|(img, dim)| {
+            let image = img.resize_to_fill(size, size, image::imageops::FilterType::Lanczos3);
+            CacheCandidate {
+                key: encoded_image_key(&image, dim, settings.encode),
+                image,
+                dim,
+            }
+        })
+        .collect()
+}
+
+/// Encode cache key for an already-resized image shown with `dim` and `settings`
+fn encoded_image_key(img: &DynamicImage, dim: f32, settings: EncodeSettings) -> u64 {
+    cache_key(img, (dim.to_bits(), settings.rotate, settings.encoding, settings.max_bytes))
+}
+
+/// Darken, rotate and encode an already-resized button image.
+///
+/// JPEGs over the size limit are re-encoded at lower quality until they fit;
+/// an image that still doesn't fit at the minimum quality is an error. The
+/// limit is the firmware's JPEG payload limit, so it doesn't apply to BMPs:
+/// their size only depends on the button size, and the packet writer splits
+/// them over as many reports as they need.
+pub fn encode_button_image(img: &DynamicImage, dim: f32, settings: EncodeSettings) -> Result<Vec<u8>, String> {
+    let dimmed = darken(img, dim);
+
+    // Rotate 180° (the Stream Deck displays images upside down)
+    // This is equivalent to flipping both horizontally and vertically
+    let rotated = if settings.rotate {
+        dimmed.rotate180()
+    } else {
+        dimmed
+    };
+
+    match settings.encoding {
+        ImageEncoding::Jpeg => {
+            let encoded = encode_jpeg_within_limit(&rotated, settings.max_bytes)?;
+            if encoded.len() > settings.max_bytes {
+                return Err(format!(
+                    "Encoded image is {} bytes, over the {} byte limit",
+                    encoded.len(),
+                    settings.max_bytes
+                ));
+            }
+            Ok(encoded)
+        }
+        ImageEncoding::Bmp => encode_bmp(&rotated),
+    }
+}
+
+/// Encode several already-resized button images at once, one per CPU core.
+///
+/// Results are in the same order as `images`, which pairs each image with its dim.
+pub fn encode_button_images(images: &[(&DynamicImage, f32)], settings: EncodeSettings) -> Vec<Result<Vec<u8>, String>> {
+    images
+        .par_iter()
+        .map(|&(img, dim)| encode_button_image(img, dim, settings))
+        .collect()
+}
+
+/// Encode as JPEG, lowering the quality step by step until the result fits
+/// within `max_bytes`.
+///
+/// Some firmware silently rejects oversized images (the button just goes blank),
+/// so detailed photos are degraded rather than dropped. Returns the smallest
+/// attempt if even the minimum quality is too big; `encode_button_image` reports that.
+fn encode_jpeg_within_limit(img: &DynamicImage, max_bytes: usize) -> Result<Vec<u8>, String> {
+    let mut quality = JPEG_QUALITY;
+
+    loop {
+        let encoded = encode_jpeg(img, quality)?;
+        if encoded.len() <= max_bytes || quality <= MIN_JPEG_QUALITY {
+            return Ok(encoded);
+        }
+
+        quality = quality.saturating_sub(JPEG_QUALITY_STEP).max(MIN_JPEG_QUALITY);
+    }
+}
+
+/// Encode an image as JPEG for MK.2.
+fn encode_jpeg(img: &DynamicImage, quality: u8) -> Result<Vec<u8>, String> {
+    let mut buffer = Cursor::new(Vec::new());
+
+    // Convert to RGB8 for JPEG encoding
+    let rgb_img = img.to_rgb8();
+
+    // Create a JPEG encoder with the specified quality
+    let mut encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buffer, quality);
+
+    encoder
+        .encode(
+            rgb_img.as_raw(),
+            rgb_img.width(),
+            rgb_img.height(),
+            image::ExtendedColorType::Rgb8,
+        )
+        .map_err(|e| format!("Failed to encode JPEG: {}", e))?;
+
+    Ok(buffer.into_inner())
+}
+
+/// Encode an image as BMP for Original.
+fn encode_bmp(img: &DynamicImage) -> Result<Vec<u8>, String> {
+    let mut buffer = Cursor::new(Vec::new());
+
+    img.write_to(&mut buffer, ImageFormat::Bmp)
+        .map_err(|e| format!("Failed to encode BMP: {}", e))?;
+
+    Ok(buffer.into_inner())
+}
+
+/// Read the serial number via feature report.
+///
+/// Used as a fallback when hidapi doesn't report a serial from the USB descriptor.
+/// MK.2 and newer return it in report 0x06 starting at byte 2; the Original uses
+/// report 0x03 starting at byte 5. The serial is the ASCII run up to the first
+/// non-printable byte.
+fn read_serial_from_feature_report(device: &HidDevice, model: &DeviceModel) -> Option<String> {
+    let (report_id, offset) = match model.protocol {
+        ImageProtocol::Gen2 => (0x06, 2),
+        ImageProtocol::Original => (0x03, 5),
+    };
+
+    let mut buf = [0u8; 32];
+    buf[0] = report_id;
+
+    let bytes_read = device.get_feature_report(&mut buf).ok()?;
+    let serial: String = buf
+        .get(offset..bytes_read)?
+        .iter()
+        .take_while(|b| b.is_ascii_graphic())
+        .map(|&b| b as char)
+        .collect();
+
+    if serial.is_empty() {
+        None
+    } else {
+        Some(serial)
+    }
+}
+
+/// Read the firmware version with a feature report (e.g. "1.01.000").
+fn read_firmware_version(device: &HidDevice, model: &DeviceModel) -> Result<String, String> {
+    let (report_id, offset) = match model.protocol {
+        ImageProtocol::Gen2 => (0x05, 6),
+        ImageProtocol::Original => (0x04, 5),
+    };
+
+    let mut buf = [0u8; 32];
+    buf[0] = report_id;
+
+    let bytes_read = device
+        .get_feature_report(&mut buf)
+        .map_err(|e| format!("Failed to read firmware version: {}", e))?;
+    let version: String = buf
+        .get(offset..bytes_read)
+        .unwrap_or_default()
+        .iter()
+        .take_while(|b| b.is_ascii_graphic())
+        .map(|&b| b as char)
+        .collect();
+
+    if version.is_empty() {
+        Err("Device reported an empty firmware version".to_string())
+    } else {
+        Ok(version)
+    }
 }
 
 impl StreamDeck {
@@ -74,6 +585,11 @@ impl StreamDeck {
     /// This function enumerates all USB HID devices and filters for
     /// Elgato Stream Deck devices (matching VID and supported PIDs).
     ///
+    /// # Arguments
+    ///
+    /// * `include_unsupported` - Also return Elgato devices whose PID isn't supported,
+    ///   marked with `supported: false`, so unknown models don't silently disappear
+    ///
     /// # Returns
     ///
     /// A vector of `DeviceInfo` for each discovered Stream Deck.
@@ -81,7 +597,7 @@ impl StreamDeck {
     /// # Errors
     ///
     /// Returns an error if the HID API cannot be initialized.
-    pub fn discover() -> Result<Vec<DeviceInfo>, String> {
+    pub fn discover(include_unsupported: bool) -> Result<Vec<DeviceInfo>, String> {
         // TODO: Initialize the HID API
         // The HidApi::new() call initializes the underlying platform-specific
         // HID library (hidraw on Linux, IOHidManager on macOS, etc.)
@@ -90,14 +606,51 @@ impl StreamDeck {
         // TODO: Enumerate all HID devices and filter for Stream Decks
         // We filter by:
         // 1. Vendor ID must be Elgato (0x0fd9)
-        // 2. Product ID must be a supported Stream Deck model
+        // 2. Product ID must be a supported Stream Deck model (unless asked otherwise)
         let devices: Vec<DeviceInfo> = api
             .device_list()
             .filter(|device| {
                 device.vendor_id() == ELGATO_VENDOR_ID
-                    && is_supported_device(device.product_id())
+                    && (include_unsupported || is_supported_device(device.product_id()))
+            })
+            .map(|device| {
+                let mut info = DeviceInfo::from_hid_device(device);
+
+                // Same fallback as connect(), so two identical decks can be told apart
+                if info.serial_number.as_deref().is_none_or(str::is_empty) {
+                    if let (Some(model), Ok(hid)) = (get_model(info.product_id), device.open_device(&api)) {
+                        info.serial_number = read_serial_from_feature_report(&hid, model);
+                    }
+                }
+
+                info
+            })
+            .collect();
+
+        Ok(devices)
+    }
+
+    /// List every HID device the OS reports, without filtering for Elgato.
+    ///
+    /// This is a troubleshooting aid: it shows whether the OS sees the deck
+    /// at all and which PID it reports.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HID API cannot be initialized.
+    pub fn list_all_hid_devices() -> Result<Vec<HidDeviceSummary>, String> {
+        let api = HidApi::new().map_err(|e| format!("Failed to initialize HID API: {}", e))?;
+
+        let devices = api
+            .device_list()
+            .map(|device| HidDeviceSummary {
+                path: device.path().to_string_lossy().into_owned(),
+                vendor_id: device.vendor_id(),
+                product_id: device.product_id(),
+                manufacturer: device.manufacturer_string().map(|s| s.to_string()),
+                product_name: device.product_string().map(|s| s.to_string()),
+                interface_number: device.interface_number(),
             })
-            .map(DeviceInfo::from_hid_device)
             .collect();
 
         Ok(devices)
@@ -121,38 +674,84 @@ impl StreamDeck {
     ///
     /// Returns an error if:
     /// - The HID API cannot be initialized
-    /// - The device cannot be opened (permissions, already in use, etc.)
+    /// - The device cannot be opened; `kind` tells apart a deck that's in use,
+    ///   a permission problem and a deck that's gone
     /// - The device is not a supported Stream Deck
-    pub fn connect(device_path: &str) -> Result<Self, String> {
+    pub fn connect(device_path: &str) -> Result<Self, ConnectError> {
         // TODO: Initialize the HID API
-        let api = HidApi::new().map_err(|e| format!("Failed to initialize HID API: {}", e))?;
+        let api = HidApi::new().map_err(|e| ConnectError::other(format!("Failed to initialize HID API: {}", e)))?;
 
         // TODO: Open the device by path
         // This establishes the HID connection to the specific device
+        let c_path = std::ffi::CString::new(device_path)
+            .map_err(|_| ConnectError::other(format!("Invalid device path: {}", device_path)))?;
         let device = api
-            .open_path(std::ffi::CString::new(device_path).unwrap().as_c_str())
-            .map_err(|e| format!("Failed to open device: {}", e))?;
+            .open_path(c_path.as_c_str())
+            .map_err(|e| ConnectError::from_open_error(device_path, &e.to_string()))?;
 
         // TODO: Get device info for caching
         // We need to re-enumerate to get the DeviceInfo for this path
-        let info = api
+        let mut info = api
             .device_list()
             .find(|d| d.path().to_string_lossy() == device_path)
             .map(|d| DeviceInfo::from_hid_device(d))
-            .ok_or_else(|| "Device not found after opening".to_string())?;
+            .ok_or_else(|| ConnectError {
+                kind: ConnectErrorKind::NotFound,
+                message: "Device not found after opening".to_string(),
+            })?;
+
+        let model = get_model(info.product_id).ok_or_else(|| {
+            ConnectError::other(format!("Unsupported Stream Deck model (PID 0x{:04x})", info.product_id))
+        })?;
+
+        // Some units don't expose the serial in the USB descriptor; ask the device directly
+        if info.serial_number.as_deref().is_none_or(str::is_empty) {
+            info.serial_number = read_serial_from_feature_report(&device, model);
+        }
 
-        // TODO: Set non-blocking mode for button reading
-        // Non-blocking allows us to poll for button states without
-        // blocking the thread when no data is available
-        device
-            .set_blocking_mode(false)
-            .map_err(|e| format!("Failed to set non-blocking mode: {}", e))?;
+        let firmware_version = read_firmware_version(&device, model)
+            .map_err(|e| eprintln!("{}", e))
+            .ok();
+        println!(
+            "{} firmware {}",
+            model.name,
+            firmware_version.as_deref().unwrap_or("unknown")
+        );
 
-        Ok(Self {
+        let mut streamdeck = Self {
             device,
             info,
+            model,
             button_states: [false; BUTTON_COUNT],
-        })
+            read_states: Vec::new(),
+            dial_states: vec![false; model.dial_count],
+            event_senders: Vec::new(),
+            input_events: Vec::new(),
+            rendered_images: vec![None; BUTTON_COUNT],
+            encoding_override: None,
+            rotate_images: true,
+            image_padding: None,
+            button_dim: vec![0.0; BUTTON_COUNT],
+            encode_cache: EncodeCache::new(ENCODE_CACHE_SIZE),
+            firmware_version,
+            image_command_override: None,
+            io_stats: IoStats::default(),
+        };
+        streamdeck.prime_button_states();
+        Ok(streamdeck)
+    }
+
+    /// Seed the button states from whatever the deck reports right after
+    /// opening, so a key held down while connecting isn't seen as a new press.
+    ///
+    /// Nothing is subscribed yet, so no events go out; queued dial/touch
+    /// events are dropped too.
+    fn prime_button_states(&mut self) {
+        if let Err(e) = self.read_buttons_timeout(PRIME_READ_TIMEOUT_MS) {
+            eprintln!("Failed to read initial button states: {}", e);
+        }
+        self.read_states.clear();
+        self.input_events.clear();
     }
 
     /// Disconnect from the Stream Deck.
@@ -168,7 +767,7 @@ impl StreamDeck {
     // Button State Reading
     // =========================================================================
 
-    /// Read the current button states from the device.
+    /// Read the current button states from the device without waiting.
     ///
     /// This method polls the HID device for input reports and updates
     /// the internal button state array.
@@ -182,32 +781,160 @@ impl StreamDeck {
     ///
     /// Returns an error if reading from the device fails.
     pub fn read_buttons(&mut self) -> Result<&[bool; BUTTON_COUNT], String> {
-        // TODO: Read HID input report from the device
-        // Buffer size needs to accommodate the full report
-        // Stream Deck Original/MK.2 reports are typically 17-32 bytes
-        let mut buf = [0u8; 32];
-
-        // TODO: Attempt to read from the device (non-blocking)
-        // Returns the number of bytes read, or 0 if no data available
-        match self.device.read(&mut buf) {
-            Ok(bytes_read) if bytes_read > 0 => {
-                // Parse the input report
-                // The button states are at specific offsets depending on the model
-                // For Stream Deck Original/MK.2, buttons start at byte 4 or 5
-                for i in 0..BUTTON_COUNT {
-                    self.button_states[i] = buf[BUTTON_DATA_OFFSET + i] != 0;
-                }
-                let _ = bytes_read; // Acknowledge we received data
+        self.read_buttons_timeout(0)
+    }
+
+    /// Read the current button states, waiting up to `timeout_ms` for input.
+    ///
+    /// Blocks until a report arrives or the timeout passes, so a polling loop
+    /// reacts to presses immediately without sleeping between reads. Every
+    /// report already queued after that is read too, so a press and release
+    /// that both land between two polls still produce their events (see
+    /// `take_read_states`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading from the device fails.
+    pub fn read_buttons_timeout(&mut self, timeout_ms: u64) -> Result<&[bool; BUTTON_COUNT], String> {
+        // Sized for the model's full report, so the last keys aren't cut off
+        let mut buf = vec![0u8; self.model.input_report_len];
+
+        // Only the first read waits; the rest collect what's already queued
+        let mut timeout_ms = timeout_ms.min(i32::MAX as u64) as i32;
+        self.read_states.clear();
+
+        for _ in 0..MAX_REPORTS_PER_READ {
+            // Returns the number of bytes read, or 0 if nothing arrived in time
+            self.io_stats.read_attempts += 1;
+            let bytes_read = self
+                .device
+                .read_timeout(&mut buf, timeout_ms)
+                .map_err(|e| format!("Failed to read from device: {}", e))?;
+            if bytes_read == 0 {
+                break;
             }
-            Ok(_) => {
-                // No data available (non-blocking read returned 0)
+
+            self.apply_input_report(&buf[..bytes_read]);
+            timeout_ms = 0;
+        }
+
+        Ok(&self.button_states)
+    }
+
+    /// Apply one input report to the button (or dial/touch) state, sending
+    /// events for the buttons it changed.
+    fn apply_input_report(&mut self, report: &[u8]) {
+        // Devices with dials/touch strip tag each report with its type in byte 1
+        if self.model.dial_count > 0 && report[1] != INPUT_REPORT_BUTTONS {
+            self.parse_extended_input(report);
+            return;
+        }
+
+        // Parse the input report
+        // The button states are at specific offsets depending on the model
+        // For Stream Deck Original/MK.2, buttons start at byte 4 or 5
+        // Key bytes are in hardware order, so remap them to logical indices
+        let mut changed = false;
+        for i in 0..self.model.button_count {
+            let logical_index = self.model.hardware_to_logical(i);
+            let pressed = report.get(BUTTON_DATA_OFFSET + i).is_some_and(|&b| b != 0);
+
+            if pressed != self.button_states[logical_index] {
+                self.button_states[logical_index] = pressed;
+                changed = true;
+                self.send_button_event(ButtonEvent {
+                    index: logical_index,
+                    kind: if pressed { ButtonEventKind::Pressed } else { ButtonEventKind::Released },
+                });
             }
-            Err(e) => {
-                return Err(format!("Failed to read from device: {}", e));
+        }
+
+        if changed {
+            self.read_states.push(self.button_states);
+        }
+    }
+
+    /// Take each state the buttons passed through during the last read, oldest
+    /// first (empty if nothing changed).
+    ///
+    /// The last entry is the current state; earlier ones are presses or releases
+    /// that were already undone by a later report in the same read.
+    pub fn take_read_states(&mut self) -> Vec<[bool; BUTTON_COUNT]> {
+        std::mem::take(&mut self.read_states)
+    }
+
+    /// Subscribe to button press/release events.
+    ///
+    /// Events are sent as `read_buttons` sees state changes, so something must
+    /// keep polling (the app's polling loop does). This lets the device be used
+    /// without Tauri's event system. Dropping the receiver unsubscribes.
+    pub fn event_receiver(&mut self) -> Receiver<ButtonEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.event_senders.push(sender);
+        receiver
+    }
+
+    /// Deliver a button event to every subscriber, dropping ones that hung up.
+    fn send_button_event(&mut self, event: ButtonEvent) {
+        self.event_senders.retain(|sender| sender.send(event).is_ok());
+    }
+
+    /// Parse a dial or touch strip input report (Stream Deck +) into queued events.
+    ///
+    /// Dial reports: byte 4 is 0x00 for press/release (one state byte per dial
+    /// from byte 5) or 0x01 for rotation (one signed detent count per dial).
+    /// Touch reports: byte 4 is the touch kind (1 = short, 2 = long, 3 = swipe),
+    /// followed by little-endian u16 coordinates from byte 6.
+    fn parse_extended_input(&mut self, report: &[u8]) {
+        let read_u16 = |offset: usize| {
+            report
+                .get(offset..offset + 2)
+                .map(|b| u16::from_le_bytes([b[0], b[1]]))
+                .unwrap_or(0)
+        };
+
+        match report[1] {
+            INPUT_REPORT_DIAL => {
+                let Some(values) = report.get(5..5 + self.model.dial_count) else {
+                    return;
+                };
+
+                for (dial, &value) in values.iter().enumerate() {
+                    if report[4] == 0x01 {
+                        if value != 0 {
+                            self.input_events.push(InputEvent::DialRotate { dial, delta: value as i8 });
+                        }
+                    } else {
+                        let pressed = value != 0;
+                        if pressed != self.dial_states[dial] {
+                            self.dial_states[dial] = pressed;
+                            self.input_events.push(InputEvent::DialPress { dial, pressed });
+                        }
+                    }
+                }
+            }
+            INPUT_REPORT_TOUCH => {
+                let (x, y) = (read_u16(6), read_u16(8));
+                let event = match report.get(4) {
+                    Some(1) => InputEvent::Touch { kind: TouchKind::Short, x, y },
+                    Some(2) => InputEvent::Touch { kind: TouchKind::Long, x, y },
+                    Some(3) => InputEvent::Swipe {
+                        from_x: x,
+                        from_y: y,
+                        to_x: read_u16(10),
+                        to_y: read_u16(12),
+                    },
+                    _ => return,
+                };
+                self.input_events.push(event);
             }
+            _ => {}
         }
+    }
 
-        Ok(&self.button_states)
+    /// Take the dial/touch events read since the last call.
+    pub fn take_input_events(&mut self) -> Vec<InputEvent> {
+        std::mem::take(&mut self.input_events)
     }
 
     /// Get the current button states without reading from the device.
@@ -220,26 +947,107 @@ impl StreamDeck {
     // =========================================================================
 
     /// Get information about this device.
-    #[allow(dead_code)]
     pub fn get_device_info(&self) -> &DeviceInfo {
         &self.info
     }
 
+    /// Get the hardware description (grid layout, image size) for this device.
+    pub fn model(&self) -> &'static DeviceModel {
+        self.model
+    }
+
+    /// Convert a flat button index into a `(row, col)` position on this device's grid.
+    pub fn index_to_rc(&self, index: usize) -> (usize, usize) {
+        self.model.index_to_rc(index)
+    }
+
+    /// Convert a `(row, col)` position on this device's grid into a flat button index.
+    ///
+    /// Returns `None` if the position is outside the grid.
+    pub fn rc_to_index(&self, row: usize, col: usize) -> Option<usize> {
+        self.model.rc_to_index(row, col)
+    }
+
     /// Get the device's serial number.
-    #[allow(dead_code)]
     pub fn get_serial_number(&self) -> Option<&str> {
         self.info.serial_number.as_deref()
     }
 
-    /// Get the firmware version of the device.
+    /// Set the brightness of the whole deck.
     ///
-    /// # TODO
+    /// The device doesn't report its brightness, so callers have to remember
+    /// the value they set.
     ///
-    /// This requires sending a feature report request to the device.
-    #[allow(dead_code)]
-    pub fn get_firmware_version(&self) -> Result<String, String> {
-        // TODO: Send feature report to get firmware version
-        Err("Firmware version reading not yet implemented".to_string())
+    /// # Arguments
+    ///
+    /// * `percent` - Brightness from 0 (off) to 100 (full); higher values are clamped
+    pub fn set_brightness(&mut self, percent: u8) -> Result<(), String> {
+        let percent = percent.min(MAX_BRIGHTNESS);
+
+        let report: Vec<u8> = match self.model.protocol {
+            ImageProtocol::Gen2 => {
+                let mut report = vec![0u8; 32];
+                report[..3].copy_from_slice(&[0x03, 0x08, percent]);
+                report
+            }
+            ImageProtocol::Original => {
+                let mut report = vec![0u8; 17];
+                report[..6].copy_from_slice(&[0x05, 0x55, 0xaa, 0xd1, 0x01, percent]);
+                report
+            }
+        };
+
+        self.device
+            .send_feature_report(&report)
+            .map_err(|e| format!("Failed to set brightness: {}", e))?;
+        self.count_written(&report);
+        Ok(())
+    }
+
+    /// HID traffic and encoding counters since connecting (or the last reset).
+    pub fn io_stats(&self) -> IoStats {
+        self.io_stats
+    }
+
+    /// Zero the HID traffic and encoding counters.
+    pub fn reset_io_stats(&mut self) {
+        self.io_stats = IoStats::default();
+    }
+
+    /// Count a report sent to the device
+    fn count_written(&mut self, report: &[u8]) {
+        self.io_stats.bytes_written += report.len() as u64;
+        self.io_stats.packets_sent += 1;
+    }
+
+    /// Send an output report, counting it
+    fn write_report(&mut self, packet: &[u8]) -> Result<(), String> {
+        self.device
+            .write(packet)
+            .map_err(|e| format!("Failed to write image packet: {}", e))?;
+        self.count_written(packet);
+        Ok(())
+    }
+
+    /// Get the firmware version read when the device was connected.
+    ///
+    /// Returns `None` if the device didn't report one.
+    pub fn get_firmware_version(&self) -> Option<&str> {
+        self.firmware_version.as_deref()
+    }
+
+    /// Button image command used for this deck (Gen2 protocol only).
+    ///
+    /// Uses the override if one is set, otherwise the standard MK.2 command.
+    pub fn image_command(&self) -> u8 {
+        self.image_command_override.unwrap_or(MK2_SET_BUTTON_IMAGE_COMMAND)
+    }
+
+    /// Force a button image command, or go back to the standard one with `None`.
+    ///
+    /// Only affects Gen2 decks (MK.2 and newer).
+    pub fn set_image_command_override(&mut self, command: Option<u8>) {
+        self.image_command_override = command;
     }
 
     // =========================================================================
@@ -261,8 +1069,8 @@ impl StreamDeck {
     /// - Image processing fails
     /// - Writing to the device fails
     pub fn set_button_image<P: AsRef<Path>>(&mut self, button_index: usize, image_path: P) -> Result<(), String> {
-        if button_index >= BUTTON_COUNT {
-            return Err(format!("Button index {} out of range (0-{})", button_index, BUTTON_COUNT - 1));
+        if button_index >= self.model.button_count {
+            return Err(format!("Button index {} out of range (0-{})", button_index, self.model.button_count - 1));
         }
 
         // Load the image from file
@@ -279,15 +1087,156 @@ impl StreamDeck {
     /// * `button_index` - The button index (0-14)
     /// * `img` - The image data
     pub fn set_button_image_from_data(&mut self, button_index: usize, img: DynamicImage) -> Result<(), String> {
-        if button_index >= BUTTON_COUNT {
-            return Err(format!("Button index {} out of range (0-{})", button_index, BUTTON_COUNT - 1));
+        if button_index >= self.model.button_count {
+            return Err(format!("Button index {} out of range (0-{})", button_index, self.model.button_count - 1));
         }
 
-        // Process the image: resize, rotate, and encode
-        let image_data = self.prepare_image(img)?;
+        // Resize to the button size, maintaining aspect ratio and filling
+        let resized = img.resize_to_fill(
+            self.model.image_size,
+            self.model.image_size,
+            image::imageops::FilterType::Lanczos3,
+        );
+
+        // Process the image: dim, rotate and encode
+        let dim = self.button_dim[button_index];
+        let image_data = self.prepare_image(&resized, dim, self.model.max_image_bytes)?;
 
         // Send the image to the device
-        self.write_image_to_device(button_index, &image_data)
+        self.write_image_to_device(button_index, &image_data)?;
+
+        // Remember what the button is showing (used for live previews)
+        self.rendered_images[button_index] = Some(resized);
+        Ok(())
+    }
+
+    /// Set several button images at once.
+    ///
+    /// Images are resized and encoded in parallel, then written to the device
+    /// one after another (the HID connection can't be shared). Already-encoded
+    /// images come from the encode cache as usual. Stops at the first write
+    /// error; encoding errors skip just that button.
+    ///
+    /// Returns how long each phase took, for measuring throughput.
+    pub fn set_button_images(&mut self, updates: &[(usize, DynamicImage)]) -> Result<BatchTiming, String> {
+        self.write_batch(updates, None)
+    }
+
+    /// Like `set_button_images`, but if at least `min_pending` of the images
+    /// aren't in the encode cache, `pending` is shown on those buttons while
+    /// they're encoded, so a slow batch visibly gets going straight away.
+    pub fn set_button_images_with_pending(
+        &mut self,
+        updates: &[(usize, DynamicImage)],
+        pending: &DynamicImage,
+        min_pending: usize,
+    ) -> Result<BatchTiming, String> {
+        self.write_batch(updates, Some((pending, min_pending)))
+    }
+
+    fn write_batch(
+        &mut self,
+        updates: &[(usize, DynamicImage)],
+        pending: Option<(&DynamicImage, usize)>,
+    ) -> Result<BatchTiming, String> {
+        if let Some(&(index, _)) = updates.iter().find(|(index, _)| *index >= self.model.button_count) {
+            return Err(format!("Button index {} out of range (0-{})", index, self.model.button_count - 1));
+        }
+
+        let started = Instant::now();
+        let size = self.model.image_size;
+        let resized: Vec<DynamicImage> = updates
+            .par_iter()
+            .map(|(_, img)| img.resize_to_fill(size, size, image::imageops::FilterType::Lanczos3))
+            .collect();
+
+        // Only encode what isn't cached
+        let settings = self.encode_settings(self.model.max_image_bytes);
+        let keys: Vec<u64> = updates
+            .iter()
+            .zip(&resized)
+            .map(|(&(index, _), img)| encoded_image_key(img, self.button_dim[index], settings))
+            .collect();
+        let mut encoded: Vec<Option<Result<Vec<u8>, String>>> =
+            keys.iter().map(|&key| self.encode_cache.get(key).map(Ok)).collect();
+
+        let misses: Vec<usize> = (0..updates.len()).filter(|&i| encoded[i].is_none()).collect();
+        if let Some((pending, _)) = pending.filter(|&(_, min_pending)| misses.len() >= min_pending.max(1)) {
+            // Always the same image, so it's only ever encoded once
+            let key = encoded_image_key(pending, 0.0, settings);
+            let data = match self.encode_cache.get(key) {
+                Some(data) => data,
+                None => {
+                    let data = encode_button_image(pending, 0.0, settings)?;
+                    self.encode_cache.insert(key, data.clone());
+                    data
+                }
+            };
+            for &i in &misses {
+                self.write_image_to_device(updates[i].0, &data)?;
+            }
+        }
+        self.io_stats.encode_cache_hits += (updates.len() - misses.len()) as u64;
+        self.io_stats.images_encoded += misses.len() as u64;
+        let to_encode: Vec<(&DynamicImage, f32)> = misses
+            .iter()
+            .map(|&i| (&resized[i], self.button_dim[updates[i].0]))
+            .collect();
+        for (&i, result) in misses.iter().zip(encode_button_images(&to_encode, settings)) {
+            if let Ok(ref data) = result {
+                self.encode_cache.insert(keys[i], data.clone());
+            }
+            encoded[i] = Some(result);
+        }
+        let encode = started.elapsed();
+
+        let started = Instant::now();
+        for ((&(index, _), img), result) in updates.iter().zip(resized).zip(encoded) {
+            match result.unwrap_or_else(|| Err("not encoded".to_string())) {
+                Ok(data) => {
+                    self.write_image_to_device(index, &data)?;
+                    self.rendered_images[index] = Some(img);
+                }
+                Err(e) => eprintln!("Failed to encode image for button {}: {} for {}", index, e, self.model.name),
+            }
+        }
+
+        Ok(BatchTiming {
+            images: updates.len(),
+            encode,
+            write: started.elapsed(),
+        })
+    }
+
+    /// How this deck sizes, pads and encodes images, for preparing images
+    /// without holding the deck (e.g. pre-rendering the pages next to the
+    /// current one with `cache_candidates`).
+    pub fn render_settings(&self) -> RenderSettings {
+        RenderSettings {
+            image_size: self.model.image_size,
+            padding: self.image_padding,
+            encode: self.encode_settings(self.model.max_image_bytes),
+        }
+    }
+
+    /// Whether an image with this encode cache key is already cached
+    pub fn is_encoded(&self, key: u64) -> bool {
+        self.encode_cache.contains(key)
+    }
+
+    /// Store an image encoded elsewhere (see `render_settings`), so showing
+    /// it later only has to write it. Encodings made for other settings are
+    /// harmless: their keys never match.
+    pub fn cache_encoded(&mut self, key: u64, data: Vec<u8>) {
+        self.io_stats.images_encoded += 1;
+        self.encode_cache.insert(key, data);
+    }
+
+    /// Get the image last sent to a button, as it appears on the device (before per-key dimming).
+    ///
+    /// Returns `None` if nothing has been rendered to the button since connecting.
+    pub fn get_rendered_image(&self, button_index: usize) -> Option<&DynamicImage> {
+        self.rendered_images.get(button_index).and_then(|img| img.as_ref())
     }
 
     /// Clear a button's image (set to black).
@@ -296,96 +1245,186 @@ impl StreamDeck {
     ///
     /// * `button_index` - The button index (0-14)
     pub fn clear_button_image(&mut self, button_index: usize) -> Result<(), String> {
-        if button_index >= BUTTON_COUNT {
-            return Err(format!("Button index {} out of range (0-{})", button_index, BUTTON_COUNT - 1));
+        if button_index >= self.model.button_count {
+            return Err(format!("Button index {} out of range (0-{})", button_index, self.model.button_count - 1));
         }
 
         // Create a black image
-        let black_img = DynamicImage::new_rgb8(IMAGE_SIZE, IMAGE_SIZE);
+        let black_img = DynamicImage::new_rgb8(self.model.image_size, self.model.image_size);
         self.set_button_image_from_data(button_index, black_img)
     }
 
     /// Clear all button images (set all to black).
     pub fn clear_all_buttons(&mut self) -> Result<(), String> {
-        for i in 0..BUTTON_COUNT {
+        for i in 0..self.model.button_count {
             self.clear_button_image(i)?;
         }
         Ok(())
     }
 
-    /// Prepare an image for the Stream Deck.
+    /// Check whether this model supports a custom standby (idle/logo) image.
+    pub fn supports_standby_image(&self) -> bool {
+        matches!(self.info.product_id, STREAM_DECK_MK2_PID | STREAM_DECK_MK2_V2_PID)
+    }
+
+    /// Set the image shown while the device is idle/in standby.
     ///
-    /// This function:
-    /// 1. Resizes to 72x72 pixels
-    /// 2. Rotates 180° (Stream Deck displays images upside down)
-    /// 3. Encodes as JPEG (for MK.2) or BMP (for Original)
-    fn prepare_image(&self, img: DynamicImage) -> Result<Vec<u8>, String> {
-        // Resize to 72x72, maintaining aspect ratio and filling
+    /// The image is scaled to cover the whole key area and sent like a button
+    /// image, but with the standby image command. Models that don't support a
+    /// standby image are skipped with a warning.
+    ///
+    /// # Arguments
+    ///
+    /// * `image_path` - Path to the image file (PNG, JPEG, etc.)
+    pub fn set_standby_image<P: AsRef<Path>>(&mut self, image_path: P) -> Result<(), String> {
+        if !self.supports_standby_image() {
+            eprintln!(
+                "Warning: {} does not support a standby image, skipping",
+                self.info.product_name
+            );
+            return Ok(());
+        }
+
+        let img = image::open(image_path.as_ref())
+            .map_err(|e| format!("Failed to load image: {}", e))?;
+
         let resized = img.resize_to_fill(
-            IMAGE_SIZE,
-            IMAGE_SIZE,
+            MK2_STANDBY_IMAGE_WIDTH,
+            MK2_STANDBY_IMAGE_HEIGHT,
             image::imageops::FilterType::Lanczos3,
         );
+        // The per-button size limit doesn't apply to the full-screen standby image
+        let image_data = self.prepare_image(&resized, 0.0, usize::MAX)?;
 
-        // Rotate 180° (the Stream Deck displays images upside down)
-        // This is equivalent to flipping both horizontally and vertically
-        let rotated = resized.rotate180();
+        self.write_mk2_image_report(MK2_SET_STANDBY_IMAGE_COMMAND, 0, &image_data)
+    }
 
-        // Encode based on device type
-        if self.info.product_id == STREAM_DECK_MK2_PID {
-            self.encode_jpeg(&rotated)
-        } else {
-            self.encode_bmp(&rotated)
+    /// Shrink an image inside the button, leaving a black margin around it.
+    ///
+    /// `padding` overrides the device default; with neither set (or a zero
+    /// margin) the image is returned unchanged.
+    pub fn apply_padding(&self, img: DynamicImage, padding: Option<ImagePadding>) -> DynamicImage {
+        match padding.or(self.image_padding) {
+            Some(padding) => pad_image(img, padding, self.model.image_size),
+            None => img,
         }
     }
 
-    /// Encode an image as JPEG for MK.2.
-    fn encode_jpeg(&self, img: &DynamicImage) -> Result<Vec<u8>, String> {
-        let mut buffer = Cursor::new(Vec::new());
+    /// Prepare an already-resized image for the Stream Deck.
+    ///
+    /// This function:
+    /// 1. Darkens the image by `dim` (keys have no brightness control of their own)
+    /// 2. Rotates 180° (Stream Deck displays images upside down), unless disabled
+    /// 3. Encodes as JPEG (for MK.2) or BMP (for Original)
+    /// 4. If a JPEG is over the model's size limit, re-encodes at lower quality until it fits
+    ///
+    /// Results are cached, so preparing the same image with the same settings
+    /// again returns the earlier bytes without re-encoding.
+    fn prepare_image(&mut self, img: &DynamicImage, dim: f32, max_bytes: usize) -> Result<Vec<u8>, String> {
+        let settings = self.encode_settings(max_bytes);
+        let key = encoded_image_key(img, dim, settings);
+        if let Some(encoded) = self.encode_cache.get(key) {
+            self.io_stats.encode_cache_hits += 1;
+            return Ok(encoded);
+        }
 
-        // Convert to RGB8 for JPEG encoding
-        let rgb_img = img.to_rgb8();
+        self.io_stats.images_encoded += 1;
+        let encoded = encode_button_image(img, dim, settings)
+            .map_err(|e| format!("{} for {}", e, self.model.name))?;
 
-        // Create a JPEG encoder with the specified quality
-        let mut encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buffer, JPEG_QUALITY);
+        self.encode_cache.insert(key, encoded.clone());
+        Ok(encoded)
+    }
 
-        encoder
-            .encode(
-                rgb_img.as_raw(),
-                rgb_img.width(),
-                rgb_img.height(),
-                image::ExtendedColorType::Rgb8,
-            )
-            .map_err(|e| format!("Failed to encode JPEG: {}", e))?;
+    /// Encoding settings for this deck, with the given size limit
+    fn encode_settings(&self, max_bytes: usize) -> EncodeSettings {
+        EncodeSettings {
+            encoding: self.image_encoding(),
+            rotate: self.model.rotate_images && self.rotate_images,
+            max_bytes,
+        }
+    }
 
-        Ok(buffer.into_inner())
+    /// Get the image encoding used for this device.
+    ///
+    /// Uses the override if one is set, otherwise picks based on the model.
+    pub fn image_encoding(&self) -> ImageEncoding {
+        self.encoding_override.unwrap_or(match self.model.protocol {
+            ImageProtocol::Gen2 => ImageEncoding::Jpeg,
+            ImageProtocol::Original => ImageEncoding::Bmp,
+        })
     }
 
-    /// Encode an image as BMP for Original.
-    fn encode_bmp(&self, img: &DynamicImage) -> Result<Vec<u8>, String> {
-        let mut buffer = Cursor::new(Vec::new());
+    /// The forced image encoding, if any (see `set_encoding_override`)
+    pub fn encoding_override(&self) -> Option<ImageEncoding> {
+        self.encoding_override
+    }
+
+    /// Force a specific image encoding, bypassing product ID detection.
+    ///
+    /// Pass `None` to go back to auto-detection. This is a diagnostic knob for
+    /// devices that are mis-detected and show corrupted images.
+    pub fn set_encoding_override(&mut self, encoding: Option<ImageEncoding>) {
+        self.encoding_override = encoding;
+    }
+
+    /// Set the default margin drawn around button images (`None` for edge to edge).
+    pub fn set_image_padding(&mut self, padding: Option<ImagePadding>) {
+        self.image_padding = padding;
+    }
+
+    /// Set how much to darken a button's image, from 0.0 (unchanged) to 1.0 (black).
+    ///
+    /// Applies to images sent after the call. Each key is its own LCD but the
+    /// device only has a global brightness, so the pixels are darkened instead.
+    pub fn set_button_dim(&mut self, button_index: usize, dim: f32) {
+        if let Some(slot) = self.button_dim.get_mut(button_index) {
+            *slot = dim.clamp(0.0, 1.0);
+        }
+    }
 
-        img.write_to(&mut buffer, ImageFormat::Bmp)
-            .map_err(|e| format!("Failed to encode BMP: {}", e))?;
+    /// Forget all cached encoded images, so the next images are encoded from scratch.
+    pub fn clear_encode_cache(&mut self) {
+        self.encode_cache.clear();
+    }
 
-        Ok(buffer.into_inner())
+    /// Set how many encoded images to keep cached (0 disables the cache).
+    pub fn set_encode_cache_size(&mut self, entries: usize) {
+        self.encode_cache.set_capacity(entries);
+    }
+
+    /// Enable or disable the 180° rotation applied to every image.
+    ///
+    /// Only affects models that rotate their images (not the Stream Deck +).
+    /// Genuine devices need it; some clones and specially-flashed units
+    /// display images the right way up already.
+    pub fn set_rotate_images(&mut self, rotate: bool) {
+        self.rotate_images = rotate;
     }
 
     /// Write image data to the Stream Deck device.
     ///
     /// The image is sent in chunks via HID output reports.
+    /// `button_index` is the logical index; it's remapped to the hardware key position here.
     fn write_image_to_device(&mut self, button_index: usize, image_data: &[u8]) -> Result<(), String> {
-        if self.info.product_id == STREAM_DECK_MK2_PID {
-            self.write_image_mk2(button_index, image_data)
-        } else {
-            self.write_image_original(button_index, image_data)
+        let hardware_index = self.model.logical_to_hardware(button_index);
+
+        match self.model.protocol {
+            ImageProtocol::Gen2 => self.write_image_mk2(hardware_index, image_data),
+            ImageProtocol::Original => self.write_image_original(hardware_index, image_data),
         }
     }
 
-    /// Write image to MK.2 Stream Deck.
+    /// Write image to MK.2 Stream Deck (also used by the Stream Deck +).
     ///
-    /// MK.2 uses 1024-byte packets with an 8-byte header.
+    /// MK.2 uses 1024-byte packets with an 8-byte header. The command byte
+    /// can be overridden for odd units (see `image_command`).
     fn write_image_mk2(&mut self, button_index: usize, image_data: &[u8]) -> Result<(), String> {
+        self.write_mk2_image_report(self.image_command(), button_index as u8, image_data)
+    }
+
+    /// Send encoded image data to an MK.2 in chunks using the given report command.
+    fn write_mk2_image_report(&mut self, command: u8, target: u8, image_data: &[u8]) -> Result<(), String> {
         let total_length = image_data.len();
         let mut bytes_sent = 0;
         let mut page_number = 0;
@@ -401,14 +1440,14 @@ impl StreamDeck {
 
             // MK.2 Header format (8 bytes):
             // [0]: Report ID (0x02)
-            // [1]: Command (0x07 for set image)
-            // [2]: Button index
+            // [1]: Command (0x07 for button image, 0x09 for standby image)
+            // [2]: Button index (0 for the standby image)
             // [3]: Is last packet (0x01 if true, 0x00 if false)
             // [4-5]: Payload length (little-endian u16)
             // [6-7]: Page number (little-endian u16)
             packet[0] = 0x02;
-            packet[1] = 0x07;
-            packet[2] = button_index as u8;
+            packet[1] = command;
+            packet[2] = target;
             packet[3] = if is_last_packet { 0x01 } else { 0x00 };
             packet[4] = (payload_length & 0xFF) as u8;
             packet[5] = ((payload_length >> 8) & 0xFF) as u8;
@@ -420,9 +1459,7 @@ impl StreamDeck {
             packet[MK2_HEADER_SIZE..MK2_HEADER_SIZE + payload_length].copy_from_slice(data_slice);
 
             // Write to device
-            self.device
-                .write(&packet)
-                .map_err(|e| format!("Failed to write image packet: {}", e))?;
+            self.write_report(&packet)?;
 
             bytes_sent += payload_length;
             page_number += 1;
@@ -471,9 +1508,7 @@ impl StreamDeck {
             let data_slice = &image_data[bytes_sent..bytes_sent + payload_length];
             packet[ORIGINAL_HEADER_SIZE..ORIGINAL_HEADER_SIZE + payload_length].copy_from_slice(data_slice);
 
-            self.device
-                .write(&packet)
-                .map_err(|e| format!("Failed to write image packet: {}", e))?;
+            self.write_report(&packet)?;
 
             bytes_sent += payload_length;
             page_number += 1;
@@ -4,6 +4,7 @@
 //! devices over USB HID. It provides device discovery, connection management,
 //! and button state reading functionality.
 
+pub mod cache;
 pub mod constants;
 pub mod device;
 
@@ -6,14 +6,30 @@
 //!
 //! Image sources:
 //! - Custom user images (file paths)
+//! - Remote images (http(s) URLs, downloaded and cached)
 //! - Built-in icons (bundled with app)
 //! - Generated text images (TODO)
 //! 
-use imageproc::drawing::draw_text_mut;
-use image::{Rgb, RgbImage};
-use ab_glyph::{FontArc, PxScale};
-use crate::config::{ButtonConfig, ButtonImage};
-use crate::hid::constants::BUTTON_COUNT;
+mod remote;
+
+use imageproc::drawing::{draw_text_mut, text_size};
+use image::{DynamicImage, Rgb, RgbImage, Rgba, RgbaImage};
+use std::collections::HashSet;
+use std::sync::atomic::Ordering;
+use std::sync::{Condvar, Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+use ab_glyph::{Font, FontArc, FontVec, PxScale};
+use font_kit::family_name::FamilyName;
+use font_kit::handle::Handle;
+use font_kit::properties::Properties;
+use font_kit::source::SystemSource;
+use crate::actions::ActionRegistry;
+use crate::config::{Action, ButtonConfig, ButtonImage, Config, TRANSIENT_PAGE};
+use crate::hid::constants::{DeviceModel, BUTTON_COUNT, IMAGE_SIZE};
+use crate::hid::device::{
+    cache_candidates, darken, encode_button_images, pad_image, ImagePadding, RenderSettings, StreamDeck,
+};
 use crate::AppState;
 use tauri::{AppHandle, Manager, State};
 
@@ -21,29 +37,66 @@ use tauri::{AppHandle, Manager, State};
 /// Returns an absolute file path that the frontend converts to a URL.
 ///
 /// Priority:
-/// 1. Custom image override (button_config.image)
-/// 2. Action default icon (based on action type)
-/// 3. None (no image)
+/// 1. Custom image override (button_config.toggle_images for the current state, then button_config.image)
+/// 2. Action default icon (built-in for the action type, or registered by a plugin)
+/// 3. Generated text image from the label
+/// 4. None (no image)
+///
+/// If the button has both an image and a label and `label_overlay` is set,
+/// the label is composited onto the bottom of the image.
 pub fn resolve_button_image(button_config: &ButtonConfig, app_handle: &AppHandle) -> Option<String> {
+    match (resolve_image_source(button_config, app_handle), &button_config.label) {
+        (Some(path), Some(label)) if button_config.label_overlay => {
+            let text_color = button_config.label_color.as_deref();
+            // Fall back to the plain image if the overlay can't be rendered
+            overlay_label(&path, label, text_color, app_handle).or(Some(path))
+        }
+        (Some(path), _) => Some(path),
+        (None, Some(label)) => {
+            println!("No image found, using the label.");
+            generate_text_image(label, app_handle)
+        }
+        (None, None) => {
+            println!("No image, no label");
+            None // no image or label womp womp
+        }
+    }
+}
+
+/// Resolve the image file for a button, ignoring its label.
+fn resolve_image_source(button_config: &ButtonConfig, app_handle: &AppHandle) -> Option<String> {
+    // State-dependent images take precedence for toggle buttons
+    if let Some(ref toggle_images) = button_config.toggle_images {
+        let image = if button_config.toggled.unwrap_or(false) {
+            &toggle_images.on
+        } else {
+            &toggle_images.off
+        };
+        return resolve_custom_image(image, app_handle);
+    }
+
     // Checking for custom image first
     if let Some(ref image) = button_config.image {
         return resolve_custom_image(image, app_handle);
     }
-    
-    println!("No custom image for this button");
-    // System default second - only return if icon is actually found
-    if let Some(icon_name) = button_config.action.default_icon() {
-        if let Some(path) = resolve_builtin_icon(icon_name, app_handle) {
-            return Some(path);
-        }
-    }
 
-    println!("No icon found, checking for a label.");
-    if let Some(ref label_name) = button_config.label{
-        return generate_text_image(label_name, app_handle);
+    // Then the action's default icon
+    resolve_action_icon(&button_config.action, app_handle)
+}
+
+/// Resolve the default icon for an action to an absolute file path:
+/// the built-in icon for its type, or one registered at runtime (e.g. by a plugin).
+pub fn resolve_action_icon(action: &Action, app_handle: &AppHandle) -> Option<String> {
+    // Built-in icon first - only return if the icon is actually found
+    if let Some(icon_name) = action.default_icon() {
+        return resolve_builtin_icon(icon_name, app_handle);
     }
-    println!("No image, no label");
-    None // no image or label womp womp
+
+    app_handle
+        .try_state::<ActionRegistry>()?
+        .icon_for(&action.action_type)
+        .filter(|path| path.exists())
+        .map(|path| path.to_string_lossy().to_string())
 }
 
 /// Resolve a custom image to an absolute file path
@@ -57,6 +110,10 @@ fn resolve_custom_image(image: &ButtonImage, app_handle: &AppHandle) -> Option<S
             // Built-in icon referenced by name
             resolve_builtin_icon(name, app_handle)
         }
+        ButtonImage::Url { url, ttl_secs } => {
+            // Downloaded into the cache dir, then used like a file
+            remote::resolve_url_image(url, *ttl_secs, app_handle)
+        }
         ButtonImage::Text { text, .. } => {
             // TODO: Generate actual text image
             None
@@ -89,16 +146,66 @@ fn resolve_builtin_icon(icon_name: &str, app_handle: &AppHandle) -> Option<Strin
     None
 }
 
+/// Whether a file in the icons folder is one of the app's own icons (generated
+/// by `tauri icon`) rather than a button icon
+fn is_app_icon(name: &str) -> bool {
+    name == "icon"
+        || name.starts_with("Square")
+        || name == "StoreLogo"
+        || name.starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// List the built-in button icons as (name, absolute path), sorted by name.
+///
+/// Looks in the bundled resources first, then (in dev mode) next to src-tauri.
+pub fn list_builtin_icons(app_handle: &AppHandle) -> Vec<(String, String)> {
+    let resource_dir = app_handle
+        .path()
+        .resolve("icons", tauri::path::BaseDirectory::Resource)
+        .ok()
+        .filter(|dir| dir.is_dir());
+    let Some(dir) = resource_dir.or_else(|| std::path::PathBuf::from("icons").canonicalize().ok()) else {
+        eprintln!("Warning: Could not find the icons folder");
+        return Vec::new();
+    };
+
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        eprintln!("Warning: Could not read {}", dir.display());
+        return Vec::new();
+    };
+
+    let mut icons: Vec<(String, String)> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "png"))
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy().to_string();
+            (!is_app_icon(&name)).then(|| (name, path.to_string_lossy().to_string()))
+        })
+        .collect();
+    icons.sort();
+    icons
+}
+
 /// Get all button image paths for the current page.
 ///
 /// Returns a Vec of Option<String> where each index corresponds to a button.
 /// This is the shared logic used by both the frontend (get_button_images command)
 /// and the device sync (sync_images_to_device).
 pub fn get_current_page_images(state: &State<'_, AppState>, app_handle: &AppHandle) -> Vec<Option<String>> {
+    get_page_images(state, app_handle, current_page(state))
+}
+
+/// Index of the page being shown
+fn current_page(state: &State<'_, AppState>) -> usize {
+    state.config.lock().unwrap().shown_page()
+}
+
+/// All button image paths for a page (see `get_current_page_images`).
+fn get_page_images(state: &State<'_, AppState>, app_handle: &AppHandle, page_index: usize) -> Vec<Option<String>> {
     let config = state.config.lock().unwrap();
     let mut images: Vec<Option<String>> = vec![None; BUTTON_COUNT];
 
-    if let Some(page) = config.pages.get(config.current_page) {
+    if let Some(page) = config.page(page_index) {
         for i in 0..BUTTON_COUNT {
             if let Some(button_config) = page.buttons.get(&i) {
                 images[i] = resolve_button_image(button_config, app_handle);
@@ -109,35 +216,749 @@ pub fn get_current_page_images(state: &State<'_, AppState>, app_handle: &AppHand
     images
 }
 
+/// Per-button padding overrides for a page (`None` uses the device default).
+fn get_page_padding(state: &State<'_, AppState>, page_index: usize) -> Vec<Option<ImagePadding>> {
+    let config = state.config.lock().unwrap();
+    let mut padding = vec![None; BUTTON_COUNT];
+
+    if let Some(page) = config.page(page_index) {
+        for (&i, button_config) in &page.buttons {
+            if i < BUTTON_COUNT {
+                padding[i] = button_config.padding;
+            }
+        }
+    }
+
+    padding
+}
+
+/// Per-button dimming for a page (0.0 = full brightness).
+fn get_page_dims(state: &State<'_, AppState>, page_index: usize) -> Vec<f32> {
+    let config = state.config.lock().unwrap();
+    let mut dims = vec![0.0; BUTTON_COUNT];
+
+    if let Some(page) = config.page(page_index) {
+        for (&i, button_config) in &page.buttons {
+            if i < BUTTON_COUNT {
+                dims[i] = button_config.dim;
+            }
+        }
+    }
+
+    dims
+}
+
+/// Border thickness (pixels) drawn around the button for the active page
+const ACTIVE_PAGE_BORDER: u32 = 4;
+
+/// Border color drawn around the button for the active page
+const ACTIVE_PAGE_BORDER_COLOR: Rgba<u8> = Rgba([255, 200, 0, 255]);
+
+/// Find buttons on a page that navigate to that same page.
+///
+/// These are "page 1/2/3" style buttons, and the one for the page being
+/// shown gets highlighted so it's obvious which page is active.
+fn get_page_active_buttons(state: &State<'_, AppState>, page_index: usize) -> Vec<bool> {
+    let config = state.config.lock().unwrap();
+    let mut active = vec![false; BUTTON_COUNT];
+
+    if let Some(page) = config.page(page_index) {
+        for (&i, button_config) in &page.buttons {
+            if i < BUTTON_COUNT
+                && button_config.action.target_page(&config.pages) == Some(page_index)
+            {
+                active[i] = true;
+            }
+        }
+    }
+
+    active
+}
+
+/// Draw the active-page highlight border onto a button image.
+fn highlight_active_button(img: DynamicImage, size: u32) -> DynamicImage {
+    let mut canvas: RgbaImage = img
+        .resize_to_fill(size, size, image::imageops::FilterType::Lanczos3)
+        .to_rgba8();
+
+    for (x, y, pixel) in canvas.enumerate_pixels_mut() {
+        if x < ACTIVE_PAGE_BORDER
+            || y < ACTIVE_PAGE_BORDER
+            || x >= size - ACTIVE_PAGE_BORDER
+            || y >= size - ACTIVE_PAGE_BORDER
+        {
+            *pixel = ACTIVE_PAGE_BORDER_COLOR;
+        }
+    }
+
+    DynamicImage::ImageRgba8(canvas)
+}
+
+/// Fewest images a page switch has to encode to show pending placeholders
+/// when `device.sync_progress` is set
+const SYNC_PROGRESS_MIN_IMAGES: usize = 8;
+
+/// Color of the dots on a pending button
+const PENDING_DOT_COLOR: Rgba<u8> = Rgba([110, 110, 110, 255]);
+
+/// Placeholder for a button whose new image is still on its way: three gray
+/// dots on black. Always the same image, so it's encoded once and then cached.
+fn pending_image(size: u32) -> DynamicImage {
+    let mut image = RgbaImage::from_pixel(size, size, Rgba([0, 0, 0, 255]));
+    let radius = (size / 24).max(1) as i32;
+    let spacing = size as i32 / 5;
+    let center = size as i32 / 2;
+
+    for dot in -1..=1 {
+        let dot_x = center + dot * spacing;
+        for y in center - radius..=center + radius {
+            for x in dot_x - radius..=dot_x + radius {
+                let (dx, dy) = (x - dot_x, y - center);
+                if dx * dx + dy * dy <= radius * radius {
+                    image.put_pixel(x as u32, y as u32, PENDING_DOT_COLOR);
+                }
+            }
+        }
+    }
+
+    DynamicImage::ImageRgba8(image)
+}
+
+/// How long a sync request waits for others to join it before the deck is redrawn
+const SYNC_DEBOUNCE: Duration = Duration::from_millis(30);
+
+/// Longest an overlay (an error flash, a prompt) waits for a pending sync
+/// before drawing anyway
+const SYNC_WAIT_LIMIT: Duration = Duration::from_secs(2);
+
+/// Requests for the image sync worker, shared through `AppState`
+#[derive(Default)]
+pub struct SyncQueue {
+    status: Mutex<SyncStatus>,
+    changed: Condvar,
+}
+
+#[derive(Default)]
+struct SyncStatus {
+    /// The deck needs redrawing
+    dirty: bool,
+    /// A sync is writing to the deck
+    running: bool,
+    /// The worker thread has been started
+    started: bool,
+}
+
 /// Sync button images to the physical Stream Deck LCD.
 ///
-/// This resolves images for all configured buttons on the current page
-/// and sends them to the device.
+/// Requests are coalesced: a single worker thread syncs `SYNC_DEBOUNCE` after
+/// a request, and any further requests made before then (e.g. a weather update
+/// landing right after a page change) are covered by that one sync. Requests
+/// made during a sync mark the deck dirty again, so the worker syncs once more
+/// when it's done; syncs never overlap, so the latest page is always drawn last.
 pub fn sync_images_to_device(state: &State<'_, AppState>, app_handle: &AppHandle) {
-    // Get all image paths
-    let images = get_current_page_images(state, app_handle);
-
-    // Collect paths with their indices (filtering out None values)
-    let image_paths: Vec<(usize, &String)> = images
-        .iter()
-        .enumerate()
-        .filter_map(|(i, opt)| opt.as_ref().map(|path| (i, path)))
-        .collect();
+    let queue = &state.image_sync;
+    let mut status = queue.status.lock().unwrap();
+    status.dirty = true;
+    if !status.started {
+        status.started = true;
+        let app_handle = app_handle.clone();
+        thread::spawn(move || sync_worker(app_handle));
+    }
+    queue.changed.notify_all();
+}
 
-    // Send images to the device
-    let mut streamdeck_guard = state.streamdeck.lock().unwrap();
+/// Redraw the deck whenever it's marked dirty, until the app exits
+fn sync_worker(app_handle: AppHandle) {
+    let state = app_handle.state::<AppState>();
+    let queue = &state.image_sync;
 
-    if let Some(ref mut streamdeck) = *streamdeck_guard {
-        for (button_index, path) in image_paths {
-            if let Err(e) = streamdeck.set_button_image(button_index, path) {
-                eprintln!("Failed to set image for button {}: {}", button_index, e);
-            } else {
-                println!("Set image for button {}: {}", button_index, path);
+    loop {
+        {
+            let status = queue.status.lock().unwrap();
+            drop(queue.changed.wait_while(status, |status| !status.dirty).unwrap());
+        }
+        thread::sleep(SYNC_DEBOUNCE);
+
+        {
+            let mut status = queue.status.lock().unwrap();
+            // Cleared before syncing, so a change made during the sync marks it dirty again
+            status.dirty = false;
+            status.running = true;
+        }
+        sync_images_now(&state, &app_handle);
+        {
+            queue.status.lock().unwrap().running = false;
+            queue.changed.notify_all();
+        }
+
+        prerender_adjacent_pages(&state, &app_handle);
+    }
+}
+
+/// Wait (up to `SYNC_WAIT_LIMIT`) for a requested sync to be written, so an
+/// overlay drawn right after a sync request isn't drawn over by it
+fn wait_for_sync(state: &AppState) {
+    let queue = &state.image_sync;
+    let status = queue.status.lock().unwrap();
+    let _ = queue
+        .changed
+        .wait_timeout_while(status, SYNC_WAIT_LIMIT, |status| status.dirty || status.running)
+        .unwrap();
+}
+
+/// Everything needed to draw one page's buttons, indexed by button
+struct PageImages {
+    paths: Vec<Option<String>>,
+    active: Vec<bool>,
+    padding: Vec<Option<ImagePadding>>,
+    dims: Vec<f32>,
+}
+
+impl PageImages {
+    fn load(state: &State<'_, AppState>, app_handle: &AppHandle, page_index: usize) -> Self {
+        Self {
+            paths: get_page_images(state, app_handle, page_index),
+            active: get_page_active_buttons(state, page_index),
+            padding: get_page_padding(state, page_index),
+            dims: get_page_dims(state, page_index),
+        }
+    }
+
+    /// Open every button's image and pad/highlight it for `streamdeck`.
+    /// Buttons whose image fails to load are logged and left out.
+    fn open_images(&self, streamdeck: &StreamDeck) -> Vec<(usize, DynamicImage)> {
+        self.open_images_for(streamdeck.render_settings())
+    }
+
+    /// Like `open_images`, for a deck with these settings (no deck needed)
+    fn open_images_for(&self, settings: RenderSettings) -> Vec<(usize, DynamicImage)> {
+        let image_size = settings.image_size;
+        self.paths
+            .iter()
+            .enumerate()
+            .filter_map(|(button_index, path)| {
+                let path = path.as_ref()?;
+                match image::open(path) {
+                    Ok(img) => {
+                        let img = match self.padding[button_index].or(settings.padding) {
+                            Some(padding) => pad_image(img, padding, image_size),
+                            None => img,
+                        };
+                        let img = if self.active[button_index] { highlight_active_button(img, image_size) } else { img };
+                        Some((button_index, img))
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to load image for button {}: {}", button_index, e);
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+/// Resolve images for all configured buttons on the current page and send
+/// them to the device right away. Buttons that navigate to the current
+/// page are highlighted.
+///
+/// Buttons that had an image from the previous sync but have none now are
+/// cleared, so a sparse page doesn't keep showing the last page's icons.
+///
+/// With `device.sync_progress` set, switching to a page whose images mostly
+/// aren't in the encode cache first shows a placeholder on each button that
+/// needs encoding, replaced by the real images once they're encoded.
+fn sync_images_now(state: &State<'_, AppState>, app_handle: &AppHandle) {
+    let page_index = current_page(state);
+    let page = PageImages::load(state, app_handle, page_index);
+    // Redraws of the same page (a toggle, a label edit) never show placeholders
+    let page_changed = state.synced_page.lock().unwrap().replace(page_index) != Some(page_index);
+    let show_progress = page_changed && state.config.lock().unwrap().device.sync_progress;
+
+    // Only the difference needs clearing; everything else is overwritten or already blank
+    let with_image: HashSet<usize> = (0..BUTTON_COUNT).filter(|&i| page.paths[i].is_some()).collect();
+    let previous = std::mem::replace(&mut *state.synced_buttons.lock().unwrap(), with_image.clone());
+    let mut to_clear: Vec<usize> = previous.difference(&with_image).copied().collect();
+    to_clear.sort_unstable();
+
+    // Send images to the device (and any mirrors), encoding them in one parallel batch
+    for_each_deck(state, |streamdeck| {
+        for &button_index in &to_clear {
+            if button_index < streamdeck.model().button_count {
+                if let Err(e) = streamdeck.clear_button_image(button_index) {
+                    eprintln!("Failed to clear button {}: {}", button_index, e);
+                }
+            }
+        }
+
+        for (button_index, &dim) in page.dims.iter().enumerate() {
+            if page.paths[button_index].is_some() {
+                streamdeck.set_button_dim(button_index, dim);
+            }
+        }
+        let updates = page.open_images(streamdeck);
+        let result = if show_progress {
+            let pending = pending_image(streamdeck.model().image_size);
+            streamdeck.set_button_images_with_pending(&updates, &pending, SYNC_PROGRESS_MIN_IMAGES)
+        } else {
+            streamdeck.set_button_images(&updates)
+        };
+
+        match result {
+            Ok(timing) => println!(
+                "Synced {} images (encode {:?}, write {:?})",
+                timing.images, timing.encode, timing.write
+            ),
+            Err(e) => eprintln!("Failed to sync images: {}", e),
+        }
+    });
+}
+
+/// Encode the pages before and after the current one (navigation wraps
+/// around) into each deck's encode cache, so switching to them only has to
+/// write the images. Off when `device.prerender_adjacent_pages` is unset.
+///
+/// The images are resized and encoded without holding the decks, which are
+/// only locked briefly to read their settings, check and fill their caches.
+fn prerender_adjacent_pages(state: &State<'_, AppState>, app_handle: &AppHandle) {
+    let (current, page_count, enabled) = {
+        let config = state.config.lock().unwrap();
+        (config.shown_page(), config.pages.len(), config.device.prerender_adjacent_pages)
+    };
+    // The transient page isn't part of page navigation, so it has no neighbours
+    if !enabled || page_count < 2 || current == TRANSIENT_PAGE {
+        return;
+    }
+
+    let mut neighbours = vec![(current + 1) % page_count, (current + page_count - 1) % page_count];
+    neighbours.dedup();
+
+    for page_index in neighbours {
+        // The page moved on; its own sync pre-renders its neighbours
+        if current_page(state) != current {
+            return;
+        }
+
+        let page = PageImages::load(state, app_handle, page_index);
+        let mut decks = Vec::new();
+        for_each_deck(state, |streamdeck| decks.push(streamdeck.render_settings()));
+
+        // Decks are visited in the same order each time; if one is added or
+        // removed in between, entries land in the wrong cache and simply never hit
+        for (deck, settings) in decks.into_iter().enumerate() {
+            let images: Vec<(DynamicImage, f32)> = page
+                .open_images_for(settings)
+                .into_iter()
+                .map(|(button_index, img)| (img, page.dims[button_index]))
+                .collect();
+            let mut candidates = cache_candidates(images, settings);
+
+            let mut index = 0;
+            for_each_deck(state, |streamdeck| {
+                if index == deck {
+                    candidates.retain(|candidate| !streamdeck.is_encoded(candidate.key));
+                }
+                index += 1;
+            });
+            if candidates.is_empty() {
+                continue;
+            }
+
+            let to_encode: Vec<(&DynamicImage, f32)> =
+                candidates.iter().map(|candidate| (&candidate.image, candidate.dim)).collect();
+            let encoded: Vec<(u64, Vec<u8>)> = candidates
+                .iter()
+                .zip(encode_button_images(&to_encode, settings.encode))
+                .filter_map(|(candidate, result)| Some((candidate.key, result.ok()?)))
+                .collect();
+
+            let count = encoded.len();
+            let mut encoded = Some(encoded);
+            let mut index = 0;
+            for_each_deck(state, |streamdeck| {
+                if index == deck {
+                    for (key, data) in encoded.take().into_iter().flatten() {
+                        streamdeck.cache_encoded(key, data);
+                    }
+                }
+                index += 1;
+            });
+            if count > 0 {
+                println!("Pre-rendered {} images for page {}", count, page_index);
             }
         }
     }
 }
 
+/// Blank every button and redraw the current page from scratch, skipping the
+/// encode cache, and re-apply the brightness. Fixes a display that's out of
+/// sync (e.g. after sleep/wake).
+pub fn redraw_deck(state: &State<'_, AppState>, app_handle: &AppHandle) {
+    let brightness = state.brightness.load(Ordering::Relaxed);
+    for_each_deck(state, |streamdeck| {
+        if let Err(e) = streamdeck.set_brightness(brightness) {
+            eprintln!("{}", e);
+        }
+        streamdeck.clear_encode_cache();
+        if let Err(e) = streamdeck.clear_all_buttons() {
+            eprintln!("Failed to clear buttons for redraw: {}", e);
+        }
+    });
+
+    sync_images_to_device(state, app_handle);
+}
+
+/// Re-send a single button's image for the current page (e.g. to undo dimming).
+pub fn refresh_button(state: &State<'_, AppState>, app_handle: &AppHandle, button_index: usize) {
+    let page = PageImages::load(state, app_handle, current_page(state));
+    let Some(ref path) = page.paths[button_index] else {
+        return;
+    };
+
+    for_each_deck(state, |streamdeck| {
+        streamdeck.set_button_dim(button_index, page.dims[button_index]);
+        send_button_image(streamdeck, button_index, path, page.active[button_index], page.padding[button_index]);
+    });
+}
+
+/// Redraw the buttons on the shown page that use the image at `url`
+/// (once a background download of it is done).
+pub(crate) fn refresh_url_buttons(app_handle: &AppHandle, url: &str) {
+    let state = app_handle.state::<AppState>();
+    let uses_url = |image: &ButtonImage| matches!(image, ButtonImage::Url { url: image_url, .. } if image_url == url);
+
+    let buttons: Vec<usize> = {
+        let config = state.config.lock().unwrap();
+        let Some(page) = config.page(config.shown_page()) else {
+            return;
+        };
+        page.buttons
+            .iter()
+            .filter(|(_, button)| {
+                button.image.as_ref().is_some_and(uses_url)
+                    || button.toggle_images.as_ref().is_some_and(|images| uses_url(&images.on) || uses_url(&images.off))
+            })
+            .map(|(&index, _)| index)
+            .collect()
+    };
+
+    for button_index in buttons {
+        refresh_button(&state, app_handle, button_index);
+    }
+}
+
+/// Show a line of generated text on a button in place of its image
+/// (e.g. a confirmation prompt). `refresh_button` restores it.
+pub fn show_button_text(state: &State<'_, AppState>, app_handle: &AppHandle, button_index: usize, text: &str) {
+    let Some(path) = generate_text_image(&text.to_string(), app_handle) else {
+        return;
+    };
+    wait_for_sync(state);
+
+    for_each_deck(state, |streamdeck| {
+        send_button_image(streamdeck, button_index, &path, false, None);
+    });
+}
+
+/// Number of segments in a level meter bar
+const METER_SEGMENTS: u32 = 12;
+
+/// Draw a vertical VU-meter bar on a button, lit up to `level` (0.0-1.0).
+///
+/// The level is rounded to whole segments, so a steady level reuses cached encodes.
+pub fn show_level_meter(state: &State<'_, AppState>, button_index: usize, level: f32) {
+    let lit = (level.clamp(0.0, 1.0) * METER_SEGMENTS as f32).round() as u32;
+
+    for_each_deck(state, |streamdeck| {
+        let size = streamdeck.model().image_size;
+        let segment_height = size / METER_SEGMENTS;
+        let margin = size / 4;
+
+        let mut img = RgbImage::new(size, size);
+        for segment in 0..METER_SEGMENTS {
+            let color = match (segment < lit, segment * 100 / METER_SEGMENTS) {
+                (false, _) => Rgb([40, 40, 40]),
+                (true, 0..=59) => Rgb([0, 200, 60]),
+                (true, 60..=84) => Rgb([230, 200, 0]),
+                (true, _) => Rgb([230, 30, 30]),
+            };
+            // Segments fill from the bottom, with a 1px gap between them
+            let bottom = size - segment * segment_height;
+            for y in (bottom - segment_height + 1)..bottom {
+                for x in margin..size - margin {
+                    img.put_pixel(x, y, color);
+                }
+            }
+        }
+
+        if let Err(e) = streamdeck.set_button_image_from_data(button_index, DynamicImage::ImageRgb8(img)) {
+            eprintln!("Failed to draw level meter on button {}: {}", button_index, e);
+        }
+    });
+}
+
+/// Run `f` on the connected deck, then on each mirror deck.
+///
+/// The two locks are never held together.
+fn for_each_deck(state: &State<'_, AppState>, mut f: impl FnMut(&mut StreamDeck)) {
+    if let Some(ref mut streamdeck) = *state.streamdeck.lock().unwrap() {
+        f(streamdeck);
+    }
+    for mirror in state.mirrors.lock().unwrap().iter_mut() {
+        f(mirror);
+    }
+}
+
+/// Load an image file and send it to a button, padding it and highlighting it if `active`.
+fn send_button_image(
+    streamdeck: &mut StreamDeck,
+    button_index: usize,
+    path: &str,
+    active: bool,
+    padding: Option<ImagePadding>,
+) {
+    let image_size = streamdeck.model().image_size;
+    let result = image::open(path)
+        .map_err(|e| format!("Failed to load image: {}", e))
+        .and_then(|img| {
+            let img = streamdeck.apply_padding(img, padding);
+            let img = if active { highlight_active_button(img, image_size) } else { img };
+            streamdeck.set_button_image_from_data(button_index, img)
+        });
+
+    if let Err(e) = result {
+        eprintln!("Failed to set image for button {}: {}", button_index, e);
+    } else {
+        println!("Set image for button {}: {}", button_index, path);
+    }
+}
+
+/// Render a button exactly as it would be sent to the device: resolved
+/// image, padding, active-page highlight and dimming, before rotation and encoding.
+///
+/// `padding` should already have the device default applied.
+pub fn render_button_image(
+    button_config: &ButtonConfig,
+    active: bool,
+    padding: Option<ImagePadding>,
+    image_size: u32,
+    app_handle: &AppHandle,
+) -> Result<DynamicImage, String> {
+    let path = resolve_button_image(button_config, app_handle)
+        .ok_or("Button has no image or label to render")?;
+    let img = image::open(&path).map_err(|e| format!("Failed to load image: {}", e))?;
+
+    let img = match padding {
+        Some(padding) => pad_image(img, padding, image_size),
+        None => img,
+    };
+    let img = if active { highlight_active_button(img, image_size) } else { img };
+    let img = img.resize_to_fill(image_size, image_size, image::imageops::FilterType::Lanczos3);
+
+    Ok(darken(&img, button_config.dim))
+}
+
+/// Brightness multiplier for buttons whose action is cooling down
+const COOLDOWN_DIM_FACTOR: f32 = 0.35;
+
+/// Darken the image currently shown on a button.
+///
+/// Used while a button's action is cooling down; `refresh_button` restores it.
+pub fn dim_button(state: &State<'_, AppState>, button_index: usize) {
+    for_each_deck(state, |streamdeck| {
+        let Some(img) = streamdeck.get_rendered_image(button_index) else {
+            return;
+        };
+
+        let mut dimmed = img.to_rgb8();
+        for pixel in dimmed.pixels_mut() {
+            for channel in pixel.0.iter_mut() {
+                *channel = (*channel as f32 * COOLDOWN_DIM_FACTOR) as u8;
+            }
+        }
+
+        if let Err(e) = streamdeck.set_button_image_from_data(button_index, DynamicImage::ImageRgb8(dimmed)) {
+            eprintln!("Failed to dim button {}: {}", button_index, e);
+        }
+    });
+}
+
+/// Color blended over a button whose action just failed
+const ERROR_TINT: Rgb<u8> = Rgb([220, 0, 0]);
+
+/// Color blended over a button whose background action just succeeded
+const SUCCESS_TINT: Rgb<u8> = Rgb([0, 200, 60]);
+
+/// How much of the tint color shows through (0.0 = none, 1.0 = solid color)
+const FLASH_TINT_STRENGTH: f32 = 0.55;
+
+/// Tint the image currently shown on a button red to flag a failed action.
+///
+/// `refresh_button` restores it.
+pub fn flash_error(state: &State<'_, AppState>, button_index: usize) {
+    tint_button(state, button_index, ERROR_TINT);
+}
+
+/// Tint the image currently shown on a button green to confirm an action
+/// that finished in the background (e.g. a webhook post).
+///
+/// `refresh_button` restores it.
+pub fn flash_success(state: &State<'_, AppState>, button_index: usize) {
+    tint_button(state, button_index, SUCCESS_TINT);
+}
+
+/// Blend `tint` over the image currently shown on a button
+fn tint_button(state: &State<'_, AppState>, button_index: usize, tint: Rgb<u8>) {
+    wait_for_sync(state);
+    for_each_deck(state, |streamdeck| {
+        let Some(img) = streamdeck.get_rendered_image(button_index) else {
+            return;
+        };
+
+        let mut tinted = img.to_rgb8();
+        for pixel in tinted.pixels_mut() {
+            for (channel, tint) in pixel.0.iter_mut().zip(tint.0) {
+                *channel = (*channel as f32 * (1.0 - FLASH_TINT_STRENGTH) + tint as f32 * FLASH_TINT_STRENGTH) as u8;
+            }
+        }
+
+        if let Err(e) = streamdeck.set_button_image_from_data(button_index, DynamicImage::ImageRgb8(tinted)) {
+            eprintln!("Failed to tint button {}: {}", button_index, e);
+        }
+    });
+}
+
+/// Test pattern for checking a deck's image encoding: red, green, blue and
+/// white quadrants (clockwise from the top left). A wrong encoding shows up
+/// as noise, stripes or a blank button instead.
+pub fn encoding_test_pattern(size: u32) -> DynamicImage {
+    let half = size / 2;
+    let pattern = RgbImage::from_fn(size, size, |x, y| match (x < half, y < half) {
+        (true, true) => Rgb([255, 0, 0]),
+        (false, true) => Rgb([0, 255, 0]),
+        (false, false) => Rgb([0, 0, 255]),
+        (true, false) => Rgb([255, 255, 255]),
+    });
+    DynamicImage::ImageRgb8(pattern)
+}
+
+/// Composite the images currently shown on the device into a single grid image.
+///
+/// Buttons are laid out in the same rows/columns as the physical deck.
+/// Buttons that haven't been rendered yet are left black.
+pub fn compose_deck_preview(streamdeck: &StreamDeck) -> RgbImage {
+    let model = streamdeck.model();
+    let mut canvas = RgbImage::new(
+        model.columns as u32 * model.image_size,
+        model.rows as u32 * model.image_size,
+    );
+
+    for i in 0..model.button_count {
+        if let Some(img) = streamdeck.get_rendered_image(i) {
+            let (row, col) = streamdeck.index_to_rc(i);
+            let x = col as u32 * model.image_size;
+            let y = row as u32 * model.image_size;
+            image::imageops::replace(&mut canvas, &img.to_rgb8(), x as i64, y as i64);
+        }
+    }
+
+    canvas
+}
+
+/// Cell size in a layout sheet: the button image plus two caption lines
+const SHEET_CELL_WIDTH: u32 = 136;
+const SHEET_CELL_HEIGHT: u32 = 136;
+/// Space around the button image and between captions
+const SHEET_MARGIN: u32 = 8;
+/// Height of the page title band above the grid
+const SHEET_TITLE_HEIGHT: u32 = 36;
+
+/// Render a page as an annotated grid for documentation: every button's
+/// image with its label and the name of its action underneath, laid out
+/// like `model`'s keys, with the page name as a title.
+pub fn render_layout_sheet(
+    config: &Config,
+    page_index: usize,
+    model: &DeviceModel,
+    app_handle: &AppHandle,
+) -> Result<RgbImage, String> {
+    let page = config
+        .pages
+        .get(page_index)
+        .ok_or_else(|| format!("Page {} out of range ({} pages)", page_index, config.pages.len()))?;
+    let font = load_font(app_handle).ok_or("No font available to draw captions")?;
+
+    let background = Rgb([32u8, 32, 36]);
+    let cell_color = Rgb([48u8, 48, 54]);
+    let label_color = Rgb([255u8, 255, 255]);
+    let action_color = Rgb([170u8, 170, 180]);
+    let title_scale = PxScale::from(22.0);
+    let caption_scale = PxScale::from(14.0);
+
+    let width = model.columns as u32 * SHEET_CELL_WIDTH;
+    let mut sheet = RgbImage::from_pixel(width, SHEET_TITLE_HEIGHT + model.rows as u32 * SHEET_CELL_HEIGHT, background);
+
+    let title = fit_text(&page.name, title_scale, &font, width - 2 * SHEET_MARGIN);
+    draw_text_mut(&mut sheet, label_color, SHEET_MARGIN as i32, SHEET_MARGIN as i32, title_scale, &font, &title);
+
+    for index in 0..model.button_count {
+        let (row, col) = model.index_to_rc(index);
+        let cell_x = col as u32 * SHEET_CELL_WIDTH;
+        let cell_y = SHEET_TITLE_HEIGHT + row as u32 * SHEET_CELL_HEIGHT;
+
+        // Inset each cell by a pixel so the grid lines show
+        for y in cell_y + 1..cell_y + SHEET_CELL_HEIGHT - 1 {
+            for x in cell_x + 1..cell_x + SHEET_CELL_WIDTH - 1 {
+                sheet.put_pixel(x, y, cell_color);
+            }
+        }
+
+        let Some(button) = page.buttons.get(&index) else {
+            continue;
+        };
+
+        let image_x = cell_x + (SHEET_CELL_WIDTH - IMAGE_SIZE) / 2;
+        let image_y = cell_y + SHEET_MARGIN;
+        let active = button.action.target_page(&config.pages) == Some(page_index);
+        let padding = button.padding.or(config.device.padding);
+        match render_button_image(button, active, padding, IMAGE_SIZE, app_handle) {
+            Ok(img) => image::imageops::replace(&mut sheet, &img.to_rgb8(), image_x as i64, image_y as i64),
+            Err(e) => eprintln!("[render_layout_sheet] Button {}: {}", index, e),
+        }
+
+        let captions = [
+            (button.label.as_deref().unwrap_or(""), label_color),
+            (button.action.display_name().unwrap_or(&button.action.action_type), action_color),
+        ];
+        let mut y = image_y + IMAGE_SIZE + SHEET_MARGIN;
+        for (text, color) in captions {
+            let text = fit_text(text, caption_scale, &font, SHEET_CELL_WIDTH - 2 * SHEET_MARGIN);
+            let (text_width, text_height) = text_size(caption_scale, &font, &text);
+            let x = cell_x + (SHEET_CELL_WIDTH.saturating_sub(text_width)) / 2;
+            draw_text_mut(&mut sheet, color, x as i32, y as i32, caption_scale, &font, &text);
+            y += text_height.max(caption_scale.y as u32) + 2;
+        }
+    }
+
+    Ok(sheet)
+}
+
+/// Shorten `text` with "..." until it fits in `max_width` pixels
+fn fit_text(text: &str, scale: PxScale, font: &FontArc, max_width: u32) -> String {
+    if text_size(scale, font, text).0 <= max_width {
+        return text.to_string();
+    }
+
+    let mut chars: Vec<char> = text.chars().collect();
+    while !chars.is_empty() {
+        chars.pop();
+        let shortened = format!("{}...", chars.iter().collect::<String>().trim_end());
+        if text_size(scale, font, &shortened).0 <= max_width {
+            return shortened;
+        }
+    }
+    String::new()
+}
+
 pub fn generate_text_image(label: &String, app_handle: &AppHandle) -> Option<String>{
     println!("[generate_text_image] Starting for label: '{}'", label);
 
@@ -151,73 +972,235 @@ pub fn generate_text_image(label: &String, app_handle: &AppHandle) -> Option<Str
     let mut image = RgbImage::from_pixel(72, 72, Rgb([0u8, 0u8, 0u8]));
     println!("[generate_text_image] Created 72x72 black image");
 
-    //load a font (basic windows font) TODO: SHip app with font for cross platform
-    let font_path = "C:\\Windows\\Fonts\\arial.ttf";
-    println!("[generate_text_image] Attempting to load font from: {}", font_path);
-    let font_data = match std::fs::read(font_path) {
-        Ok(data) => {
-            println!("[generate_text_image] Font loaded, {} bytes", data.len());
-            data
+    let font = load_font(app_handle)?;
+
+    // Set text size and color
+    let scale = PxScale::from(20.0);
+    let white = Rgb([255u8, 255u8, 255u8]);
+
+    // Draw the text (centered-ish)
+    draw_text_mut(&mut image, white, 10, 25, scale, &font, label);
+    println!("[generate_text_image] Text drawn on image");
+
+    // Create filename from label (use hash to ensure uniqueness)
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    let mut hasher = DefaultHasher::new();
+    label.hash(&mut hasher);
+    let hash = hasher.finish();
+    let file_path = cache_file_path(app_handle, &format!("text_{:x}.png", hash))?;
+    println!("[generate_text_image] Will save to: {:?}", file_path);
+
+    // Save the image
+    if let Err(e) = image.save(&file_path) {
+        println!("[generate_text_image] FAILED to save image: {}", e);
+        return None;
+    }
+
+    let result = file_path.to_string_lossy().to_string();
+    println!("[generate_text_image] SUCCESS! Returning: {}", result);
+    Some(result)
+}
+
+/// Whether the button text font has a glyph for every character of `text`.
+///
+/// Emoji presentation selectors and joiners aren't drawn on their own, so
+/// they're not checked. `false` when no font is available at all.
+pub fn font_can_draw(text: &str, app_handle: &AppHandle) -> bool {
+    let Some(font) = load_font(app_handle) else {
+        return false;
+    };
+
+    text.chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, '\u{FE0E}' | '\u{FE0F}' | '\u{200D}'))
+        .all(|c| font.glyph_id(c).0 != 0)
+}
+
+/// Load the button text font in the background, so the first label rendered
+/// after startup doesn't wait on a system font lookup.
+pub fn preload_font(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+    std::thread::spawn(move || {
+        load_font(&app_handle);
+    });
+}
+
+/// Font bundled with the app as a resource, tried first
+const BUNDLED_FONT: &str = "fonts/label.ttf";
+
+/// Font files tried next, before asking the OS for any sans-serif font
+const FALLBACK_FONT_PATHS: &[&str] = &["C:\\Windows\\Fonts\\arial.ttf"];
+
+/// Font used for button text, kept once one has loaded
+static LABEL_FONT: OnceLock<FontArc> = OnceLock::new();
+
+/// Get the font used for rendering text onto buttons.
+///
+/// Tries the bundled font, then the fallback paths, then the system's default
+/// sans-serif. The first font that loads is reused for every later render.
+fn load_font(app_handle: &AppHandle) -> Option<FontArc> {
+    if let Some(font) = LABEL_FONT.get() {
+        return Some(font.clone());
+    }
+
+    let font = bundled_font_path(app_handle)
+        .into_iter()
+        .chain(FALLBACK_FONT_PATHS.iter().map(std::path::PathBuf::from))
+        .find_map(|path| load_font_file(&path))
+        .or_else(load_system_font);
+
+    match font {
+        Some(font) => Some(LABEL_FONT.get_or_init(|| font).clone()),
+        None => {
+            eprintln!("No usable font found - button text can't be drawn");
+            None
+        }
+    }
+}
+
+/// Path of the bundled font, if it's installed (production) or in src-tauri (dev)
+fn bundled_font_path(app_handle: &AppHandle) -> Option<std::path::PathBuf> {
+    app_handle
+        .path()
+        .resolve(BUNDLED_FONT, tauri::path::BaseDirectory::Resource)
+        .ok()
+        .filter(|path| path.exists())
+        .or_else(|| Some(std::path::PathBuf::from(BUNDLED_FONT)).filter(|path| path.exists()))
+}
+
+/// Read and parse a font file
+fn load_font_file(path: &std::path::Path) -> Option<FontArc> {
+    let data = std::fs::read(path).ok()?;
+    match FontArc::try_from_vec(data) {
+        Ok(font) => {
+            println!("[load_font] Using font {}", path.display());
+            Some(font)
         }
         Err(e) => {
-            println!("[generate_text_image] FAILED to load font: {}", e);
-            return None;
+            eprintln!("[load_font] Failed to parse font {}: {}", path.display(), e);
+            None
         }
+    }
+}
+
+/// Ask the OS for its default sans-serif font
+fn load_system_font() -> Option<FontArc> {
+    let handle = SystemSource::new()
+        .select_best_match(&[FamilyName::SansSerif], &Properties::new())
+        .map_err(|e| eprintln!("[load_font] No system sans-serif font: {}", e))
+        .ok()?;
+
+    let (data, index) = match handle {
+        Handle::Path { path, font_index } => (std::fs::read(&path).ok()?, font_index),
+        Handle::Memory { bytes, font_index } => (bytes.to_vec(), font_index),
     };
 
-    let font = match FontArc::try_from_vec(font_data) {
-        Ok(f) => {
-            println!("[generate_text_image] Font parsed successfully");
-            f
+    match FontVec::try_from_vec_and_index(data, index) {
+        Ok(font) => {
+            println!("[load_font] Using the system sans-serif font");
+            Some(FontArc::new(font))
         }
         Err(e) => {
-            println!("[generate_text_image] FAILED to parse font: {}", e);
+            eprintln!("[load_font] Failed to parse the system font: {}", e);
+            None
+        }
+    }
+}
+
+/// Get a path in the app cache directory for a generated image, creating the directory if needed.
+pub(crate) fn cache_file_path(app_handle: &AppHandle, file_name: &str) -> Option<std::path::PathBuf> {
+    let cache_dir = match app_handle.path().app_cache_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            println!("[cache_file_path] FAILED to get cache dir: {}", e);
             return None;
         }
     };
 
-    // Set text size and color
-    let scale = PxScale::from(20.0);
-    let white = Rgb([255u8, 255u8, 255u8]);
+    if let Err(e) = std::fs::create_dir_all(&cache_dir) {
+        println!("[cache_file_path] FAILED to create cache dir: {}", e);
+        return None;
+    }
 
-    // Draw the text (centered-ish)
-    draw_text_mut(&mut image, white, 10, 25, scale, &font, label);
-    println!("[generate_text_image] Text drawn on image");
+    Some(cache_dir.join(file_name))
+}
 
-    // Save to cache directory
-    let cache_dir = match app_handle.path().app_cache_dir() {
-        Ok(dir) => {
-            println!("[generate_text_image] Cache dir: {:?}", dir);
-            dir
-        }
+/// Parse a `#RRGGBB` (or `RRGGBB`) hex color string.
+pub fn parse_hex_color(color: &str) -> Option<Rgb<u8>> {
+    let hex = color.trim().trim_start_matches('#');
+    if hex.len() != 6 {
+        return None;
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Rgb([channel(0)?, channel(2)?, channel(4)?]))
+}
+
+/// Height of the caption band drawn along the bottom of the button
+const LABEL_BAND_HEIGHT: u32 = 20;
+
+/// Opacity of the caption band (0 = invisible, 255 = solid black)
+const LABEL_BAND_ALPHA: u32 = 160;
+
+/// Composite a button's label onto the bottom of its image.
+///
+/// Draws a semi-transparent dark band for legibility and centers the label
+/// text in it, then saves the result to the cache directory.
+///
+/// # Returns
+///
+/// The path of the composited image, or `None` if it couldn't be generated.
+pub fn overlay_label(image_path: &str, label: &str, text_color: Option<&str>, app_handle: &AppHandle) -> Option<String> {
+    let source = match image::open(image_path) {
+        Ok(img) => img,
         Err(e) => {
-            println!("[generate_text_image] FAILED to get cache dir: {}", e);
+            eprintln!("[overlay_label] FAILED to load image {}: {}", image_path, e);
             return None;
         }
     };
 
-    if let Err(e) = std::fs::create_dir_all(&cache_dir) {
-        println!("[generate_text_image] FAILED to create cache dir: {}", e);
-        return None;
+    let mut image = source
+        .resize_to_fill(IMAGE_SIZE, IMAGE_SIZE, image::imageops::FilterType::Lanczos3)
+        .to_rgb8();
+
+    // Darken the bottom band so the text stays readable over busy icons
+    let band_top = IMAGE_SIZE - LABEL_BAND_HEIGHT;
+    for (_, y, pixel) in image.enumerate_pixels_mut() {
+        if y >= band_top {
+            for channel in pixel.0.iter_mut() {
+                *channel = (*channel as u32 * (255 - LABEL_BAND_ALPHA) / 255) as u8;
+            }
+        }
     }
-    println!("[generate_text_image] Cache dir exists/created");
 
-    // Create filename from label (use hash to ensure uniqueness)
+    let font = load_font(app_handle)?;
+    let scale = PxScale::from(14.0);
+    let color = text_color
+        .and_then(parse_hex_color)
+        .unwrap_or(Rgb([255u8, 255u8, 255u8]));
+
+    // Center the label horizontally within the band
+    let (text_width, text_height) = text_size(scale, &font, label);
+    let x = (IMAGE_SIZE.saturating_sub(text_width) / 2) as i32;
+    let y = (band_top + LABEL_BAND_HEIGHT.saturating_sub(text_height) / 2) as i32;
+    draw_text_mut(&mut image, color, x, y, scale, &font, label);
+
+    // Key the cached file on everything that affects the output
     use std::collections::hash_map::DefaultHasher;
     use std::hash::{Hash, Hasher};
     let mut hasher = DefaultHasher::new();
+    image_path.hash(&mut hasher);
     label.hash(&mut hasher);
-    let hash = hasher.finish();
-    let file_path = cache_dir.join(format!("text_{:x}.png", hash));
-    println!("[generate_text_image] Will save to: {:?}", file_path);
+    text_color.hash(&mut hasher);
+    if let Ok(modified) = std::fs::metadata(image_path).and_then(|m| m.modified()) {
+        modified.hash(&mut hasher);
+    }
+    let file_path = cache_file_path(app_handle, &format!("overlay_{:x}.png", hasher.finish()))?;
 
-    // Save the image
     if let Err(e) = image.save(&file_path) {
-        println!("[generate_text_image] FAILED to save image: {}", e);
+        eprintln!("[overlay_label] FAILED to save image: {}", e);
         return None;
     }
 
-    let result = file_path.to_string_lossy().to_string();
-    println!("[generate_text_image] SUCCESS! Returning: {}", result);
-    Some(result)
-}
\ No newline at end of file
+    Some(file_path.to_string_lossy().to_string())
+}
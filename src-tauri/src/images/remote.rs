@@ -0,0 +1,140 @@
+//! Remote Button Images
+//!
+//! Downloads `ButtonImage::Url` images into the app cache directory so the
+//! rest of the image pipeline can treat them as files.
+//!
+//! Each URL is cached as a PNG named after a hash of the URL. A cached copy
+//! younger than its TTL is used as-is. An older one (or none) is downloaded
+//! again in the background, so a slow server never holds up a page sync: the
+//! stale copy, or no image, is shown meanwhile and the buttons using the URL
+//! are redrawn once the download is in. A failed download keeps the stale
+//! copy so a flaky connection doesn't blank the button, and the URL isn't
+//! tried again for `RETRY_AFTER_FAILURE`.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use image::ImageFormat;
+use tauri::AppHandle;
+
+/// How long a downloaded image is used before fetching it again
+pub const DEFAULT_URL_IMAGE_TTL_SECS: u64 = 300;
+
+/// Largest response accepted, so a wrong URL can't fill memory
+const MAX_DOWNLOAD_BYTES: usize = 10 * 1024 * 1024;
+
+/// How long a URL that failed to download is left alone before trying again
+const RETRY_AFTER_FAILURE: Duration = Duration::from_secs(60);
+
+/// URLs being downloaded, so a page synced twice doesn't fetch them twice
+static DOWNLOADING: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(Default::default);
+
+/// When each URL last failed to download, so a dead link isn't requested on every sync
+static FAILED: LazyLock<Mutex<HashMap<String, Instant>>> = LazyLock::new(Default::default);
+
+/// Resolve a remote image to its cached file path, starting a background
+/// download if the cached copy is missing or stale.
+///
+/// Returns the cached copy even if it's stale, or `None` until the first
+/// download is in.
+pub fn resolve_url_image(url: &str, ttl_secs: Option<u64>, app_handle: &AppHandle) -> Option<String> {
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        eprintln!("Ignoring image URL that isn't http(s): {}", url);
+        return None;
+    }
+
+    let path = super::cache_file_path(app_handle, &cache_file_name(url))?;
+    let ttl = Duration::from_secs(ttl_secs.unwrap_or(DEFAULT_URL_IMAGE_TTL_SECS));
+    if is_fresh(&path, ttl) {
+        return Some(path.to_string_lossy().to_string());
+    }
+
+    start_download(url, path.clone(), app_handle);
+
+    // A stale copy beats no image
+    path.exists().then(|| path.to_string_lossy().to_string())
+}
+
+/// Download `url` to `path` on a background thread, then redraw the buttons
+/// showing it. Does nothing if it's already being downloaded or failed recently.
+fn start_download(url: &str, path: PathBuf, app_handle: &AppHandle) {
+    if FAILED.lock().unwrap().get(url).is_some_and(|failed| failed.elapsed() < RETRY_AFTER_FAILURE) {
+        return;
+    }
+    if !DOWNLOADING.lock().unwrap().insert(url.to_string()) {
+        return;
+    }
+
+    let url = url.to_string();
+    let app_handle = app_handle.clone();
+    thread::spawn(move || {
+        let result = download_image(&url, &path);
+        DOWNLOADING.lock().unwrap().remove(&url);
+        match result {
+            Ok(()) => {
+                println!("Downloaded button image: {}", url);
+                FAILED.lock().unwrap().remove(&url);
+                super::refresh_url_buttons(&app_handle, &url);
+            }
+            Err(e) => {
+                eprintln!("Failed to download {} (retrying in {}s): {}", url, RETRY_AFTER_FAILURE.as_secs(), e);
+                FAILED.lock().unwrap().insert(url, Instant::now());
+            }
+        }
+    });
+}
+
+/// Name of the cache file for a URL
+fn cache_file_name(url: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    url.hash(&mut hasher);
+    format!("url_{:016x}.png", hasher.finish())
+}
+
+/// Whether a cached file exists and was written less than `ttl` ago
+fn is_fresh(path: &Path, ttl: Duration) -> bool {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age < ttl)
+}
+
+/// Download an image, check it decodes, and save it as a PNG at `path`.
+fn download_image(url: &str, path: &Path) -> Result<(), String> {
+    // Shares the weather client's timeouts, so a dead server doesn't tie up a thread for long
+    let client = crate::weather::http_client().ok_or("HTTP client unavailable")?;
+    let response = client.get(url).send().map_err(|e| e.to_string())?;
+
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()));
+    }
+
+    // Checked up front when the server says, and enforced while reading when it doesn't
+    let too_large = || format!("image is larger than {} MB", MAX_DOWNLOAD_BYTES / (1024 * 1024));
+    if response.content_length().is_some_and(|length| length > MAX_DOWNLOAD_BYTES as u64) {
+        return Err(too_large());
+    }
+    let mut bytes = Vec::new();
+    response
+        .take(MAX_DOWNLOAD_BYTES as u64 + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| e.to_string())?;
+    if bytes.len() > MAX_DOWNLOAD_BYTES {
+        return Err(too_large());
+    }
+
+    let img = image::load_from_memory(&bytes).map_err(|e| format!("not a supported image: {}", e))?;
+
+    // Written aside and renamed into place, so a sync never reads half a file
+    let temp = path.with_extension("png.tmp");
+    img.save_with_format(&temp, ImageFormat::Png)
+        .map_err(|e| format!("failed to cache image: {}", e))?;
+    std::fs::rename(&temp, path).map_err(|e| format!("failed to cache image: {}", e))
+}
@@ -0,0 +1,162 @@
+//! Synthetic Keyboard Input
+//!
+//! Sends key presses to the OS as if they came from a real keyboard.
+//! Key combinations are written as `+`-separated names, e.g. "ctrl+shift+m".
+//!
+//! Letters, digits and punctuation name a key position on a US QWERTY
+//! keyboard by default, so shortcuts keep working on other layouts.
+
+mod scancodes;
+
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+
+/// How single-character key names are sent
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeyMode {
+    /// Press the key at that character's US QWERTY position, whatever the layout
+    #[default]
+    Physical,
+    /// Type that character, wherever it is on the current layout
+    Character,
+}
+
+impl KeyMode {
+    /// `Character` if `by_char` is set, otherwise `Physical`
+    pub fn from_by_char(by_char: bool) -> Self {
+        if by_char {
+            KeyMode::Character
+        } else {
+            KeyMode::Physical
+        }
+    }
+}
+
+/// A parsed key, ready to press
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ComboKey {
+    /// A named or character key, resolved by enigo
+    Key(Key),
+    /// A platform keycode for a physical key position
+    Raw(u16),
+}
+
+/// Parse a single key name (case-insensitive).
+///
+/// Single characters map to a physical key or to that character, depending
+/// on `mode`; everything else must be a known name.
+pub fn parse_key(name: &str, mode: KeyMode) -> Result<ComboKey, String> {
+    let lower = name.trim().to_ascii_lowercase();
+
+    let key = match lower.as_str() {
+        "ctrl" | "control" => Key::Control,
+        "shift" => Key::Shift,
+        "alt" => Key::Alt,
+        "win" | "meta" | "super" | "cmd" => Key::Meta,
+        "enter" | "return" => Key::Return,
+        "space" => Key::Space,
+        "tab" => Key::Tab,
+        "esc" | "escape" => Key::Escape,
+        "backspace" => Key::Backspace,
+        "delete" | "del" => Key::Delete,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "pageup" => Key::PageUp,
+        "pagedown" => Key::PageDown,
+        "up" => Key::UpArrow,
+        "down" => Key::DownArrow,
+        "left" => Key::LeftArrow,
+        "right" => Key::RightArrow,
+        "capslock" => Key::CapsLock,
+        "f1" => Key::F1,
+        "f2" => Key::F2,
+        "f3" => Key::F3,
+        "f4" => Key::F4,
+        "f5" => Key::F5,
+        "f6" => Key::F6,
+        "f7" => Key::F7,
+        "f8" => Key::F8,
+        "f9" => Key::F9,
+        "f10" => Key::F10,
+        "f11" => Key::F11,
+        "f12" => Key::F12,
+        _ => {
+            let mut chars = lower.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => {
+                    // Characters off the main block (e.g. 'é') can only be typed
+                    let physical = (mode == KeyMode::Physical)
+                        .then(|| scancodes::physical_keycode(c))
+                        .flatten();
+                    return Ok(physical.map_or(ComboKey::Key(Key::Unicode(c)), ComboKey::Raw));
+                }
+                _ => return Err(format!("Unknown key: '{}'", name)),
+            }
+        }
+    };
+
+    Ok(ComboKey::Key(key))
+}
+
+/// Parse a key combination like "ctrl+shift+m" into its keys, in press order.
+pub fn parse_combo(keys: &str, mode: KeyMode) -> Result<Vec<ComboKey>, String> {
+    let combo: Vec<ComboKey> = keys
+        .split('+')
+        .filter(|part| !part.trim().is_empty())
+        .map(|part| parse_key(part, mode))
+        .collect::<Result<_, _>>()?;
+
+    if combo.is_empty() {
+        return Err("No keys given".to_string());
+    }
+    Ok(combo)
+}
+
+/// Create a connection to the OS input system.
+///
+/// Each call gets its own connection, and the press and release of a held key
+/// (`system.key_hold`) come from different ones. By default enigo releases
+/// every key it pressed when the connection is dropped, which would let go of
+/// a held key as soon as the press handler returns, so that's turned off.
+fn connect() -> Result<Enigo, String> {
+    let settings = Settings {
+        release_keys_when_dropped: false,
+        ..Settings::default()
+    };
+    Enigo::new(&settings).map_err(|e| format!("Failed to initialize keyboard input: {}", e))
+}
+
+/// Send one key event
+fn send(enigo: &mut Enigo, key: ComboKey, direction: Direction) -> Result<(), enigo::InputError> {
+    match key {
+        ComboKey::Key(key) => enigo.key(key, direction),
+        ComboKey::Raw(code) => enigo.raw(code, direction),
+    }
+}
+
+/// Press and hold every key in a combination (in order).
+pub fn press_keys(keys: &str, mode: KeyMode) -> Result<(), String> {
+    let combo = parse_combo(keys, mode)?;
+    let mut enigo = connect()?;
+
+    for key in combo {
+        send(&mut enigo, key, Direction::Press).map_err(|e| format!("Failed to press key: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Release every key in a combination (in reverse order).
+pub fn release_keys(keys: &str, mode: KeyMode) -> Result<(), String> {
+    let combo = parse_combo(keys, mode)?;
+    let mut enigo = connect()?;
+
+    for key in combo.into_iter().rev() {
+        send(&mut enigo, key, Direction::Release).map_err(|e| format!("Failed to release key: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Press a combination and release it again (a normal shortcut keystroke).
+pub fn tap_keys(keys: &str, mode: KeyMode) -> Result<(), String> {
+    press_keys(keys, mode)?;
+    release_keys(keys, mode)
+}
@@ -0,0 +1,79 @@
+//! Physical Key Codes
+//!
+//! Maps the characters of a US QWERTY keyboard to the platform code of the
+//! key in that position, so shortcuts like "ctrl+z" press the same physical
+//! key on every layout (the key labelled W on AZERTY, Y on QWERTZ).
+//!
+//! Codes are what enigo's `raw()` expects: set-1 scancodes on Windows, X11
+//! keycodes (evdev code + 8) on Linux and virtual keycodes on macOS.
+
+/// (US QWERTY character, set-1 scancode, macOS virtual keycode)
+const KEY_POSITIONS: &[(char, u16, u16)] = &[
+    ('a', 0x1E, 0x00),
+    ('b', 0x30, 0x0B),
+    ('c', 0x2E, 0x08),
+    ('d', 0x20, 0x02),
+    ('e', 0x12, 0x0E),
+    ('f', 0x21, 0x03),
+    ('g', 0x22, 0x05),
+    ('h', 0x23, 0x04),
+    ('i', 0x17, 0x22),
+    ('j', 0x24, 0x26),
+    ('k', 0x25, 0x28),
+    ('l', 0x26, 0x25),
+    ('m', 0x32, 0x2E),
+    ('n', 0x31, 0x2D),
+    ('o', 0x18, 0x1F),
+    ('p', 0x19, 0x23),
+    ('q', 0x10, 0x0C),
+    ('r', 0x13, 0x0F),
+    ('s', 0x1F, 0x01),
+    ('t', 0x14, 0x11),
+    ('u', 0x16, 0x20),
+    ('v', 0x2F, 0x09),
+    ('w', 0x11, 0x0D),
+    ('x', 0x2D, 0x07),
+    ('y', 0x15, 0x10),
+    ('z', 0x2C, 0x06),
+    ('1', 0x02, 0x12),
+    ('2', 0x03, 0x13),
+    ('3', 0x04, 0x14),
+    ('4', 0x05, 0x15),
+    ('5', 0x06, 0x17),
+    ('6', 0x07, 0x16),
+    ('7', 0x08, 0x1A),
+    ('8', 0x09, 0x1C),
+    ('9', 0x0A, 0x19),
+    ('0', 0x0B, 0x1D),
+    ('-', 0x0C, 0x1B),
+    ('=', 0x0D, 0x18),
+    ('[', 0x1A, 0x21),
+    (']', 0x1B, 0x1E),
+    (';', 0x27, 0x29),
+    ('\'', 0x28, 0x27),
+    ('`', 0x29, 0x32),
+    ('\\', 0x2B, 0x2A),
+    (',', 0x33, 0x2B),
+    ('.', 0x34, 0x2F),
+    ('/', 0x35, 0x2C),
+];
+
+/// X11 keycodes are evdev codes (equal to set-1 scancodes for these keys) plus 8
+const X11_KEYCODE_OFFSET: u16 = 8;
+
+/// Platform code of the key at `c`'s position on a US QWERTY keyboard.
+///
+/// Returns `None` for characters that aren't on the main block of keys.
+pub fn physical_keycode(c: char) -> Option<u16> {
+    let (_, scancode, mac_keycode) = KEY_POSITIONS
+        .iter()
+        .find(|(key, _, _)| *key == c.to_ascii_lowercase())?;
+
+    Some(if cfg!(target_os = "macos") {
+        *mac_keycode
+    } else if cfg!(target_os = "windows") {
+        *scancode
+    } else {
+        scancode + X11_KEYCODE_OFFSET
+    })
+}
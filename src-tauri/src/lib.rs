@@ -5,26 +5,101 @@
 
 mod actions;
 mod audio;
+mod capture;
+mod combos;
 mod commands;
 mod config;
-mod hid;
+pub mod hid;
 mod images;
+mod keyboard;
+mod paths;
+pub mod plugins;
 mod polling;
+mod resume;
+mod scheduler;
+mod scripting;
+mod shortcut;
+mod switcher;
+mod tray;
 mod weather;
+mod webhook;
 
-use std::sync::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::thread::JoinHandle;
+use std::time::Instant;
 use crate::actions::ActionRegistry;
 use crate::config::Config;
+use crate::hid::constants::{DEFAULT_BRIGHTNESS, MAX_BRIGHTNESS};
 use crate::hid::device::StreamDeck;
-use commands::streamdeck::{connect_device, disconnect_device, get_button_images, get_button_state, list_devices};
+use commands::audio::list_audio_devices;
+use commands::config::{
+    apply_preset, describe_button, get_action_icon, list_builtin_icons, list_presets, list_profiles, move_button,
+    replace_config, save_profile, set_auto_home, set_button_image_from_clipboard, set_button_label, set_close_to_tray,
+    set_current_page, set_focus_shortcut, swap_buttons, switch_profile,
+};
+use commands::streamdeck::{
+    add_mirror_device, confirm_encoding, connect_device, disconnect_device, export_button_image, export_layout_image,
+    get_brightness, get_button_images, get_button_state, get_deck_preview, get_io_stats, is_connected,
+    list_all_hid_devices, list_devices, probe_encoding, redraw_deck, remove_mirror_device, set_brightness,
+    set_device_nickname, set_encoding_override, set_image_command, set_mirror_presses, set_preview_quality,
+    set_rotate_images, set_standby_image, validate_image,
+};
 use tauri::Manager;
 
 /// Application state shared across commands
 pub struct AppState {
     /// Currently connected Stream Deck device
     pub streamdeck: Mutex<Option<StreamDeck>>,
+    /// Extra decks that mirror the connected one (same images and page)
+    pub mirrors: Mutex<Vec<StreamDeck>>,
     /// Button/action configuration
     pub config: Mutex<Config>,
+    /// How long each button read in the polling loop waits for input (milliseconds)
+    pub polling_interval_ms: AtomicU64,
+    /// When set, side-effecting actions are logged/emitted instead of executed
+    pub simulate_actions: AtomicBool,
+    /// When set, button presses are ignored except for the unlock action
+    pub locked: AtomicBool,
+    /// Brightness (percent) applied to every deck; the hardware can't be asked for it
+    pub brightness: AtomicU8,
+    /// `meta.confirm` buttons waiting for their second press, by (page, button), with when they were armed
+    pub confirm_armed: Mutex<HashMap<(usize, usize), Instant>>,
+    /// Image syncs requested from the sync worker
+    pub image_sync: images::SyncQueue,
+    /// Buttons given an image by the last page sync, so the next sync can clear
+    /// the ones its page leaves empty
+    pub synced_buttons: Mutex<HashSet<usize>>,
+    /// Page drawn by the last sync, to tell page switches from redraws
+    pub synced_page: Mutex<Option<usize>>,
+    /// Buttons whose action is still running, by (page, button)
+    pub busy_buttons: Mutex<HashMap<(usize, usize), actions::busy::Running>>,
+    /// Work other threads left for the polling loop
+    pub deferred: Mutex<Vec<polling::Deferred>>,
+    /// The running polling loop, if any
+    pub polling_thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+/// Blank every button on the connected deck, if any (used when shutting down).
+///
+/// Locks are taken even if poisoned: a panic elsewhere (say, in the polling
+/// thread) shouldn't turn a clean exit into a second panic that hides it.
+pub(crate) fn clear_deck(app_handle: &tauri::AppHandle) {
+    let state = app_handle.state::<AppState>();
+    let mut guard = state.streamdeck.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(ref mut streamdeck) = *guard {
+        if let Err(e) = streamdeck.clear_all_buttons() {
+            eprintln!("Warning: Failed to clear buttons on exit: {}", e);
+        }
+    }
+    drop(guard);
+
+    for mirror in state.mirrors.lock().unwrap_or_else(PoisonError::into_inner).iter_mut() {
+        if let Err(e) = mirror.clear_all_buttons() {
+            eprintln!("Warning: Failed to clear mirror buttons on exit: {}", e);
+        }
+    }
 }
 
 /// Runs the Tauri application.
@@ -32,31 +107,121 @@ pub struct AppState {
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(shortcut::handle_shortcut)
+                .build(),
+        )
         // Register all Tauri commands that can be invoked from the frontend
         .invoke_handler(tauri::generate_handler![
             list_devices,
+            list_all_hid_devices,
+            set_device_nickname,
             connect_device,
             disconnect_device,
+            is_connected,
+            redraw_deck,
             get_button_state,
             get_button_images,
+            get_deck_preview,
+            set_preview_quality,
+            get_io_stats,
+            export_button_image,
+            export_layout_image,
+            set_standby_image,
+            validate_image,
+            get_brightness,
+            set_brightness,
+            set_encoding_override,
+            probe_encoding,
+            confirm_encoding,
+            set_rotate_images,
+            set_image_command,
+            add_mirror_device,
+            remove_mirror_device,
+            set_mirror_presses,
+            list_presets,
+            apply_preset,
+            replace_config,
+            list_profiles,
+            save_profile,
+            switch_profile,
+            move_button,
+            swap_buttons,
+            set_button_label,
+            set_button_image_from_clipboard,
+            describe_button,
+            get_action_icon,
+            list_builtin_icons,
+            set_current_page,
+            set_auto_home,
+            set_focus_shortcut,
+            set_close_to_tray,
+            list_audio_devices,
         ])
         // Manage application state
         .manage(AppState {
             streamdeck: Mutex::new(None),
+            mirrors: Mutex::new(Vec::new()),
             config: Mutex::new(Config::default()),
+            polling_interval_ms: AtomicU64::new(polling::DEFAULT_POLL_INTERVAL_MS),
+            simulate_actions: AtomicBool::new(false),
+            locked: AtomicBool::new(false),
+            brightness: AtomicU8::new(DEFAULT_BRIGHTNESS),
+            confirm_armed: Mutex::new(HashMap::new()),
+            image_sync: images::SyncQueue::default(),
+            synced_buttons: Mutex::new(HashSet::new()),
+            synced_page: Mutex::new(None),
+            busy_buttons: Mutex::new(HashMap::new()),
+            deferred: Mutex::new(Vec::new()),
+            polling_thread: Mutex::new(None),
+        })
+        // Replace the default config with the saved one and load plugins
+        .setup(|app| {
+            let mut config = config::storage::load(app.handle());
+            if let Err(e) = shortcut::register_focus_shortcut(app.handle(), config.app.focus_shortcut.as_deref()) {
+                eprintln!("{}", e);
+            }
+
+            // Bring back the button states and brightness from last time; the deck
+            // picks them up when it connects
+            let runtime_state = config::runtime::load(app.handle());
+            runtime_state.apply(&mut config);
+            if let Some(brightness) = runtime_state.brightness {
+                app.state::<AppState>().brightness.store(brightness.min(MAX_BRIGHTNESS), Ordering::Relaxed);
+            }
+            *app.state::<AppState>().config.lock().unwrap() = config;
+
+            // Built-in actions plus any loaded from plugins. The registry doesn't need a
+            // Mutex - it's read-only once plugins have registered.
+            let mut registry = ActionRegistry::new();
+            plugins::load_plugins(&mut registry, app.handle());
+            app.manage(registry);
+
+            images::preload_font(app.handle());
+            tray::create(app.handle())?;
+            scheduler::start_scheduler(app.handle().clone());
+            resume::start_resume_watcher(app.handle().clone());
+            Ok(())
         })
-        // Manage action registry separately (it doesn't need a Mutex - it's read-only after init)
-        .manage(ActionRegistry::new())
         // Handle cleanup when app exits
         .on_window_event(|window, event| {
-            if let tauri::WindowEvent::CloseRequested { .. } = event {
-                // Clear Stream Deck buttons when window closes
-                let state = window.state::<AppState>();
-                let mut guard = state.streamdeck.lock().unwrap();
-                if let Some(ref mut streamdeck) = *guard {
-                    if let Err(e) = streamdeck.clear_all_buttons() {
-                        eprintln!("Warning: Failed to clear buttons on exit: {}", e);
-                    }
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                let close_to_tray = window
+                    .state::<AppState>()
+                    .config
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .app
+                    .close_to_tray;
+
+                if close_to_tray {
+                    // Keep running in the tray; the deck stays active
+                    api.prevent_close();
+                    let _ = window.hide();
+                } else {
+                    // Clear Stream Deck buttons when window closes
+                    clear_deck(window.app_handle());
                 }
             }
         })
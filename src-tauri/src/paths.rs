@@ -0,0 +1,134 @@
+//! Path Expansion Module
+//!
+//! Expands `~` and environment variables in paths from the config, so one
+//! profile works across machines and user accounts.
+
+use std::env;
+use std::path::PathBuf;
+
+/// Expand a leading `~` and any `%VAR%`, `$VAR` or `${VAR}` references.
+///
+/// Variables that aren't set are left as written, so the resulting
+/// "file not found" error shows what was missing.
+pub fn expand_path(path: &str) -> PathBuf {
+    PathBuf::from(expand_home(&expand_vars(path)))
+}
+
+/// Replace a leading `~` (alone or followed by a separator) with the home directory.
+fn expand_home(path: &str) -> String {
+    let Some(rest) = path.strip_prefix('~') else {
+        return path.to_string();
+    };
+    if !(rest.is_empty() || rest.starts_with('/') || rest.starts_with('\\')) {
+        // `~user` style paths aren't supported
+        return path.to_string();
+    }
+
+    match env::var("HOME").or_else(|_| env::var("USERPROFILE")) {
+        Ok(home) => format!("{}{}", home, rest),
+        Err(_) => path.to_string(),
+    }
+}
+
+/// Substitute `%VAR%`, `${VAR}` and `$VAR` references.
+fn expand_vars(path: &str) -> String {
+    let mut out = String::with_capacity(path.len());
+    let mut rest = path;
+
+    while let Some(pos) = rest.find(['%', '$']) {
+        out.push_str(&rest[..pos]);
+        let tail = &rest[pos..];
+
+        let (name, written) = if let Some(after) = tail.strip_prefix('%') {
+            match after.find('%') {
+                Some(end) if is_var_name(&after[..end]) => (&after[..end], &tail[..end + 2]),
+                _ => ("", &tail[..1]),
+            }
+        } else if let Some(after) = tail.strip_prefix("${") {
+            match after.find('}') {
+                Some(end) if is_var_name(&after[..end]) => (&after[..end], &tail[..end + 3]),
+                _ => ("", &tail[..1]),
+            }
+        } else {
+            let after = &tail[1..];
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], &tail[..end + 1])
+        };
+
+        match env::var(name) {
+            Ok(value) if !name.is_empty() => out.push_str(&value),
+            _ => out.push_str(written),
+        }
+        rest = &tail[written.len()..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// Whether `name` looks like an environment variable name
+fn is_var_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '(' || c == ')')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests run in parallel, so each one sets its own variables
+
+    #[test]
+    fn expands_percent_vars() {
+        env::set_var("STREAMDECK_TEST_PERCENT", "C:\\Users\\me");
+        assert_eq!(expand_vars("%STREAMDECK_TEST_PERCENT%\\icons"), "C:\\Users\\me\\icons");
+    }
+
+    #[test]
+    fn expands_dollar_vars() {
+        env::set_var("STREAMDECK_TEST_DOLLAR", "/home/me");
+        assert_eq!(expand_vars("$STREAMDECK_TEST_DOLLAR/icons"), "/home/me/icons");
+        assert_eq!(expand_vars("/a/$STREAMDECK_TEST_DOLLAR"), "/a//home/me");
+    }
+
+    #[test]
+    fn expands_braced_vars() {
+        env::set_var("STREAMDECK_TEST_BRACED", "icons");
+        assert_eq!(expand_vars("/data/${STREAMDECK_TEST_BRACED}_old"), "/data/icons_old");
+    }
+
+    #[test]
+    fn leaves_unset_vars_as_written() {
+        env::remove_var("STREAMDECK_TEST_UNSET");
+        let path = "%STREAMDECK_TEST_UNSET%/$STREAMDECK_TEST_UNSET/${STREAMDECK_TEST_UNSET}";
+        assert_eq!(expand_vars(path), path);
+    }
+
+    #[test]
+    fn leaves_unterminated_braces_as_written() {
+        env::set_var("STREAMDECK_TEST_UNTERMINATED", "x");
+        assert_eq!(expand_vars("/a/${STREAMDECK_TEST_UNTERMINATED"), "/a/${STREAMDECK_TEST_UNTERMINATED");
+        assert_eq!(expand_vars("/a/${}"), "/a/${}");
+    }
+
+    #[test]
+    fn leaves_lone_percent_and_dollar_as_written() {
+        env::set_var("STREAMDECK_TEST_LONE", "x");
+        assert_eq!(expand_vars("100% done"), "100% done");
+        assert_eq!(expand_vars("50% of %STREAMDECK_TEST_LONE%"), "50% of x");
+        assert_eq!(expand_vars("costs $5 or $"), "costs $5 or $");
+    }
+
+    #[test]
+    fn expands_leading_tilde_only() {
+        let Ok(home) = env::var("HOME").or_else(|_| env::var("USERPROFILE")) else {
+            return;
+        };
+        assert_eq!(expand_home("~"), home);
+        assert_eq!(expand_home("~/icons"), format!("{}/icons", home));
+        assert_eq!(expand_home("~\\icons"), format!("{}\\icons", home));
+        assert_eq!(expand_home("~user/icons"), "~user/icons");
+        assert_eq!(expand_home("/a/~/icons"), "/a/~/icons");
+    }
+}
@@ -0,0 +1,179 @@
+//! Plugin Loader Module
+//!
+//! Loads extra actions from dynamic libraries (`.dll`, `.so`, `.dylib`) in the
+//! `plugins` folder of the app config directory, once at startup.
+//!
+//! A plugin is a `cdylib` crate that depends on this one and exports three symbols:
+//!
+//! ```ignore
+//! use std::ffi::c_char;
+//! use streamdeck_lib::plugins::{Action, ActionRegistry, AppHandle, PLUGIN_API_VERSION, PLUGIN_BUILD_ID};
+//!
+//! #[no_mangle]
+//! pub static STREAMDECK_PLUGIN_API_VERSION: u32 = PLUGIN_API_VERSION;
+//!
+//! #[no_mangle]
+//! pub extern "C" fn streamdeck_plugin_build_id() -> *const c_char {
+//!     PLUGIN_BUILD_ID.as_ptr().cast()
+//! }
+//!
+//! #[no_mangle]
+//! pub fn streamdeck_plugin_register(registry: &mut ActionRegistry) {
+//!     registry.register("myplugin.hello", hello);
+//!     registry.register_icon("myplugin.hello", "hello.png");
+//! }
+//!
+//! fn hello(_action: &Action, _app: &AppHandle) -> Result<(), String> {
+//!     println!("Hello from a plugin!");
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The entry point uses the Rust ABI, so plugins must be built with the same
+//! compiler version and the same version of this crate as the app. The build
+//! ID records both and is read through the C ABI before the entry point is
+//! called. A plugin that fails to load, reports a different API version or
+//! build ID, or panics while registering is skipped.
+
+use std::ffi::{c_char, CStr};
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use libloading::{Library, Symbol};
+use tauri::Manager;
+
+pub use crate::actions::{ActionHandler, ActionRegistry, ForwardingActionHandler, StatefulActionHandler};
+pub use crate::config::Action;
+pub use tauri::AppHandle;
+
+/// Bumped whenever `ActionRegistry`, the handler signatures or the exported symbols change
+pub const PLUGIN_API_VERSION: u32 = 3;
+
+/// Version of this crate and of the compiler it was built with (nul-terminated
+/// for the C ABI). A plugin exports the value it was compiled with.
+pub const PLUGIN_BUILD_ID: &str =
+    concat!(env!("CARGO_PKG_VERSION"), " / ", env!("STREAMDECK_RUSTC_VERSION"), "\0");
+
+/// Folder in the app config directory that plugins are loaded from
+const PLUGINS_DIR_NAME: &str = "plugins";
+
+/// Exported `u32` holding the API version the plugin was built against
+const VERSION_SYMBOL: &[u8] = b"STREAMDECK_PLUGIN_API_VERSION";
+
+/// Exported `extern "C" fn() -> *const c_char` returning the plugin's `PLUGIN_BUILD_ID`
+const BUILD_ID_SYMBOL: &[u8] = b"streamdeck_plugin_build_id";
+
+/// Exported `fn(&mut ActionRegistry)` that registers the plugin's actions
+const REGISTER_SYMBOL: &[u8] = b"streamdeck_plugin_register";
+
+/// Signature of a plugin's build ID function
+type BuildIdFn = extern "C" fn() -> *const c_char;
+
+/// Signature of a plugin's register function
+type RegisterFn = fn(&mut ActionRegistry);
+
+/// Load every plugin in the plugins folder into `registry`.
+///
+/// Failures are logged per plugin and never stop the app from starting.
+/// Returns the names of the plugins that loaded.
+pub fn load_plugins(registry: &mut ActionRegistry, app_handle: &AppHandle) -> Vec<String> {
+    let dir = match plugins_dir(app_handle) {
+        Ok(dir) => dir,
+        Err(e) => {
+            eprintln!("Skipping plugins: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        // No plugins folder just means no plugins
+        Err(_) => return Vec::new(),
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == std::env::consts::DLL_EXTENSION))
+        .collect();
+    // Load in a stable order so later plugins predictably override earlier ones
+    paths.sort();
+
+    let mut loaded = Vec::new();
+    for path in paths {
+        let name = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        match load_plugin(&path, registry) {
+            Ok(()) => {
+                println!("Loaded plugin '{}'", name);
+                loaded.push(name);
+            }
+            Err(e) => eprintln!("Failed to load plugin '{}': {}", name, e),
+        }
+    }
+
+    // Plugins name their icons relative to the plugins folder
+    registry.resolve_icon_paths(&dir);
+    loaded
+}
+
+/// Path of the plugins folder (it isn't created if missing)
+fn plugins_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    app_handle
+        .path()
+        .app_config_dir()
+        .map(|dir| dir.join(PLUGINS_DIR_NAME))
+        .map_err(|e| format!("Failed to get config dir: {}", e))
+}
+
+/// Open one plugin, check its API version and build ID, and let it register
+/// its actions. If it panics, anything it registered is undone.
+fn load_plugin(path: &Path, registry: &mut ActionRegistry) -> Result<(), String> {
+    // SAFETY: loading a library runs its initialisers; plugins in the user's
+    // config folder are trusted the same way the app itself is.
+    let library = unsafe { Library::new(path) }.map_err(|e| e.to_string())?;
+
+    // SAFETY: the symbol types match the ones documented for plugin authors
+    unsafe {
+        let version: Symbol<*const u32> = library
+            .get(VERSION_SYMBOL)
+            .map_err(|_| "missing STREAMDECK_PLUGIN_API_VERSION".to_string())?;
+        let version = **version;
+        if version != PLUGIN_API_VERSION {
+            return Err(format!(
+                "built for plugin API {}, this app uses {}",
+                version, PLUGIN_API_VERSION
+            ));
+        }
+
+        let build_id: Symbol<BuildIdFn> = library
+            .get(BUILD_ID_SYMBOL)
+            .map_err(|_| "missing streamdeck_plugin_build_id".to_string())?;
+        let build_id = CStr::from_ptr(build_id()).to_string_lossy().to_string();
+        let app_build_id = PLUGIN_BUILD_ID.trim_end_matches('\0');
+        if build_id != app_build_id {
+            return Err(format!("built with {}, this app was built with {}", build_id, app_build_id));
+        }
+
+        let register: Symbol<RegisterFn> = library
+            .get(REGISTER_SYMBOL)
+            .map_err(|_| "missing streamdeck_plugin_register".to_string())?;
+        let before = registry.clone();
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| register(registry))) {
+            // Nothing may point into the library once it's unloaded, including
+            // the panic payload, so it's turned into a message first
+            *registry = before;
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            drop(payload);
+            return Err(format!("panicked while registering: {}", message));
+        }
+    }
+
+    // The registered handlers point into the library, so it has to stay
+    // loaded for the rest of the app's life
+    std::mem::forget(library);
+    Ok(())
+}
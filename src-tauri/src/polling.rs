@@ -3,88 +3,651 @@
 //! Handles the background thread that polls the Stream Deck for button presses
 //! and executes configured actions.
 
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::{self, RecvTimeoutError};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use serde_json::json;
 use tauri::{AppHandle, Emitter, Manager};
 
+use crate::actions::busy::{self, BusyGuard};
 use crate::actions::ActionRegistry;
+use crate::combos::ComboTracker;
+use crate::config::runtime::{self, RuntimeState};
+use crate::config::Action;
 use crate::hid::constants::BUTTON_COUNT;
+use crate::hid::device::InputEvent;
 use crate::AppState;
 
+/// Default time each button read waits for input, in milliseconds.
+///
+/// Presses are handled as soon as they arrive; this only bounds how long the
+/// loop goes without noticing a disconnect or releasing the device lock.
+pub const DEFAULT_POLL_INTERVAL_MS: u64 = 20;
+
+/// Lowest allowed read timeout (anything shorter just pegs a CPU core)
+pub const MIN_POLL_INTERVAL_MS: u64 = 5;
+
+/// Default for `app.action_timeout_ms`: how long the loop waits for a pressed
+/// button's action before handling input again without it
+pub const DEFAULT_ACTION_TIMEOUT_MS: u64 = 2000;
+
+/// How long a button stays tinted red after its action fails
+pub const ERROR_FLASH_DURATION: Duration = Duration::from_secs(2);
+
+/// Longest a single read holds the deck lock while waiting for input
+const READ_SLICE_MS: u64 = 5;
+
+/// What one poll read: the button states, every state passed through since the
+/// last poll (see `take_read_states`) and the dial/touch events
+type ReadInput = ([bool; BUTTON_COUNT], Vec<[bool; BUTTON_COUNT]>, Vec<InputEvent>);
+
+/// Parameter set on actions run by a button press to the index of the button,
+/// for handlers that keep per-button state
+pub const PRESSED_BUTTON_PARAM: &str = "pressed_button";
+
+/// Parameter set alongside `PRESSED_BUTTON_PARAM` to the index of the page the
+/// button was pressed on
+pub const PRESSED_PAGE_PARAM: &str = "pressed_page";
+
+/// The only action that still runs while the deck is locked
+const UNLOCK_ACTION: &str = "meta.toggle_lock";
+
+/// Work handed back to the polling loop by other threads, done on its next pass
+pub enum Deferred {
+    /// A pressed button's action that outlived `action_timeout_ms` has finished
+    Finished { action: Action, result: Result<Option<bool>, String> },
+    /// A press queued while its button was busy (`on_busy: "queue"`), to be
+    /// handled like a fresh press if its page is still shown
+    Press { page: usize, button: usize },
+}
+
+/// What the polling loop keeps track of for each button between passes
+struct ButtonSlots {
+    /// Hold-type actions started by a press, waiting for their button to be released
+    held_actions: Vec<Option<Action>>,
+    /// When each button's cooldown ends, and whether it was dimmed for it
+    cooldown_until: [Option<Instant>; BUTTON_COUNT],
+    dimmed: [bool; BUTTON_COUNT],
+    /// When each button's error tint should be cleared
+    error_until: [Option<Instant>; BUTTON_COUNT],
+}
+
+impl ButtonSlots {
+    fn new() -> Self {
+        Self {
+            held_actions: vec![None; BUTTON_COUNT],
+            cooldown_until: [None; BUTTON_COUNT],
+            dimmed: [false; BUTTON_COUNT],
+            error_until: [None; BUTTON_COUNT],
+        }
+    }
+}
+
 /// Start the button polling loop in a background thread.
 ///
 /// This spawns a thread that continuously reads button states from the Stream Deck,
 /// detects button presses (rising edges), and executes the configured actions.
+/// The thread is kept in `AppState.polling_thread` so it can be waited for.
 pub fn start_polling(app_handle: AppHandle) {
-    thread::spawn(move || {
-        polling_loop(app_handle);
+    let thread = thread::spawn({
+        let app_handle = app_handle.clone();
+        move || polling_loop(app_handle)
     });
+    *app_handle.state::<AppState>().polling_thread.lock().unwrap() = Some(thread);
 }
 
 /// The main polling loop.
 ///
 /// Runs until the Stream Deck is disconnected (state.streamdeck becomes None).
 fn polling_loop(app_handle: AppHandle) {
-    let mut prev_states = [false; BUTTON_COUNT];
+    // Keys held down while connecting aren't presses, only what changes after
+    let mut prev_states = app_handle
+        .state::<AppState>()
+        .streamdeck
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map_or([false; BUTTON_COUNT], |streamdeck| *streamdeck.get_button_states());
+    let mut slots = ButtonSlots::new();
+    let mut combo_tracker = ComboTracker::new(prev_states);
+    // Last input (or page change) for returning to the home page when idle
+    let mut last_activity = Instant::now();
+    let mut last_page = app_handle.state::<AppState>().config.lock().unwrap().shown_page();
 
     loop {
         // Get access to state and registry through the app handle
         let state = app_handle.state::<AppState>();
         let registry = app_handle.state::<ActionRegistry>();
 
-        // Read buttons with lock held, then release before processing
-        let button_result = {
-            let mut streamdeck_guard = state.streamdeck.lock().unwrap();
-            match &mut *streamdeck_guard {
-                Some(streamdeck) => streamdeck.read_buttons().ok().copied(),
-                None => None,
-            }
-        };
+        // Read buttons (and any dial/touch input), then process them with the deck lock released.
+        // The read blocks until input arrives or the timeout passes, so there's no sleep below.
+        let read_timeout = state.polling_interval_ms.load(Ordering::Relaxed);
+        let button_result = read_input(&state, read_timeout);
 
-        let Some(buttons) = button_result else {
-            // Device disconnected
+        let Some((buttons, read_states, input_events)) = button_result else {
+            // Device disconnected - don't leave any held keys stuck down
+            for action in slots.held_actions.iter_mut().filter_map(Option::take) {
+                if let Err(e) = registry.execute_release(&action, &app_handle) {
+                    eprintln!("Action error: {}", e);
+                }
+            }
             break;
         };
+        let mut mirror_buttons = [false; BUTTON_COUNT];
+        merge_mirror_presses(&state, &mut mirror_buttons);
+
+        let now = Instant::now();
+        if !input_events.is_empty() || read_states.iter().any(|state| *state != prev_states) {
+            last_activity = now;
+        }
+
+        for event in input_events {
+            handle_input_event(&app_handle, &registry, event);
+        }
+
+        let deferred = std::mem::take(&mut *state.deferred.lock().unwrap());
+        for item in deferred {
+            match item {
+                Deferred::Finished { action, result } => {
+                    let Some((_, i)) = pressed_button(&action) else {
+                        continue;
+                    };
+                    if finish_press(&app_handle, action, result, prev_states[i], &mut slots.held_actions[i]) {
+                        slots.error_until[i] = Some(now + ERROR_FLASH_DURATION);
+                    }
+                }
+                Deferred::Press { page, button } => {
+                    if state.config.lock().unwrap().shown_page() != page {
+                        println!("Button {} - dropping queued press, page {} is no longer shown", button, page);
+                        continue;
+                    }
+                    println!("Button {} - running queued press", button);
+                    press_button(&app_handle, &mut slots, button, prev_states[button], now);
+                }
+            }
+        }
 
-        // Process button presses (no streamdeck lock held)
+        // Restore buttons whose cooldown or error tint has ended
         for i in 0..BUTTON_COUNT {
-            if buttons[i] && !prev_states[i] {
-                // Button i was just pressed - look up its action
-                let config_guard = state.config.lock().unwrap();
-
-                if let Some(page) = config_guard.pages.get(config_guard.current_page) {
-                    if let Some(button_config) = page.buttons.get(&i) {
-                        println!(
-                            "Button {} pressed - executing: {}",
-                            i, button_config.action.action_type
-                        );
-
-                        // Clone the action so we can release the config lock
-                        let action = button_config.action.clone();
-                        drop(config_guard);
-
-                        // Execute the action via registry
-                        if let Err(e) = registry.execute(&action, &app_handle) {
+            if slots.cooldown_until[i].is_some_and(|until| now >= until) {
+                slots.cooldown_until[i] = None;
+                if slots.dimmed[i] {
+                    slots.dimmed[i] = false;
+                    // An error tint still showing is cleared below instead
+                    if slots.error_until[i].is_none() {
+                        crate::images::refresh_button(&state, &app_handle, i);
+                    }
+                }
+            }
+
+            if slots.error_until[i].is_some_and(|until| now >= until) {
+                slots.error_until[i] = None;
+                crate::images::refresh_button(&state, &app_handle, i);
+                if slots.dimmed[i] {
+                    crate::images::dim_button(&state, i);
+                }
+            }
+        }
+
+        // Step through every state the deck passed through since the last poll,
+        // so a press and release between two polls still runs the action.
+        // Combos are picked out first, hiding their buttons from the presses below.
+        let (combos, combo_timeout_ms) = {
+            let config = state.config.lock().unwrap();
+            (config.combos.clone(), config.app.action_timeout_ms)
+        };
+
+        let steps = if read_states.is_empty() { vec![buttons] } else { read_states };
+        let mut visible_states = Vec::new();
+        for mut raw in steps {
+            for (pressed, mirror_pressed) in raw.iter_mut().zip(mirror_buttons) {
+                *pressed |= mirror_pressed;
+            }
+
+            let (states, combo_actions) = combo_tracker.step(raw, &combos);
+            for action in combo_actions {
+                run_combo_action(&state, &registry, &app_handle, &action, combo_timeout_ms);
+            }
+            visible_states.extend(states);
+        }
+
+        for buttons in visible_states {
+            // Process button presses (no streamdeck lock held)
+            for i in 0..BUTTON_COUNT {
+                if buttons[i] && !prev_states[i] {
+                    press_button(&app_handle, &mut slots, i, true, now);
+                } else if !buttons[i] && prev_states[i] {
+                    // Button i was just released - finish any hold-type action it started
+                    if let Some(action) = slots.held_actions[i].take() {
+                        if let Err(e) = registry.execute_release(&action, &app_handle) {
                             eprintln!("Action error: {}", e);
                         }
                     }
                 }
             }
+
+            // Check if anything changed, then update prev_states and emit events.
+            // Indices are logical (already remapped by read_buttons), so they match
+            // the button config and images regardless of model or image rotation.
+            if buttons != prev_states {
+                for i in 0..BUTTON_COUNT {
+                    if buttons[i] != prev_states[i] {
+                        let event = if buttons[i] { "streamdeck://button-down" } else { "streamdeck://button-up" };
+                        let _ = app_handle.emit(event, json!({ "index": i }));
+                    }
+                }
+                prev_states = buttons;
+
+                // Emit event to frontend
+                let _ = app_handle.emit(
+                    "streamdeck://button-state",
+                    json!({ "buttons": buttons.to_vec() }),
+                );
+            }
+        }
+
+        return_home_when_idle(&app_handle, &mut last_page, &mut last_activity, now);
+    }
+}
+
+/// Wait up to `timeout_ms` for input, holding the deck lock for at most
+/// `READ_SLICE_MS` at a time so image syncs and commands that need the deck
+/// aren't shut out for the whole read.
+///
+/// Returns `None` once the deck is gone or can't be read.
+fn read_input(state: &AppState, timeout_ms: u64) -> Option<ReadInput> {
+    let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+
+    loop {
+        let slice = deadline.saturating_duration_since(Instant::now()).min(Duration::from_millis(READ_SLICE_MS));
+        {
+            let mut streamdeck_guard = state.streamdeck.lock().unwrap();
+            let streamdeck = streamdeck_guard.as_mut()?;
+            let buttons = *streamdeck.read_buttons_timeout(slice.as_millis() as u64).ok()?;
+            let read_states = streamdeck.take_read_states();
+            let input_events = streamdeck.take_input_events();
+            if !read_states.is_empty() || !input_events.is_empty() || slice.is_zero() {
+                return Some((buttons, read_states, input_events));
+            }
         }
 
-        // Check if anything changed, then update prev_states and emit event
-        if buttons != prev_states {
-            prev_states = buttons;
+        // Give anyone waiting for the deck a chance at the lock before the next slice
+        thread::yield_now();
+    }
+}
+
+/// Switch back to the home page once the deck has sat on another page for
+/// `auto_home_timeout_ms` without input.
+///
+/// Any page change (from the deck, the UI or a script) restarts the timer.
+fn return_home_when_idle(app_handle: &AppHandle, last_page: &mut usize, last_activity: &mut Instant, now: Instant) {
+    let state = app_handle.state::<AppState>();
+    let mut config = state.config.lock().unwrap();
+
+    if config.shown_page() != *last_page {
+        *last_page = config.shown_page();
+        *last_activity = now;
+        return;
+    }
+
+    let Some(timeout_ms) = config.auto_home_timeout_ms else {
+        return;
+    };
+    let home_page = config.home_page;
+    if home_page == config.shown_page()
+        || home_page >= config.pages.len()
+        || now.duration_since(*last_activity) < Duration::from_millis(timeout_ms)
+    {
+        return;
+    }
+
+    config.go_to_page(home_page);
+    println!("Idle for {}ms - returning to home page: {}", timeout_ms, config.pages[home_page].name);
+    drop(config);
+
+    *last_page = home_page;
+    *last_activity = now;
+    crate::images::sync_images_to_device(&state, app_handle);
+    crate::commands::config::emit_page_changed(&state, app_handle);
+}
+
+/// Handle a press of button `i` on the shown page: run its action unless the
+/// deck is locked, the button is cooling down or its action is still running.
+/// `still_down` is false for a queued press whose button has been let go.
+fn press_button(app_handle: &AppHandle, slots: &mut ButtonSlots, i: usize, still_down: bool, now: Instant) {
+    let state = app_handle.state::<AppState>();
+    let registry = app_handle.state::<ActionRegistry>();
+
+    // Look up the button's action, cloning it so the config lock can be released
+    let config_guard = state.config.lock().unwrap();
+    let page_index = config_guard.shown_page();
+    let Some(button_config) = config_guard.page(page_index).and_then(|page| page.buttons.get(&i)) else {
+        return;
+    };
+    println!("Button {} pressed - executing: {}", i, button_config.action.action_type);
 
-            // Emit event to frontend
-            let _ = app_handle.emit(
-                "streamdeck://button-state",
-                json!({ "buttons": buttons.to_vec() }),
+    let mut action = button_config.action.clone();
+    let cooldown = button_config.cooldown_ms.map(Duration::from_millis);
+    let dim_on_cooldown = button_config.dim_on_cooldown;
+    let action_timeout_ms = config_guard.app.action_timeout_ms;
+    drop(config_guard);
+
+    // While locked, only the unlock action is allowed through
+    if state.locked.load(Ordering::Relaxed) && action.action_type != UNLOCK_ACTION {
+        println!("Deck is locked - ignoring button {}", i);
+        return;
+    }
+
+    if slots.cooldown_until[i].is_some() {
+        println!("Button {} is cooling down - ignoring press", i);
+        return;
+    }
+
+    // A still-running action skips or queues the press, depending on its `on_busy`
+    action.params.insert(PRESSED_BUTTON_PARAM.to_string(), json!(i));
+    action.params.insert(PRESSED_PAGE_PARAM.to_string(), json!(page_index));
+    let Some(busy) = busy::begin(app_handle, page_index, i, &action) else {
+        return;
+    };
+
+    if let Some(cooldown) = cooldown {
+        slots.cooldown_until[i] = Some(now + cooldown);
+        if dim_on_cooldown {
+            crate::images::dim_button(&state, i);
+            slots.dimmed[i] = true;
+        }
+    }
+
+    // Execute the action via registry. Hold-type actions are always
+    // waited for, so the release can't overtake the press.
+    let timeout_ms = if registry.has_release_handler(&action.action_type) { 0 } else { action_timeout_ms };
+    let Some(result) = execute_with_timeout(&registry, &action, app_handle, timeout_ms, Some(busy)) else {
+        // Followed up once it's done (see `Deferred::Finished`)
+        return;
+    };
+    if finish_press(app_handle, action, result, still_down, &mut slots.held_actions[i]) {
+        slots.error_until[i] = Some(now + ERROR_FLASH_DURATION);
+    }
+}
+
+/// The (page, button) a button press's action was run for
+fn pressed_button(action: &Action) -> Option<(usize, usize)> {
+    let page = action.get_int_param(PRESSED_PAGE_PARAM)?;
+    let button = action.get_int_param(PRESSED_BUTTON_PARAM)?;
+    Some((page as usize, button as usize))
+}
+
+/// Follow up on a pressed button's action once it has returned: update the
+/// button's toggle or group, keep a hold-type action until the button is
+/// released (or release it now if it already was), or flash the button red.
+///
+/// Returns whether the button was flashed.
+fn finish_press(
+    app_handle: &AppHandle,
+    action: Action,
+    result: Result<Option<bool>, String>,
+    still_down: bool,
+    held_action: &mut Option<Action>,
+) -> bool {
+    let state = app_handle.state::<AppState>();
+    let registry = app_handle.state::<ActionRegistry>();
+    let Some((page_index, i)) = pressed_button(&action) else {
+        return false;
+    };
+
+    match result {
+        Ok(reported) => {
+            let in_group = {
+                let config = state.config.lock().unwrap();
+                config
+                    .page(page_index)
+                    .and_then(|page| page.buttons.get(&i))
+                    .is_some_and(|button| button.group.is_some())
+            };
+            if in_group {
+                // Radio buttons: the pressed one is on, whatever the action reports
+                select_in_group(app_handle, page_index, i);
+            } else if let Some(on) = reported {
+                update_toggle_state(app_handle, page_index, i, on);
+            }
+
+            if registry.has_release_handler(&action.action_type) {
+                if still_down {
+                    *held_action = Some(action);
+                } else if let Err(e) = registry.execute_release(&action, app_handle) {
+                    eprintln!("Action error: {}", e);
+                }
+            }
+            false
+        }
+        Err(e) => {
+            eprintln!("Action error: {}", e);
+            // A late failure is only shown if its page still is
+            if state.config.lock().unwrap().shown_page() != page_index {
+                return false;
+            }
+            crate::images::flash_error(&state, i);
+            true
+        }
+    }
+}
+
+/// Run the action of a combo whose buttons were all pressed.
+fn run_combo_action(state: &AppState, registry: &ActionRegistry, app_handle: &AppHandle, action: &Action, timeout_ms: u64) {
+    if state.locked.load(Ordering::Relaxed) && action.action_type != UNLOCK_ACTION {
+        println!("Deck is locked - ignoring combo");
+        return;
+    }
+
+    println!("Combo pressed - executing: {}", action.action_type);
+    if let Some(Err(e)) = execute_with_timeout(registry, action, app_handle, timeout_ms, None) {
+        eprintln!("Action error: {}", e);
+    }
+}
+
+/// Run a pressed button's action, waiting at most `timeout_ms` for it (0 waits
+/// as long as it takes).
+///
+/// The action runs on its own thread, so a slow one (say, a blocking network
+/// call) can't freeze button input. Past the deadline this returns `None` and
+/// the action finishes in the background. A button press's result is then
+/// handed back to the loop as `Deferred::Finished`; anything else only has its
+/// errors logged. `busy` is released once the action returns.
+fn execute_with_timeout(
+    registry: &ActionRegistry,
+    action: &Action,
+    app_handle: &AppHandle,
+    timeout_ms: u64,
+    busy: Option<BusyGuard>,
+) -> Option<Result<Option<bool>, String>> {
+    if timeout_ms == 0 {
+        let result = registry.execute(action, app_handle);
+        drop(busy);
+        return Some(result);
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    let worker_action = action.clone();
+    let worker_handle = app_handle.clone();
+    thread::spawn(move || {
+        let result = worker_handle.state::<ActionRegistry>().execute(&worker_action, &worker_handle);
+        drop(busy);
+        // The loop stopped waiting, so it has to be told separately
+        let Err(mpsc::SendError(result)) = sender.send(result) else {
+            return;
+        };
+        if pressed_button(&worker_action).is_some() {
+            let state = worker_handle.state::<AppState>();
+            state.deferred.lock().unwrap().push(Deferred::Finished { action: worker_action, result });
+        } else if let Err(e) = result {
+            eprintln!("Action error ({}, after timeout): {}", worker_action.action_type, e);
+        }
+    });
+
+    match receiver.recv_timeout(Duration::from_millis(timeout_ms)) {
+        Ok(result) => Some(result),
+        Err(RecvTimeoutError::Timeout) => {
+            eprintln!(
+                "Warning: action {} is taking longer than {}ms - handling input without waiting for it",
+                action.action_type, timeout_ms
             );
+            None
         }
+        Err(RecvTimeoutError::Disconnected) => Some(Err(format!("Action {} panicked", action.action_type))),
+    }
+}
 
-        thread::sleep(Duration::from_millis(50));
+/// OR the mirror decks' button states into `buttons`, when mirror presses are enabled.
+///
+/// Mirrors that fail to read (e.g. were unplugged) are dropped.
+fn merge_mirror_presses(state: &AppState, buttons: &mut [bool; BUTTON_COUNT]) {
+    if !state.config.lock().unwrap().device.mirror_presses {
+        return;
+    }
+
+    state.mirrors.lock().unwrap().retain_mut(|mirror| match mirror.read_buttons() {
+        Ok(mirror_buttons) => {
+            for (pressed, &mirror_pressed) in buttons.iter_mut().zip(mirror_buttons) {
+                *pressed |= mirror_pressed;
+            }
+            true
+        }
+        Err(e) => {
+            eprintln!("Removing mirror deck: {}", e);
+            false
+        }
+    });
+}
+
+/// Record the state a button's action reported, redrawing the button if it
+/// has toggle images and is still on screen.
+pub(crate) fn update_toggle_state(app_handle: &AppHandle, page_index: usize, button_index: usize, on: bool) {
+    let state = app_handle.state::<AppState>();
+
+    // Refreshed buttons report their state over and over, so nothing is
+    // redrawn or saved unless it changed
+    let (changed, redraw) = {
+        let mut config = state.config.lock().unwrap();
+        let current_page = config.shown_page();
+        match config.page_mut(page_index).and_then(|page| page.buttons.get_mut(&button_index)) {
+            Some(button) => {
+                let changed = button.toggled != Some(on);
+                button.toggled = Some(on);
+                (changed, changed && button.toggle_images.is_some() && page_index == current_page)
+            }
+            None => (false, false),
+        }
+    };
+
+    if redraw {
+        crate::images::refresh_button(&state, app_handle, button_index);
+    }
+    if changed {
+        save_runtime_state(&state, app_handle);
+    }
+}
+
+/// Turn a grouped button on and every other button in its group (on the same
+/// page) off, redrawing the ones with toggle images if the page is on screen.
+pub(crate) fn select_in_group(app_handle: &AppHandle, page_index: usize, button_index: usize) {
+    let state = app_handle.state::<AppState>();
+
+    let mut changed = false;
+    let redraw: Vec<usize> = {
+        let mut config = state.config.lock().unwrap();
+        let current_page = config.shown_page();
+        let Some(page) = config.page_mut(page_index) else {
+            return;
+        };
+        let Some(group) = page.buttons.get(&button_index).and_then(|button| button.group.clone()) else {
+            return;
+        };
+
+        page.buttons
+            .iter_mut()
+            .filter(|(_, button)| button.group.as_ref() == Some(&group))
+            .filter_map(|(&index, button)| {
+                let on = index == button_index;
+                let button_changed = button.toggled != Some(on);
+                button.toggled = Some(on);
+                changed |= button_changed;
+                (button_changed && button.toggle_images.is_some() && page_index == current_page).then_some(index)
+            })
+            .collect()
+    };
+
+    for index in redraw {
+        crate::images::refresh_button(&state, app_handle, index);
+    }
+    if changed {
+        save_runtime_state(&state, app_handle);
+    }
+}
+
+/// Save the button states and brightness for the next launch.
+/// Must be called with the config lock released.
+pub(crate) fn save_runtime_state(state: &AppState, app_handle: &AppHandle) {
+    let runtime_state = {
+        let config = state.config.lock().unwrap();
+        RuntimeState::capture(&config, state.brightness.load(Ordering::Relaxed))
+    };
+    if let Err(e) = runtime::save(&runtime_state, app_handle) {
+        eprintln!("Failed to save button states: {}", e);
+    }
+}
+
+/// Emit a dial/touch event to the frontend and run any action bound to it.
+fn handle_input_event(app_handle: &AppHandle, registry: &ActionRegistry, event: InputEvent) {
+    let state = app_handle.state::<AppState>();
+
+    // Look up the bound action (and how many times to run it) for this event
+    let bound = match &event {
+        InputEvent::DialRotate { dial, delta } => {
+            let _ = app_handle.emit("streamdeck://dial-rotate", event.clone());
+            let config = state.config.lock().unwrap();
+            config
+                .page(config.shown_page())
+                .and_then(|page| page.dials.get(dial))
+                .and_then(|dial_config| {
+                    if *delta > 0 {
+                        dial_config.rotate_clockwise.clone()
+                    } else {
+                        dial_config.rotate_counterclockwise.clone()
+                    }
+                })
+                .map(|action| (action, delta.unsigned_abs() as usize))
+        }
+        InputEvent::DialPress { dial, pressed } => {
+            let _ = app_handle.emit("streamdeck://dial-press", event.clone());
+            let config = state.config.lock().unwrap();
+            config
+                .page(config.shown_page())
+                .and_then(|page| page.dials.get(dial))
+                .and_then(|dial_config| dial_config.press.clone())
+                .filter(|_| *pressed)
+                .map(|action| (action, 1))
+        }
+        InputEvent::Touch { .. } | InputEvent::Swipe { .. } => {
+            let _ = app_handle.emit("streamdeck://touch", event.clone());
+            None
+        }
+    };
+
+    let Some((action, repeat)) = bound else {
+        return;
+    };
+
+    if state.locked.load(Ordering::Relaxed) && action.action_type != UNLOCK_ACTION {
+        println!("Deck is locked - ignoring dial input");
+        return;
+    }
+
+    for _ in 0..repeat {
+        if let Err(e) = registry.execute(&action, app_handle) {
+            eprintln!("Action error: {}", e);
+            break;
+        }
     }
 }
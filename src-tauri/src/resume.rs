@@ -0,0 +1,215 @@
+//! System Resume Module
+//!
+//! Reopens the deck when the computer wakes from sleep, since its HID handle
+//! usually doesn't survive a suspend.
+//!
+//! Resume comes from the OS power events: `WM_POWERBROADCAST` with
+//! `PBT_APMRESUMEAUTOMATIC` on Windows, and logind's `PrepareForSleep` signal
+//! on Linux. Other platforms aren't watched; `meta.redraw` or reconnecting
+//! by hand still works there.
+
+use std::thread;
+use std::time::Duration;
+
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::commands::streamdeck::{start_device, start_mirror, ConnectionStatus};
+use crate::AppState;
+
+/// Reconnect attempts after a resume (USB devices can take a moment to come back)
+const RECONNECT_ATTEMPTS: u32 = 5;
+
+/// Delay between reconnect attempts
+const RECONNECT_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Start listening for system resume in a background thread.
+pub fn start_resume_watcher(app_handle: AppHandle) {
+    thread::spawn(move || {
+        if let Err(e) = platform::watch(app_handle) {
+            eprintln!("Not watching for system resume: {}", e);
+        }
+    });
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::sync::OnceLock;
+
+    use tauri::AppHandle;
+    use windows::core::w;
+    use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+    use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, RegisterClassW, MSG, PBT_APMRESUMEAUTOMATIC,
+        WINDOW_EX_STYLE, WM_POWERBROADCAST, WNDCLASSW, WS_OVERLAPPED,
+    };
+
+    /// The app, for the window procedure (which can't be given any state)
+    static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+    /// Create a hidden window for the power broadcasts and run its message loop.
+    ///
+    /// It has to be a top-level window: message-only windows don't get broadcasts.
+    pub fn watch(app_handle: AppHandle) -> Result<(), String> {
+        let _ = APP_HANDLE.set(app_handle);
+        let class_name = w!("StreamDeckResumeWatcher");
+
+        unsafe {
+            let instance = GetModuleHandleW(None).map_err(|e| format!("Failed to get module handle: {}", e))?;
+            let class = WNDCLASSW {
+                lpfnWndProc: Some(window_proc),
+                hInstance: instance.into(),
+                lpszClassName: class_name,
+                ..Default::default()
+            };
+            if RegisterClassW(&class) == 0 {
+                return Err("Failed to register the power event window class".to_string());
+            }
+
+            // Never shown, so its size and position don't matter
+            CreateWindowExW(
+                WINDOW_EX_STYLE::default(),
+                class_name,
+                w!(""),
+                WS_OVERLAPPED,
+                0,
+                0,
+                0,
+                0,
+                None,
+                None,
+                instance,
+                None,
+            )
+            .map_err(|e| format!("Failed to create the power event window: {}", e))?;
+
+            let mut message = MSG::default();
+            // 0 means WM_QUIT, -1 an error
+            while GetMessageW(&mut message, None, 0, 0).0 > 0 {
+                DispatchMessageW(&message);
+            }
+        }
+        Ok(())
+    }
+
+    unsafe extern "system" fn window_proc(window: HWND, message: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        if message == WM_POWERBROADCAST && wparam.0 as u32 == PBT_APMRESUMEAUTOMATIC {
+            if let Some(app_handle) = APP_HANDLE.get().cloned() {
+                println!("System resumed");
+                // Reconnecting takes a while; don't hold up the message loop
+                std::thread::spawn(move || super::handle_resume(&app_handle));
+            }
+            return LRESULT(1);
+        }
+        DefWindowProcW(window, message, wparam, lparam)
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use tauri::AppHandle;
+    use zbus::blocking::{Connection, Proxy};
+
+    /// Wait for logind's `PrepareForSleep` signals on the system bus.
+    pub fn watch(app_handle: AppHandle) -> Result<(), String> {
+        let connection = Connection::system().map_err(|e| format!("Failed to connect to the system bus: {}", e))?;
+        let login_manager = Proxy::new(
+            &connection,
+            "org.freedesktop.login1",
+            "/org/freedesktop/login1",
+            "org.freedesktop.login1.Manager",
+        )
+        .map_err(|e| format!("Failed to reach logind: {}", e))?;
+        let signals = login_manager
+            .receive_signal("PrepareForSleep")
+            .map_err(|e| format!("Failed to subscribe to logind's sleep signal: {}", e))?;
+
+        for signal in signals {
+            // Sent with `true` before going to sleep and `false` after waking
+            match signal.body().deserialize::<bool>() {
+                Ok(false) => {
+                    println!("System resumed");
+                    super::handle_resume(&app_handle);
+                }
+                Ok(true) => {}
+                Err(e) => eprintln!("Unexpected PrepareForSleep signal: {}", e),
+            }
+        }
+        Err("The system bus connection closed".to_string())
+    }
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+mod platform {
+    use tauri::AppHandle;
+
+    pub fn watch(_app_handle: AppHandle) -> Result<(), String> {
+        Err("system resume is only detected on Windows and Linux".to_string())
+    }
+}
+
+/// Reopen the connected deck (and its mirrors) and redraw the current page.
+///
+/// Emits `streamdeck://resumed` with `{ reconnected }` once done.
+fn handle_resume(app_handle: &AppHandle) {
+    let state = app_handle.state::<AppState>();
+
+    let device_path = state
+        .streamdeck
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|streamdeck| streamdeck.get_device_info().path.clone());
+
+    let Some(device_path) = device_path else {
+        // Nothing was connected, nothing to restore
+        let _ = app_handle.emit("streamdeck://resumed", json!({ "reconnected": false }));
+        return;
+    };
+
+    let mirror_paths: Vec<String> = state
+        .mirrors
+        .lock()
+        .unwrap()
+        .drain(..)
+        .map(|mirror| mirror.get_device_info().path.clone())
+        .collect();
+
+    // Drop the stale handle and wait for the polling loop to notice and exit,
+    // so it's gone before the reconnect starts a new one
+    *state.streamdeck.lock().unwrap() = None;
+    let polling_thread = state.polling_thread.lock().unwrap().take();
+    if let Some(polling_thread) = polling_thread {
+        let _ = polling_thread.join();
+    }
+
+    let mut reconnected = false;
+    for attempt in 1..=RECONNECT_ATTEMPTS {
+        match start_device(&device_path, &state, app_handle.clone()) {
+            Ok(()) => {
+                reconnected = true;
+                break;
+            }
+            Err(e) => {
+                eprintln!("Reconnect attempt {} after resume failed: {}", attempt, e);
+                thread::sleep(RECONNECT_RETRY_DELAY);
+            }
+        }
+    }
+
+    if reconnected {
+        for path in mirror_paths {
+            if let Err(e) = start_mirror(&path, &state, app_handle) {
+                eprintln!("Failed to reopen mirror deck {}: {}", path, e);
+            }
+        }
+    } else {
+        let _ = app_handle.emit(
+            "streamdeck://disconnected",
+            ConnectionStatus { connected: false, serial_number: None },
+        );
+    }
+
+    let _ = app_handle.emit("streamdeck://resumed", json!({ "reconnected": reconnected }));
+}
@@ -0,0 +1,98 @@
+//! Refresh Scheduler Module
+//!
+//! Re-runs the actions of buttons that set `refresh_interval_ms`, so
+//! self-updating buttons (weather and the like) share one background thread
+//! instead of each starting their own.
+
+use std::collections::HashMap;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+use tauri::{AppHandle, Manager};
+
+use crate::actions::ActionRegistry;
+use crate::config::Action;
+use crate::AppState;
+
+/// Parameter set to `true` on actions run by the scheduler, so handlers can
+/// tell a refresh from a button press
+pub const REFRESH_PARAM: &str = "refresh";
+
+/// Shortest allowed refresh interval (milliseconds)
+pub const MIN_REFRESH_INTERVAL_MS: u64 = 1000;
+
+/// How often the scheduler checks for due buttons
+const TICK: Duration = Duration::from_millis(250);
+
+/// Start the refresh scheduler in a background thread.
+///
+/// It runs for the lifetime of the app and only does work while a device is
+/// connected.
+pub fn start_scheduler(app_handle: AppHandle) {
+    thread::spawn(move || {
+        scheduler_loop(app_handle);
+    });
+}
+
+/// The main scheduler loop.
+///
+/// Buttons are refreshed as soon as they appear on screen (connect, page
+/// switch, config change) and then every `refresh_interval_ms`.
+fn scheduler_loop(app_handle: AppHandle) {
+    // When each (page, button) is next due
+    let mut next_due: HashMap<(usize, usize), Instant> = HashMap::new();
+
+    loop {
+        thread::sleep(TICK);
+
+        let state = app_handle.state::<AppState>();
+        let registry = app_handle.state::<ActionRegistry>();
+
+        if state.streamdeck.lock().unwrap().is_none() {
+            next_due.clear();
+            continue;
+        }
+
+        let now = Instant::now();
+        let mut due: Vec<(usize, usize, Action)> = Vec::new();
+        {
+            let config = state.config.lock().unwrap();
+            let page_index = config.shown_page();
+            let mut scheduled = HashMap::new();
+
+            if let Some(page) = config.page(page_index) {
+                for (&button_index, button) in &page.buttons {
+                    let Some(interval_ms) = button.refresh_interval_ms else {
+                        continue;
+                    };
+                    let interval = Duration::from_millis(interval_ms.max(MIN_REFRESH_INTERVAL_MS));
+                    let key = (page_index, button_index);
+
+                    let next = match next_due.get(&key) {
+                        Some(&next) if next > now => next,
+                        _ => {
+                            due.push((page_index, button_index, button.action.clone()));
+                            now + interval
+                        }
+                    };
+                    scheduled.insert(key, next);
+                }
+            }
+
+            // Buttons that left the screen start over when they come back
+            next_due = scheduled;
+        }
+
+        // Execute with the config lock released (handlers may need it)
+        for (page_index, button_index, mut action) in due {
+            action.params.insert(REFRESH_PARAM.to_string(), json!(true));
+
+            match registry.execute(&action, &app_handle) {
+                Ok(Some(on)) => crate::polling::update_toggle_state(&app_handle, page_index, button_index, on),
+                Ok(None) => {}
+                Err(e) => eprintln!("Refresh error: {}", e),
+            }
+        }
+    }
+}
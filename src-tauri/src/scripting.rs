@@ -0,0 +1,122 @@
+//! Scripting Module
+//!
+//! Runs `script.run` actions: small Rhai scripts for custom button logic
+//! without writing a Rust plugin.
+//!
+//! Scripts get a few functions on top of the Rhai language:
+//!
+//! | Function | Does |
+//! |----------|------|
+//! | `set_label(button, text)` | Set a button's label on the current page |
+//! | `set_image(button, path)` | Show an image file on a button on the current page |
+//! | `go_to_page(index)` / `go_to_page(name)` | Switch pages |
+//! | `current_page()` | Index of the current page |
+//! | `weather()` | Current weather text (empty if it can't be fetched) |
+//! | `send_keys(keys)` | Tap a key combination, e.g. `"ctrl+shift+m"` |
+//!
+//! Scripts run on the polling thread, so each one is stopped after a timeout
+//! instead of being allowed to hang the deck.
+
+use std::time::{Duration, Instant};
+
+use rhai::{Engine, EvalAltResult};
+use tauri::{AppHandle, Manager};
+
+use crate::config::{ButtonConfig, ButtonImage, Page};
+use crate::AppState;
+
+/// How long a script may run before it's stopped
+pub const DEFAULT_SCRIPT_TIMEOUT_MS: u64 = 2000;
+
+/// Result type returned by the functions exposed to scripts
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// Run a script, stopping it if it takes longer than `timeout`.
+pub fn run_script(source: &str, timeout: Duration, app_handle: &AppHandle) -> Result<(), String> {
+    let mut engine = create_engine(app_handle);
+
+    let deadline = Instant::now() + timeout;
+    engine.on_progress(move |_| (Instant::now() > deadline).then(|| "timeout".into()));
+
+    engine.run(source).map_err(|e| match *e {
+        EvalAltResult::ErrorTerminated(..) => format!("Script timed out after {} ms", timeout.as_millis()),
+        e => format!("Script error: {}", e),
+    })
+}
+
+/// Create an engine with the deck API registered
+fn create_engine(app_handle: &AppHandle) -> Engine {
+    let mut engine = Engine::new();
+
+    let app = app_handle.clone();
+    engine.register_fn("set_label", move |button: i64, text: &str| -> ScriptResult<()> {
+        edit_button(&app, button, |config| config.label = Some(text.to_string()))
+    });
+
+    let app = app_handle.clone();
+    engine.register_fn("set_image", move |button: i64, path: &str| -> ScriptResult<()> {
+        edit_button(&app, button, |config| {
+            config.image = Some(ButtonImage::File { path: path.to_string() })
+        })
+    });
+
+    let app = app_handle.clone();
+    engine.register_fn("go_to_page", move |index: i64| -> ScriptResult<()> {
+        go_to_page(&app, |pages| usize::try_from(index).ok().filter(|&i| i < pages.len()))
+            .ok_or_else(|| format!("Page index {} out of range", index).into())
+    });
+
+    let app = app_handle.clone();
+    engine.register_fn("go_to_page", move |name: &str| -> ScriptResult<()> {
+        go_to_page(&app, |pages| pages.iter().position(|page| page.name == name))
+            .ok_or_else(|| format!("No page named '{}'", name).into())
+    });
+
+    let app = app_handle.clone();
+    engine.register_fn("current_page", move || -> i64 {
+        app.state::<AppState>().config.lock().unwrap().current_page as i64
+    });
+
+    let app = app_handle.clone();
+    engine.register_fn("weather", move || crate::weather::get_weather(&app, None).unwrap_or_default());
+
+    engine.register_fn("send_keys", |keys: &str| -> ScriptResult<()> {
+        crate::keyboard::tap_keys(keys, crate::keyboard::KeyMode::Physical).map_err(Into::into)
+    });
+
+    engine
+}
+
+/// Apply `f` to a button on the current page and redraw it.
+fn edit_button(app_handle: &AppHandle, button: i64, f: impl FnOnce(&mut ButtonConfig)) -> ScriptResult<()> {
+    let state = app_handle.state::<AppState>();
+    let index = usize::try_from(button).map_err(|_| format!("Invalid button index {}", button))?;
+
+    {
+        let mut config = state.config.lock().unwrap();
+        let shown_page = config.shown_page();
+        let button_config = config
+            .page_mut(shown_page)
+            .and_then(|page| page.buttons.get_mut(&index))
+            .ok_or_else(|| format!("No button at position {}", index))?;
+        f(button_config);
+    }
+
+    crate::images::refresh_button(&state, app_handle, index);
+    Ok(())
+}
+
+/// Switch to the page picked by `find` and redraw the deck.
+/// Returns `None` if no page was picked.
+fn go_to_page(app_handle: &AppHandle, find: impl FnOnce(&[Page]) -> Option<usize>) -> Option<()> {
+    let state = app_handle.state::<AppState>();
+    {
+        let mut config = state.config.lock().unwrap();
+        let index = find(&config.pages)?;
+        config.go_to_page(index);
+    }
+
+    crate::images::sync_images_to_device(&state, app_handle);
+    crate::commands::config::emit_page_changed(&state, app_handle);
+    Some(())
+}
@@ -0,0 +1,65 @@
+//! Global Shortcut Module
+//!
+//! Registers a system-wide hotkey that brings the main window to the front,
+//! for when the deck misbehaves and the window is buried or hidden.
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+/// Default hotkey for focusing the main window
+pub const DEFAULT_FOCUS_SHORTCUT: &str = "CmdOrCtrl+Alt+Shift+D";
+
+/// Global shortcut plugin handler.
+///
+/// The focus shortcut is the only one registered, so any press means "focus".
+pub fn handle_shortcut(app_handle: &AppHandle, _shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() == ShortcutState::Pressed {
+        show_main_window(app_handle);
+    }
+}
+
+/// Show, restore and focus the main window.
+pub fn show_main_window(app_handle: &AppHandle) {
+    let Some(window) = app_handle.get_webview_window("main") else {
+        eprintln!("Main window not found");
+        return;
+    };
+
+    let _ = window.unminimize();
+    let _ = window.show();
+    let _ = window.set_focus();
+}
+
+/// Hide the main window if it's in front, otherwise show and focus it.
+///
+/// A visible window that's behind other windows is brought forward rather
+/// than hidden, since that's what a press meant.
+pub fn toggle_main_window(app_handle: &AppHandle) -> Result<(), String> {
+    let window = app_handle.get_webview_window("main").ok_or("Main window not found")?;
+
+    let visible = window.is_visible().unwrap_or(false) && !window.is_minimized().unwrap_or(false);
+    if visible && window.is_focused().unwrap_or(false) {
+        window.hide().map_err(|e| format!("Failed to hide window: {}", e))
+    } else {
+        show_main_window(app_handle);
+        Ok(())
+    }
+}
+
+/// Replace the focus shortcut (`None` disables it).
+pub fn register_focus_shortcut(app_handle: &AppHandle, shortcut: Option<&str>) -> Result<(), String> {
+    let global_shortcut = app_handle.global_shortcut();
+
+    global_shortcut
+        .unregister_all()
+        .map_err(|e| format!("Failed to unregister shortcut: {}", e))?;
+
+    if let Some(shortcut) = shortcut {
+        global_shortcut
+            .register(shortcut)
+            .map_err(|e| format!("Failed to register shortcut '{}': {}", shortcut, e))?;
+        println!("Focus shortcut: {}", shortcut);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,201 @@
+//! Window Switcher
+//!
+//! Builds a page of the open windows for `system.window_switcher`: one button
+//! per window, showing a thumbnail of it with the app's name, that brings the
+//! window to the front (`system.focus_window`). The last button goes back to
+//! the page the switcher was opened from.
+//!
+//! The page is shown as the config's `transient_page`, so it's never saved
+//! and doesn't take part in page navigation.
+//!
+//! Windows are listed with xcap on every platform, but bringing one to the
+//! front is only implemented on Windows.
+
+use std::collections::HashMap;
+
+use image::imageops::FilterType;
+use serde_json::json;
+use tauri::AppHandle;
+use xcap::Window;
+
+use crate::config::{Action, ButtonImage, Config, Page};
+use crate::hid::constants::IMAGE_SIZE;
+
+/// Name of the generated page, unless the action sets `page_name`
+pub const DEFAULT_SWITCHER_PAGE_NAME: &str = "Windows";
+
+/// A top-level window that can be switched to
+pub struct OpenWindow {
+    pub id: u32,
+    pub app_name: String,
+    pub title: String,
+    minimized: bool,
+    window: Window,
+}
+
+/// List the windows worth switching to: titled and not this app's own.
+pub fn list_windows() -> Result<Vec<OpenWindow>, String> {
+    let own_pid = std::process::id();
+    let windows = Window::all().map_err(|e| format!("Failed to list windows: {}", e))?;
+
+    Ok(windows
+        .into_iter()
+        .filter_map(|window| {
+            let title = window.title().ok()?;
+            if title.trim().is_empty() || window.pid().ok() == Some(own_pid) {
+                return None;
+            }
+            Some(OpenWindow {
+                id: window.id().ok()?,
+                app_name: window.app_name().unwrap_or_default(),
+                title,
+                minimized: window.is_minimized().unwrap_or(false),
+                window,
+            })
+        })
+        .collect())
+}
+
+/// Build the switcher page for a deck with `button_count` keys: up to
+/// `button_count - 1` windows, then the Back button on the last key.
+///
+/// Thumbnails are saved to the app cache directory, replacing the previous
+/// build's; a minimized window, or one that can't be captured, gets a text
+/// button with its name instead.
+pub fn build_page(
+    windows: &[OpenWindow],
+    name: &str,
+    return_page: usize,
+    button_count: usize,
+    app_handle: &AppHandle,
+) -> Result<Page, String> {
+    let back_button = button_count.saturating_sub(1);
+    remove_thumbnails(app_handle);
+    let mut builder = Config::builder().page(name);
+
+    for (index, window) in windows.iter().take(back_button).enumerate() {
+        let label = if window.app_name.is_empty() { &window.title } else { &window.app_name };
+        let action = Action::with_params(
+            "system.focus_window",
+            HashMap::from([
+                ("id".to_string(), json!(window.id)),
+                ("title".to_string(), json!(window.title)),
+                ("return_page".to_string(), json!(return_page)),
+            ]),
+        );
+
+        builder = builder.button(index, action).label(label);
+        builder = match save_thumbnail(window, app_handle) {
+            Some(path) => builder.image(ButtonImage::File { path }).label_overlay(None),
+            None => builder.image(ButtonImage::Text {
+                text: label.clone(),
+                background_color: None,
+                text_color: None,
+            }),
+        };
+    }
+
+    let back = Action::with_params("navigation.go_to_page", HashMap::from([("page".to_string(), json!(return_page))]));
+    let config = builder.button(back_button, back).label("Back").build()?;
+    Ok(config.pages.into_iter().next().expect("builder made one page"))
+}
+
+/// Capture a window and save it as a button-sized PNG, returning the path
+fn save_thumbnail(window: &OpenWindow, app_handle: &AppHandle) -> Option<String> {
+    if window.minimized {
+        return None;
+    }
+    let capture = match window.window.capture_image() {
+        Ok(capture) => capture,
+        Err(e) => {
+            eprintln!("Failed to capture window '{}': {}", window.title, e);
+            return None;
+        }
+    };
+
+    let thumbnail = image::DynamicImage::ImageRgba8(capture).resize(IMAGE_SIZE, IMAGE_SIZE, FilterType::Triangle);
+    let path = crate::images::cache_file_path(app_handle, &format!("window_{}.png", window.id))?;
+    if let Err(e) = thumbnail.save(&path) {
+        eprintln!("Failed to save thumbnail of '{}': {}", window.title, e);
+        return None;
+    }
+    Some(path.to_string_lossy().into_owned())
+}
+
+/// Delete the thumbnails saved by earlier builds, whose windows may be long gone
+fn remove_thumbnails(app_handle: &AppHandle) {
+    // An empty file name gives the cache directory itself
+    let Some(cache_dir) = crate::images::cache_file_path(app_handle, "") else {
+        return;
+    };
+    let Ok(entries) = std::fs::read_dir(&cache_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with("window_") && name.ends_with(".png") {
+            if let Err(e) = std::fs::remove_file(entry.path()) {
+                eprintln!("Failed to remove old thumbnail {}: {}", name, e);
+            }
+        }
+    }
+}
+
+/// Bring a window (by its id from `list_windows`) to the front, restoring it if minimized.
+pub fn focus_window(id: u32) -> Result<(), String> {
+    platform::focus_window(id)
+}
+
+#[cfg(windows)]
+mod platform {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::System::Threading::{AttachThreadInput, GetCurrentThreadId};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        AllowSetForegroundWindow, BringWindowToTop, GetForegroundWindow, GetWindowThreadProcessId, IsIconic, IsWindow,
+        SetForegroundWindow, ShowWindow, ASFW_ANY, SW_RESTORE,
+    };
+
+    pub fn focus_window(id: u32) -> Result<(), String> {
+        // xcap's window id is the HWND
+        let hwnd = HWND(id as usize as *mut core::ffi::c_void);
+
+        unsafe {
+            if !IsWindow(hwnd).as_bool() {
+                return Err("That window has been closed".to_string());
+            }
+            if IsIconic(hwnd).as_bool() {
+                let _ = ShowWindow(hwnd, SW_RESTORE);
+            }
+
+            // Windows only lets the process that owns the foreground window (or
+            // one sharing its input state) change it, and this app normally runs
+            // in the background. Attaching to the foreground thread's input for
+            // the call gets around that.
+            let current_thread = GetCurrentThreadId();
+            let foreground_thread = GetWindowThreadProcessId(GetForegroundWindow(), None);
+            let attached = foreground_thread != 0
+                && foreground_thread != current_thread
+                && AttachThreadInput(current_thread, foreground_thread, true).as_bool();
+
+            let _ = AllowSetForegroundWindow(ASFW_ANY);
+            let _ = BringWindowToTop(hwnd);
+            let focused = SetForegroundWindow(hwnd).as_bool();
+
+            if attached {
+                let _ = AttachThreadInput(current_thread, foreground_thread, false);
+            }
+            if !focused {
+                return Err("Windows refused to bring the window to the front".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    pub fn focus_window(_id: u32) -> Result<(), String> {
+        Err("Switching windows is only supported on Windows".to_string())
+    }
+}
@@ -0,0 +1,88 @@
+//! System Tray Module
+//!
+//! Tray icon with entries to bring the window back, switch profiles, or quit
+//! for real. With `app.close_to_tray` set, closing the window only hides it
+//! and the deck keeps working in the background.
+
+use tauri::menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+const TRAY_ID: &str = "main";
+
+const MENU_SHOW: &str = "show";
+const MENU_QUIT: &str = "quit";
+
+/// Prefix of profile entries' ids; the rest of the id is the profile name
+const MENU_PROFILE_PREFIX: &str = "profile:";
+
+/// Create the tray icon and its menu.
+pub fn create(app_handle: &AppHandle) -> tauri::Result<()> {
+    let menu = build_menu(app_handle)?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("Stream Deck")
+        .menu(&menu)
+        .on_menu_event(|app_handle, event| match event.id.as_ref() {
+            MENU_SHOW => crate::shortcut::show_main_window(app_handle),
+            MENU_QUIT => {
+                // The only path that shuts the deck down when closing to tray
+                crate::clear_deck(app_handle);
+                app_handle.exit(0);
+            }
+            id => {
+                if let Some(name) = id.strip_prefix(MENU_PROFILE_PREFIX) {
+                    let state = app_handle.state::<AppState>();
+                    if let Err(e) = crate::commands::config::switch_to_profile(name, &state, app_handle) {
+                        eprintln!("Failed to switch to profile '{}': {}", name, e.message);
+                        // Put the check mark back on the active profile
+                        refresh_menu(app_handle);
+                    }
+                }
+            }
+        });
+
+    if let Some(icon) = app_handle.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+
+    builder.build(app_handle)?;
+    Ok(())
+}
+
+/// Rebuild the tray menu, e.g. after a profile was saved or switched.
+pub fn refresh_menu(app_handle: &AppHandle) {
+    let Some(tray) = app_handle.tray_by_id(TRAY_ID) else {
+        return;
+    };
+
+    if let Err(e) = build_menu(app_handle).and_then(|menu| tray.set_menu(Some(menu))) {
+        eprintln!("Failed to update tray menu: {}", e);
+    }
+}
+
+/// Menu with a Profiles submenu listing every profile, the active one checked
+fn build_menu(app_handle: &AppHandle) -> tauri::Result<Menu> {
+    let state = app_handle.state::<AppState>();
+    let profiles = crate::commands::config::profile_list(&state, app_handle).unwrap_or_else(|e| {
+        eprintln!("Failed to list profiles: {}", e);
+        Vec::new()
+    });
+
+    let profile_items = profiles
+        .iter()
+        .map(|profile| {
+            let id = format!("{}{}", MENU_PROFILE_PREFIX, profile.name);
+            CheckMenuItem::with_id(app_handle, id, &profile.name, true, profile.active, None::<&str>)
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let profile_refs: Vec<&dyn IsMenuItem> = profile_items.iter().map(|item| item as &dyn IsMenuItem).collect();
+
+    let show = MenuItem::with_id(app_handle, MENU_SHOW, "Show Window", true, None::<&str>)?;
+    let profiles = Submenu::with_items(app_handle, "Profiles", !profile_refs.is_empty(), &profile_refs)?;
+    let separator = PredefinedMenuItem::separator(app_handle)?;
+    let quit = MenuItem::with_id(app_handle, MENU_QUIT, "Quit", true, None::<&str>)?;
+    Menu::with_items(app_handle, &[&show, &profiles, &separator, &quit])
+}
@@ -1,8 +1,37 @@
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use reqwest::blocking::Client;
 use serde::Deserialize;
+use tauri::AppHandle;
 
 // TODO: Make location configurable via action params and persist to disk
 pub const WEATHER_API: &str = "https://wttr.in/28376?format=j1";
 
+/// How long to wait for wttr.in before giving up (connect and read)
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// wttr.in asks clients to identify themselves
+const USER_AGENT: &str = concat!("streamdeck/", env!("CARGO_PKG_VERSION"));
+
+/// Shared HTTP client, built once so connections are reused between refreshes
+static HTTP_CLIENT: OnceLock<Option<Client>> = OnceLock::new();
+
+/// Get the shared HTTP client (with timeouts and User-Agent set).
+pub fn http_client() -> Option<&'static Client> {
+    HTTP_CLIENT
+        .get_or_init(|| {
+            Client::builder()
+                .connect_timeout(REQUEST_TIMEOUT)
+                .timeout(REQUEST_TIMEOUT)
+                .user_agent(USER_AGENT)
+                .build()
+                .map_err(|e| eprintln!("Failed to build HTTP client: {}", e))
+                .ok()
+        })
+        .as_ref()
+}
+
 #[derive(Deserialize)]
 pub struct WttrResponse {
     current_condition: Vec<CurrentCondition>,
@@ -14,22 +43,61 @@ pub struct CurrentCondition {
     weatherCode: String,
 }
 
-pub fn get_weather() -> Option<String> {
-    let response = reqwest::blocking::get(WEATHER_API).ok()?;
+/// Current conditions, ready to be turned into a button label
+pub struct Weather {
+    temp_f: String,
+    code: String,
+}
+
+impl Weather {
+    /// Label like "72°F ☀️", or "72°F Sunny" without `emoji`
+    pub fn label(&self, emoji: bool) -> String {
+        let condition = if emoji {
+            weather_code_to_emoji(&self.code)
+        } else {
+            weather_code_to_description(&self.code)
+        };
+        format!("{}°F {}", self.temp_f, condition)
+    }
+}
+
+pub fn fetch_weather() -> Option<Weather> {
+    // Times out instead of hanging the weather thread forever
+    let response = http_client()?.get(WEATHER_API).send().ok()?;
 
     if response.status().is_success() {
         let weather: WttrResponse = response.json().ok()?;
         let condition = weather.current_condition.first()?;
 
-        let temp = &condition.temp_F;
-        let emoji = weather_code_to_emoji(&condition.weatherCode);
-
-        Some(format!("{}°F {}", temp, emoji))
+        Some(Weather {
+            temp_f: condition.temp_F.clone(),
+            code: condition.weatherCode.clone(),
+        })
     } else {
         None
     }
 }
 
+/// Fetch the weather as a button label.
+///
+/// `emoji` picks the emoji or the text label; with `None` the emoji is used
+/// only if the button font can draw it, so a missing emoji font shows "Sunny"
+/// instead of empty boxes.
+pub fn get_weather(app_handle: &AppHandle, emoji: Option<bool>) -> Option<String> {
+    let weather = fetch_weather()?;
+    let label = weather.label(true);
+
+    match emoji {
+        Some(true) => Some(label),
+        Some(false) => Some(weather.label(false)),
+        None if crate::images::font_can_draw(&label, app_handle) => Some(label),
+        None => {
+            println!("Button font has no weather emoji - using the text description");
+            Some(weather.label(false))
+        }
+    }
+}
+
 fn weather_code_to_emoji(code: &str) -> &'static str {
     match code {
         // Clear/Sunny
@@ -58,3 +126,22 @@ fn weather_code_to_emoji(code: &str) -> &'static str {
         _ => "🌡️",
     }
 }
+
+/// Text for the conditions `weather_code_to_emoji` shows, for fonts without emoji
+fn weather_code_to_description(code: &str) -> &'static str {
+    match code {
+        "113" => "Sunny",
+        "116" => "Partly cloudy",
+        "119" => "Cloudy",
+        "122" => "Overcast",
+        "143" | "248" | "260" => "Fog",
+        "176" | "263" | "266" | "293" | "296" | "353" => "Showers",
+        "299" | "302" | "305" | "308" | "356" | "359" => "Rain",
+        "185" | "281" | "284" | "311" | "314" => "Freezing rain",
+        "179" | "323" | "326" => "Light snow",
+        "227" | "230" | "329" | "332" | "335" | "338" | "368" | "371" => "Snow",
+        "182" | "317" | "320" | "362" | "365" | "350" | "374" | "377" => "Sleet",
+        "200" | "386" | "389" | "392" | "395" => "Storm",
+        _ => "",
+    }
+}
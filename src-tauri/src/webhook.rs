@@ -0,0 +1,74 @@
+//! Chat Webhooks
+//!
+//! Posts messages to Discord and Slack incoming webhooks, for
+//! `web.post_message` ("I'm live", "BRB" buttons).
+//!
+//! The two services want different JSON bodies, so the platform is detected
+//! from the webhook URL (or given explicitly, e.g. for a proxy).
+
+use reqwest::Url;
+use serde_json::json;
+
+/// Chat service a webhook belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookPlatform {
+    Discord,
+    Slack,
+}
+
+impl WebhookPlatform {
+    /// Parse a platform name ("discord" or "slack", case-insensitive)
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "discord" => Ok(WebhookPlatform::Discord),
+            "slack" => Ok(WebhookPlatform::Slack),
+            _ => Err(format!("Unknown webhook platform: '{}'", name)),
+        }
+    }
+
+    /// Work out the platform from a webhook URL's host
+    pub fn detect(url: &Url) -> Result<Self, String> {
+        let host = url.host_str().unwrap_or_default();
+        let is_host = |domain: &str| host == domain || host.ends_with(&format!(".{}", domain));
+
+        if is_host("discord.com") || is_host("discordapp.com") {
+            Ok(WebhookPlatform::Discord)
+        } else if is_host("hooks.slack.com") {
+            Ok(WebhookPlatform::Slack)
+        } else {
+            Err(format!(
+                "Can't tell which service '{}' belongs to; set 'platform' to \"discord\" or \"slack\"",
+                host
+            ))
+        }
+    }
+
+    /// JSON body that posts `message`
+    fn payload(self, message: &str) -> serde_json::Value {
+        match self {
+            WebhookPlatform::Discord => json!({ "content": message }),
+            WebhookPlatform::Slack => json!({ "text": message }),
+        }
+    }
+}
+
+/// Post a message to a webhook, detecting the platform from the URL unless one is given.
+pub fn post_message(webhook_url: &str, message: &str, platform: Option<WebhookPlatform>) -> Result<(), String> {
+    let url = Url::parse(webhook_url).map_err(|e| format!("Invalid webhook URL: {}", e))?;
+    let platform = match platform {
+        Some(platform) => platform,
+        None => WebhookPlatform::detect(&url)?,
+    };
+
+    let client = crate::weather::http_client().ok_or("HTTP client unavailable")?;
+    let response = client
+        .post(url)
+        .json(&platform.payload(message))
+        .send()
+        .map_err(|e| format!("Failed to post to webhook: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Webhook returned HTTP {}", response.status()));
+    }
+    Ok(())
+}
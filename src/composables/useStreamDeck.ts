@@ -14,18 +14,203 @@ export interface DeviceInfo {
   serial_number: string | null;
   /** USB Vendor ID (should be 0x0fd9 for Elgato) */
   vendor_id: number;
-  /** USB Product ID (0x0060 for Original, 0x0080 for MK.2) */
+  /** USB Product ID (0x0060 for Original, 0x0080 or 0x00a5 for MK.2, 0x0084 for +, 0x009a for Neo) */
   product_id: number;
+  /** False for Elgato devices whose model isn't supported yet */
+  supported: boolean;
+  /** User-chosen name for this deck (e.g. "Left Deck"), if set */
+  nickname: string | null;
+}
+
+/**
+ * Connection status returned by `is_connected` and emitted on connect/disconnect.
+ */
+export interface ConnectionStatus {
+  /** Whether a Stream Deck is currently connected */
+  connected: boolean;
+  /** Serial number of the connected device, if known */
+  serial_number: string | null;
+}
+
+/**
+ * Bundled starter preset returned by `list_presets`.
+ */
+export interface PresetInfo {
+  /** Preset name, passed to `apply_preset` */
+  name: string;
+  /** Short summary of what the preset sets up */
+  description: string;
+}
+
+/**
+ * Saved profile returned by `list_profiles`.
+ */
+export interface ProfileInfo {
+  /** Profile name, passed to `switch_profile` */
+  name: string;
+  /** Whether the current config belongs to this profile */
+  active: boolean;
+}
+
+/**
+ * Summary of what a button does, returned by `describe_button`.
+ */
+export interface ButtonDescription {
+  /** Action type identifier (e.g. "system.launch_app") */
+  action_type: string;
+  /** Human-readable action name (the action type for unknown actions) */
+  display_name: string;
+  /** Parameters as `key: value` pairs, comma separated */
+  params_summary: string;
+  /** Whether the backend has a handler for this action type */
+  known_action: boolean;
+  /** The button's label, if any */
+  label: string | null;
+  /** Absolute path of the button image, if any */
+  image_path: string | null;
+}
+
+/**
+ * Stable error codes returned by commands in `CommandError.code`.
+ */
+export type ErrorCode =
+  | "NO_DEVICE"
+  | "DEVICE_ERROR"
+  | "DEVICE_IN_USE"
+  | "PERMISSION_DENIED"
+  | "INVALID_BUTTON"
+  | "INVALID_PAGE"
+  | "INVALID_IMAGE"
+  | "INVALID_ARGUMENT"
+  | "NOT_FOUND"
+  | "CONFIRMATION_REQUIRED"
+  | "INVALID_CONFIG"
+  | "IO_ERROR";
+
+/**
+ * Error thrown by `invoke()` when a command fails.
+ */
+export interface CommandError {
+  /** Machine-readable code to branch on */
+  code: ErrorCode;
+  /** Human-readable description */
+  message: string;
+  /** Individual problems, e.g. everything wrong with a config passed to `replace_config` */
+  details?: string[];
+}
+
+/**
+ * Check whether a caught value is a `CommandError` from the backend.
+ */
+export function isCommandError(e: unknown): e is CommandError {
+  return typeof e === "object" && e !== null && "code" in e && "message" in e;
+}
+
+/**
+ * Get a displayable message from a caught command error.
+ */
+export function errorMessage(e: unknown, fallback: string): string {
+  if (isCommandError(e) || e instanceof Error) {
+    return e.message;
+  }
+  return fallback;
 }
 
 /**
  * Button event emitted from Rust when button states change.
  */
 export interface ButtonEvent {
-  /** Array of 15 boolean values for each button's press state */
+  /** Array of 15 boolean values for each button's press state, by logical index */
   buttons: boolean[];
 }
 
+/**
+ * Payload of `streamdeck://button-down` / `streamdeck://button-up`.
+ */
+export interface ButtonPressEvent {
+  /** Logical button index (left-to-right, top-to-bottom), the same index used in the config */
+  index: number;
+}
+
+/**
+ * Payload of `streamdeck://page-changed`, emitted whenever the deck switches pages.
+ */
+export interface PageChangedEvent {
+  /** Index of the page now shown */
+  index: number;
+  /** Name of the page now shown */
+  name: string;
+}
+
+/**
+ * Payload of `streamdeck://brightness-changed`.
+ */
+export interface BrightnessChangedEvent {
+  /** New brightness, 0-100 */
+  brightness: number;
+}
+
+/** Button image encoding */
+export type ImageEncoding = "jpeg" | "bmp";
+
+/**
+ * Payload of `streamdeck://encoding-probe`, emitted when the test pattern is drawn on button 0.
+ */
+export interface EncodingProbeEvent {
+  /** Encoding the test pattern was sent with */
+  encoding: ImageEncoding;
+}
+
+/**
+ * Result of checking an image file with `validate_image`.
+ */
+export interface ImageValidation {
+  width: number;
+  height: number;
+  /** Detected format as a file extension (e.g. "png", "jpg") */
+  format: string;
+  /** Why the image may look or perform badly on a button, if anything */
+  warning: string | null;
+}
+
+/**
+ * A built-in icon returned by `list_builtin_icons`.
+ */
+export interface BuiltinIcon {
+  /** Name to use in `{ type: "icon", name }` button images */
+  name: string;
+  /** Absolute path of the icon file (pass through convertFileSrc) */
+  path: string;
+}
+
+/**
+ * Live composite of the deck returned by `get_deck_preview`.
+ */
+export interface DeckPreview {
+  /** Encoded image, base64 */
+  data: string;
+  /** "image/png", or "image/jpeg" when a preview quality is set */
+  mime_type: string;
+  width: number;
+  height: number;
+}
+
+/**
+ * HID traffic and encoding counters returned by `get_io_stats`.
+ */
+export interface IoStats {
+  /** Bytes sent to the device in output and feature reports */
+  bytes_written: number;
+  /** Output and feature reports sent */
+  packets_sent: number;
+  /** Button images encoded */
+  images_encoded: number;
+  /** Button images served from the encode cache instead of being encoded */
+  encode_cache_hits: number;
+  /** Input report reads, including ones that timed out */
+  read_attempts: number;
+}
+
 /**
  * Composable for interacting with Stream Deck devices via Tauri commands.
  *
@@ -48,14 +233,21 @@ export function useStreamDeck() {
   /** Current button images (15 URLs or null for 5x3 grid) */
   const buttonImages = ref<(string | null)[]>(new Array(15).fill(null));
 
-  /** Unlisten function for cleaning up event listener */
-  let unlistenFn: UnlistenFn | null = null;
+  /** Index of the page shown on the deck, once known */
+  const currentPage = ref<number | null>(null);
+
+  /** Deck brightness (0-100), as last set */
+  const brightness = ref<number>(100);
+
+  /** Unlisten functions for cleaning up event listeners */
+  let unlistenFns: UnlistenFn[] = [];
 
   /**
    * Discover and list all connected Stream Deck devices.
+   * Unsupported Elgato models are included so the UI can explain why they can't connect.
    */
   async function listDevices(): Promise<void> {
-    const result = await invoke<DeviceInfo[]>("list_devices");
+    const result = await invoke<DeviceInfo[]>("list_devices", { includeUnsupported: true });
     devices.value = result;
   }
 
@@ -82,6 +274,17 @@ export function useStreamDeck() {
     buttonImages.value = new Array(15).fill(null);
   }
 
+  /**
+   * Ask the backend whether a device is connected, instead of inferring it.
+   */
+  async function checkConnection(): Promise<ConnectionStatus> {
+    const status = await invoke<ConnectionStatus>("is_connected");
+    if (!status.connected) {
+      connectedDevice.value = null;
+    }
+    return status;
+  }
+
   /**
    * Fetch button images for the current page from the backend.
    * Converts file paths to URLs the webview can display.
@@ -97,25 +300,213 @@ export function useStreamDeck() {
   }
 
   /**
-   * Set up listener for button state change events from Rust.
+   * Show a page on the deck. `currentPage` updates from the resulting page-changed event.
+   */
+  async function setCurrentPage(index: number): Promise<void> {
+    await invoke("set_current_page", { index });
+  }
+
+  /**
+   * Return to `homePage` after `timeoutMs` without input on another page (null never returns).
+   */
+  async function setAutoHome(homePage: number, timeoutMs: number | null): Promise<void> {
+    await invoke("set_auto_home", { homePage, timeoutMs });
+  }
+
+  /**
+   * Read the brightness the backend is applying to the deck.
+   */
+  async function fetchBrightness(): Promise<void> {
+    brightness.value = await invoke<number>("get_brightness");
+  }
+
+  /**
+   * Read the connected deck's HID traffic and encoding counters,
+   * optionally zeroing them afterwards.
+   */
+  async function getIoStats(reset = false): Promise<IoStats> {
+    return await invoke<IoStats>("get_io_stats", { reset });
+  }
+
+  /**
+   * Get what the deck is showing as a single image, ready for an `img` src.
+   */
+  async function getDeckPreview(): Promise<string> {
+    const preview = await invoke<DeckPreview>("get_deck_preview");
+    return `data:${preview.mime_type};base64,${preview.data}`;
+  }
+
+  /**
+   * Set the preview's JPEG quality (1-100) and largest width; null for PNG / full size.
+   */
+  async function setPreviewQuality(quality: number | null, maxWidth: number | null): Promise<void> {
+    await invoke("set_preview_quality", { quality, maxWidth });
+  }
+
+  /**
+   * Save an annotated PNG of a page's layout (null for the current page) to `outPath`.
+   */
+  async function exportLayoutImage(page: number | null, outPath: string): Promise<void> {
+    await invoke("export_layout_image", { page, outPath });
+  }
+
+  /**
+   * Draw the encoding test pattern (red, green, blue and white quadrants) on button 0.
+   */
+  async function probeEncoding(): Promise<ImageEncoding> {
+    return await invoke<ImageEncoding>("probe_encoding");
+  }
+
+  /**
+   * Answer the encoding probe. A correct answer remembers the encoding for this deck;
+   * otherwise the other encoding is drawn. Returns the encoding now in use.
+   */
+  async function confirmEncoding(correct: boolean): Promise<ImageEncoding> {
+    return await invoke<ImageEncoding>("confirm_encoding", { correct });
+  }
+
+  /**
+   * Set the deck brightness (0-100). `brightness` updates from the resulting event.
+   */
+  async function setBrightness(value: number): Promise<void> {
+    await invoke("set_brightness", { brightness: Math.round(value) });
+  }
+
+  /**
+   * Set or clear (with null) the nickname of a device, then refresh the list.
+   */
+  async function setDeviceNickname(devicePath: string, nickname: string | null): Promise<void> {
+    await invoke("set_device_nickname", { devicePath, nickname });
+    await listDevices();
+  }
+
+  /**
+   * List the bundled starter presets.
+   */
+  async function listPresets(): Promise<PresetInfo[]> {
+    return await invoke<PresetInfo[]>("list_presets");
+  }
+
+  /**
+   * Replace the current config with a preset, after the user confirms.
+   * Returns false if the user cancelled.
+   */
+  async function applyPreset(name: string): Promise<boolean> {
+    if (!window.confirm(`Replace your current buttons with the "${name}" preset?`)) {
+      return false;
+    }
+    await invoke("apply_preset", { name, confirm: true });
+    await fetchButtonImages();
+    return true;
+  }
+
+  /**
+   * List the built-in icons with displayable URLs, for an icon picker.
+   */
+  async function listBuiltinIcons(): Promise<(BuiltinIcon & { url: string })[]> {
+    const icons = await invoke<BuiltinIcon[]>("list_builtin_icons");
+    return icons.map((icon) => ({ ...icon, url: convertFileSrc(icon.path) }));
+  }
+
+  /**
+   * Use the image on the clipboard as a button's image. Rejects with INVALID_IMAGE if there's none.
+   */
+  async function setButtonImageFromClipboard(page: number, button: number): Promise<void> {
+    await invoke("set_button_image_from_clipboard", { page, button });
+  }
+
+  /**
+   * Replace the whole config at once. Rejects with an INVALID_CONFIG error
+   * (problems in `details`) and keeps the old config if the new one is invalid.
+   */
+  async function replaceConfig(config: Record<string, unknown>): Promise<void> {
+    await invoke("replace_config", { config });
+    await fetchButtonImages();
+  }
+
+  /**
+   * List saved profiles, with the active one marked.
+   */
+  async function listProfiles(): Promise<ProfileInfo[]> {
+    return await invoke<ProfileInfo[]>("list_profiles");
+  }
+
+  /**
+   * Save the current buttons as a profile and make it the active one.
+   */
+  async function saveProfile(name: string): Promise<void> {
+    await invoke("save_profile", { name });
+  }
+
+  /**
+   * Switch to a saved profile. The current one is saved first, and the
+   * page-changed event refreshes the button images.
+   */
+  async function switchProfile(name: string): Promise<void> {
+    await invoke("switch_profile", { name });
+  }
+
+  /**
+   * Check that an image file decodes before assigning it to a button.
+   * Rejects with an INVALID_IMAGE error if it can't be used.
+   */
+  async function validateImage(path: string): Promise<ImageValidation> {
+    return await invoke<ImageValidation>("validate_image", { path });
+  }
+
+  /**
+   * Get a human-readable summary of what a button does.
+   */
+  async function describeButton(page: number, button: number): Promise<ButtonDescription> {
+    return await invoke<ButtonDescription>("describe_button", { page, button });
+  }
+
+  /**
+   * Get the default icon an action type shows (absolute path), or null if it has none.
+   * Pass the result through `convertFileSrc` to display it.
+   */
+  async function getActionIcon(actionType: string): Promise<string | null> {
+    return await invoke<string | null>("get_action_icon", { actionType });
+  }
+
+  /**
+   * Set up listeners for button state and connection events from Rust.
    * Call this in onMounted() and cleanupButtonListener() in onUnmounted().
    */
   async function setupButtonListener(): Promise<void> {
     // TODO: The Rust backend will emit "streamdeck://button-state" events
     // when button states change
-    unlistenFn = await listen<ButtonEvent>("streamdeck://button-state", (event) => {
-      buttonStates.value = event.payload.buttons;
-    });
+    unlistenFns.push(
+      await listen<ButtonEvent>("streamdeck://button-state", (event) => {
+        buttonStates.value = event.payload.buttons;
+      })
+    );
+    unlistenFns.push(
+      await listen<PageChangedEvent>("streamdeck://page-changed", async (event) => {
+        currentPage.value = event.payload.index;
+        await fetchButtonImages();
+      })
+    );
+    unlistenFns.push(
+      await listen<BrightnessChangedEvent>("streamdeck://brightness-changed", (event) => {
+        brightness.value = event.payload.brightness;
+      })
+    );
+    unlistenFns.push(
+      await listen<ConnectionStatus>("streamdeck://disconnected", () => {
+        connectedDevice.value = null;
+        buttonStates.value = new Array(15).fill(false);
+        buttonImages.value = new Array(15).fill(null);
+      })
+    );
   }
 
   /**
-   * Clean up the button state event listener.
+   * Clean up the event listeners.
    */
   function cleanupButtonListener(): void {
-    if (unlistenFn) {
-      unlistenFn();
-      unlistenFn = null;
-    }
+    unlistenFns.forEach((unlisten) => unlisten());
+    unlistenFns = [];
   }
 
   return {
@@ -123,10 +514,35 @@ export function useStreamDeck() {
     connectedDevice,
     buttonStates,
     buttonImages,
+    currentPage,
+    brightness,
     listDevices,
     connect,
     disconnect,
+    checkConnection,
+    setDeviceNickname,
     fetchButtonImages,
+    setCurrentPage,
+    setAutoHome,
+    fetchBrightness,
+    setBrightness,
+    getIoStats,
+    getDeckPreview,
+    setPreviewQuality,
+    exportLayoutImage,
+    probeEncoding,
+    confirmEncoding,
+    listPresets,
+    applyPreset,
+    replaceConfig,
+    listBuiltinIcons,
+    setButtonImageFromClipboard,
+    listProfiles,
+    saveProfile,
+    switchProfile,
+    describeButton,
+    validateImage,
+    getActionIcon,
     setupButtonListener,
     cleanupButtonListener,
   };